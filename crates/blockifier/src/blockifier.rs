@@ -1,3 +1,5 @@
 pub mod block;
+pub mod block_builder;
 pub mod bouncer;
+pub mod mempool;
 pub mod transaction_executor;
@@ -80,7 +80,13 @@ pub fn pre_process_block(
         return Err(StateError::OldBlockHashNotProvided);
     }
 
-    Ok(BlockContext { block_info, chain_info, versioned_constants })
+    Ok(BlockContext {
+        block_info,
+        chain_info,
+        versioned_constants,
+        vm_tracer: None,
+        concurrency_mode: false,
+    })
 }
 
 pub struct BlockNumberHashPair {
@@ -0,0 +1,233 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use starknet_api::core::{ClassHash, ContractAddress};
+use starknet_api::transaction::TransactionHash;
+
+use crate::blockifier::block::{pre_process_block, BlockInfo, BlockNumberHashPair};
+use crate::blockifier::bouncer::{BouncerConfig, BouncerInfo};
+use crate::blockifier::transaction_executor::{
+    TransactionExecutor, TransactionExecutorError, TransactionExecutorResult,
+};
+use crate::context::ChainInfo;
+use crate::state::cached_state::{CachedState, CommitmentStateDiff};
+use crate::state::state_api::{StateReader, StateResult};
+use crate::transaction::objects::{
+    TransactionExecutionInfo, TransactionInfo, TransactionInfoCreator,
+};
+use crate::transaction::transaction_execution::Transaction;
+use crate::versioned_constants::VersionedConstants;
+
+#[cfg(test)]
+#[path = "block_builder_test.rs"]
+pub mod block_builder_test;
+
+/// A source of transactions for the block builder; e.g., a mempool.
+pub trait TransactionProvider {
+    /// Returns up to `n_txs` transactions; an empty batch indicates there are no more
+    /// transactions to include in the block.
+    fn get_txs(&mut self, n_txs: usize) -> Vec<Transaction>;
+}
+
+#[derive(Clone, Debug)]
+pub struct BlockBuilderConfig {
+    /// The number of transactions requested from the provider at a time.
+    pub tx_chunk_size: usize,
+    /// The time, since the beginning of the build, after which the block is closed.
+    pub block_timeout: Duration,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BlockCloseReason {
+    /// The provider has no more transactions.
+    NoMoreTransactions,
+    /// A transaction did not fit into the remaining capacity of the block.
+    BlockFull,
+    /// The block timeout has passed.
+    Deadline,
+}
+
+/// The outputs of building a block.
+#[derive(Debug)]
+pub struct BlockExecutionArtifacts {
+    /// The execution infos of the transactions included in the block, in block order.
+    pub execution_infos: Vec<(TransactionHash, TransactionExecutionInfo)>,
+    /// The transactions that failed execution (e.g., pre-validation), and were left out of the
+    /// block.
+    pub rejected_txs: Vec<(TransactionHash, TransactionExecutorError)>,
+    /// Transactions taken from the provider that were not executed, since the block was closed
+    /// before their turn; these may be included in a later block.
+    pub remaining_txs: Vec<Transaction>,
+    pub close_reason: BlockCloseReason,
+    pub bouncer_weights: BouncerInfo,
+    pub state_diff: CommitmentStateDiff,
+    pub visited_pcs: Vec<(ClassHash, Vec<usize>)>,
+}
+
+/// Builds a block out of the transactions of a `TransactionProvider`, on top of a
+/// `TransactionExecutor`.
+pub struct BlockBuilder<S: StateReader> {
+    pub config: BlockBuilderConfig,
+    executor: TransactionExecutor<S>,
+}
+
+impl<S: StateReader> BlockBuilder<S> {
+    /// Pre-processes the block on top of the given state, and returns a builder for it.
+    pub fn new(
+        mut state: CachedState<S>,
+        old_block_number_and_hash: Option<BlockNumberHashPair>,
+        block_info: BlockInfo,
+        chain_info: ChainInfo,
+        versioned_constants: VersionedConstants,
+        bouncer_config: BouncerConfig,
+        config: BlockBuilderConfig,
+    ) -> StateResult<Self> {
+        let block_context = pre_process_block(
+            &mut state,
+            old_block_number_and_hash,
+            block_info,
+            chain_info,
+            versioned_constants,
+        )?;
+        let executor = TransactionExecutor::new(state, block_context, bouncer_config);
+
+        Ok(Self { config, executor })
+    }
+
+    /// Executes the transactions of the given provider, chunk by chunk, until the provider runs
+    /// out of transactions, the block is full or the block timeout passes; then, finalizes the
+    /// block.
+    /// Transactions that fail execution are skipped; state errors abort the build.
+    pub fn build_block(
+        mut self,
+        tx_provider: &mut impl TransactionProvider,
+    ) -> TransactionExecutorResult<BlockExecutionArtifacts> {
+        let charge_fee = true;
+        // A timeout too large to represent as an instant never expires.
+        let deadline = Instant::now().checked_add(self.config.block_timeout);
+        let is_past_deadline = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        let mut execution_infos = Vec::new();
+        let mut rejected_txs = Vec::new();
+        let mut remaining_txs = Vec::new();
+
+        let close_reason = 'build: loop {
+            if is_past_deadline() {
+                break BlockCloseReason::Deadline;
+            }
+            let txs = tx_provider.get_txs(self.config.tx_chunk_size);
+            if txs.is_empty() {
+                break BlockCloseReason::NoMoreTransactions;
+            }
+
+            let mut txs = order_txs(txs).into_iter();
+            while let Some(tx) = txs.next() {
+                if is_past_deadline() {
+                    remaining_txs.push(tx);
+                    remaining_txs.extend(txs);
+                    break 'build BlockCloseReason::Deadline;
+                }
+
+                let tx_hash = tx.create_tx_info().transaction_hash();
+                match self.executor.execute(tx.clone(), charge_fee) {
+                    Ok((tx_execution_info, _, _)) => {
+                        self.executor.commit();
+                        execution_infos.push((tx_hash, tx_execution_info));
+                    }
+                    Err(TransactionExecutorError::BlockFull) => {
+                        remaining_txs.push(tx);
+                        remaining_txs.extend(txs);
+                        break 'build BlockCloseReason::BlockFull;
+                    }
+                    Err(error @ TransactionExecutorError::StateError(_)) => return Err(error),
+                    Err(error) => rejected_txs.push((tx_hash, error)),
+                }
+            }
+        };
+
+        let is_pending_block = false;
+        let (state_diff, visited_pcs) = self.executor.finalize(is_pending_block);
+
+        Ok(BlockExecutionArtifacts {
+            execution_infos,
+            rejected_txs,
+            remaining_txs,
+            close_reason,
+            bouncer_weights: self.executor.bouncer.accumulated_weights().clone(),
+            state_diff,
+            visited_pcs,
+        })
+    }
+}
+
+/// Orders the given transactions for execution.
+/// L1 handler and (legacy) deploy transactions come first, in arrival order. Account transactions
+/// follow; each account's transactions are executed in nonce order, and the accounts are
+/// interleaved by the priority of their next transaction (ties are broken by arrival order); see
+/// [TxPriority].
+pub fn order_txs(txs: Vec<Transaction>) -> Vec<Transaction> {
+    let mut ordered_txs = Vec::with_capacity(txs.len());
+    let mut account_queues: HashMap<ContractAddress, Vec<(usize, TransactionInfo, Transaction)>> =
+        HashMap::new();
+    for (arrival_index, tx) in txs.into_iter().enumerate() {
+        match tx {
+            Transaction::DeployTransaction(_) | Transaction::L1HandlerTransaction(_) => {
+                ordered_txs.push(tx)
+            }
+            Transaction::AccountTransaction(_) => {
+                let tx_info = tx.create_tx_info();
+                account_queues.entry(tx_info.sender_address()).or_default().push((
+                    arrival_index,
+                    tx_info,
+                    tx,
+                ));
+            }
+        }
+    }
+
+    let mut account_queues: Vec<VecDeque<(usize, TransactionInfo, Transaction)>> = account_queues
+        .into_values()
+        .map(|mut account_txs| {
+            // A stable sort; transactions of the same nonce remain in arrival order.
+            account_txs.sort_by_key(|(_, tx_info, _)| tx_info.nonce());
+            account_txs.into()
+        })
+        .collect();
+
+    // Holds the ordering key of the next transaction of each account.
+    let mut heads = BinaryHeap::new();
+    let head_key = |queue_index: usize,
+                    (arrival_index, tx_info, _): &(usize, TransactionInfo, _)| {
+        (tx_priority(tx_info), Reverse(*arrival_index), queue_index)
+    };
+    for (queue_index, account_txs) in account_queues.iter().enumerate() {
+        heads.extend(account_txs.front().map(|head| head_key(queue_index, head)));
+    }
+    while let Some((_, _, queue_index)) = heads.pop() {
+        let account_txs = &mut account_queues[queue_index];
+        let (_, _, tx) = account_txs.pop_front().expect("Account queue of a head is not empty.");
+        ordered_txs.push(tx);
+        heads.extend(account_txs.front().map(|head| head_key(queue_index, head)));
+    }
+
+    ordered_txs
+}
+
+/// The priority of a transaction in the block.
+/// Tips (per L2 gas unit, in fri) and max fees (totals, in wei) are not comparable: transactions
+/// with a tip (i.e., of version 3) precede ones without, and each group is ordered by its own
+/// measure.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum TxPriority {
+    MaxFee(u128),
+    Tip(u64),
+}
+
+/// Returns the priority of a transaction in the block: the tip, for transactions that have one,
+/// and the max fee otherwise.
+pub(crate) fn tx_priority(tx_info: &TransactionInfo) -> TxPriority {
+    match tx_info {
+        TransactionInfo::Current(context) => TxPriority::Tip(context.tip.0),
+        TransactionInfo::Deprecated(context) => TxPriority::MaxFee(context.max_fee.0),
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::{ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{Calldata, Fee, TransactionHash, TransactionVersion};
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::abi::constants;
+use crate::blockifier::block::BlockNumberHashPair;
+use crate::blockifier::block_builder::{
+    order_txs, BlockBuilder, BlockBuilderConfig, BlockCloseReason, TransactionProvider,
+};
+use crate::blockifier::bouncer::BouncerConfig;
+use crate::blockifier::mempool::{Mempool, MempoolConfig};
+use crate::blockifier::transaction_executor::TransactionExecutorError;
+use crate::context::BlockContext;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{CairoVersion, BALANCE, MAX_FEE, MAX_L1_GAS_PRICE};
+use crate::transaction::objects::TransactionInfoCreator;
+use crate::transaction::test_utils::{
+    block_context, test_contract_invoke_tx, test_contract_invoke_tx_v3,
+};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::L1HandlerTransaction;
+
+const OLD_BLOCK_HASH: u64 = 0x1234;
+
+/// Provides the transactions of a queue, in order.
+struct QueueTxProvider(VecDeque<Transaction>);
+
+impl TransactionProvider for QueueTxProvider {
+    fn get_txs(&mut self, n_txs: usize) -> Vec<Transaction> {
+        let n_txs = n_txs.min(self.0.len());
+        self.0.drain(..n_txs).collect()
+    }
+}
+
+fn block_builder(
+    block_context: BlockContext,
+    bouncer_config: BouncerConfig,
+    block_timeout: Duration,
+) -> BlockBuilder<DictStateReader> {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state = test_state(&block_context.chain_info, BALANCE, &[(account, 2), (test_contract, 1)]);
+    let old_block_number =
+        block_context.block_info.block_number.0 - constants::STORED_BLOCK_HASH_BUFFER;
+
+    BlockBuilder::new(
+        state,
+        Some(BlockNumberHashPair::new(old_block_number, stark_felt!(OLD_BLOCK_HASH))),
+        block_context.block_info,
+        block_context.chain_info,
+        block_context.versioned_constants,
+        bouncer_config,
+        BlockBuilderConfig { tx_chunk_size: 2, block_timeout },
+    )
+    .unwrap()
+}
+
+fn invoke_tx(account_instance: u8, nonce: u8, max_fee: u128) -> Transaction {
+    test_contract_invoke_tx(account_instance, nonce, max_fee).into()
+}
+
+fn l1_handler_tx(contract_address: ContractAddress) -> Transaction {
+    Transaction::L1HandlerTransaction(L1HandlerTransaction {
+        tx: starknet_api::transaction::L1HandlerTransaction {
+            version: TransactionVersion::ZERO,
+            nonce: Nonce::default(),
+            contract_address,
+            entry_point_selector: selector_from_name("l1_handler_set_value"),
+            calldata: calldata![stark_felt!(1_u8), stark_felt!(2_u8), stark_felt!(3_u8)],
+        },
+        tx_hash: TransactionHash::default(),
+        paid_fee_on_l1: Fee(1),
+    })
+}
+
+/// Returns the sender address and nonce of each of the given transactions.
+fn senders_and_nonces(txs: &[Transaction]) -> Vec<(ContractAddress, Nonce)> {
+    txs.iter()
+        .map(|tx| {
+            let tx_info = tx.create_tx_info();
+            (tx_info.sender_address(), tx_info.nonce())
+        })
+        .collect()
+}
+
+#[test]
+fn test_order_txs() {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let (account_0, account_1) = (account.get_instance_address(0), account.get_instance_address(1));
+    let l1_handler_address = contract_address!("0x100");
+    let txs = vec![
+        invoke_tx(0, 1, 30),
+        invoke_tx(0, 0, 10),
+        invoke_tx(1, 0, 20),
+        invoke_tx(1, 1, 5),
+        l1_handler_tx(l1_handler_address),
+        invoke_tx(0, 2, 20),
+    ];
+
+    // The L1 handler comes first; then, each account's next transaction competes by its max fee:
+    // account 1 (20) beats account 0 (10), whose nonce-0 transaction then beats account 1's (5),
+    // and so on.
+    let expected_order = vec![
+        (l1_handler_address, Nonce::default()),
+        (account_1, Nonce(stark_felt!(0_u8))),
+        (account_0, Nonce(stark_felt!(0_u8))),
+        (account_0, Nonce(stark_felt!(1_u8))),
+        (account_0, Nonce(stark_felt!(2_u8))),
+        (account_1, Nonce(stark_felt!(1_u8))),
+    ];
+    assert_eq!(senders_and_nonces(&order_txs(txs)), expected_order);
+}
+
+#[test]
+fn test_order_txs_of_mixed_versions() {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let (account_0, account_1) = (account.get_instance_address(0), account.get_instance_address(1));
+    let txs = vec![
+        invoke_tx(0, 0, MAX_FEE),
+        test_contract_invoke_tx_v3(1, 0, 1, MAX_L1_GAS_PRICE).into(),
+        invoke_tx(1, 1, MAX_FEE),
+        test_contract_invoke_tx_v3(0, 1, 2, MAX_L1_GAS_PRICE).into(),
+    ];
+
+    // Tips and max fees are not compared: a transaction with a tip, however low, precedes one
+    // without; account 1's nonce-0 transaction (tip 1) beats account 0's (max fee), which then
+    // ties with account 1's nonce-1 one and wins by arrival order.
+    let expected_order = vec![
+        (account_1, Nonce(stark_felt!(0_u8))),
+        (account_0, Nonce(stark_felt!(0_u8))),
+        (account_0, Nonce(stark_felt!(1_u8))),
+        (account_1, Nonce(stark_felt!(1_u8))),
+    ];
+    assert_eq!(senders_and_nonces(&order_txs(txs)), expected_order);
+}
+
+#[rstest]
+fn test_build_block(block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    // The first chunk arrives out of nonce order; the second one holds a transaction with an
+    // invalid nonce.
+    let txs = [
+        invoke_tx(0, 1, MAX_FEE),
+        invoke_tx(0, 0, MAX_FEE),
+        invoke_tx(1, 0, MAX_FEE),
+        invoke_tx(0, 100, MAX_FEE),
+        invoke_tx(1, 1, MAX_FEE),
+    ];
+    let mut tx_provider = QueueTxProvider(txs.into());
+
+    let artifacts = block_builder(block_context, BouncerConfig::max(), Duration::MAX)
+        .build_block(&mut tx_provider)
+        .unwrap();
+    assert_eq!(artifacts.close_reason, BlockCloseReason::NoMoreTransactions);
+    assert_eq!(artifacts.execution_infos.len(), 4);
+    assert_matches!(
+        artifacts.rejected_txs.as_slice(),
+        [(_, TransactionExecutorError::TransactionExecutionError(_))]
+    );
+    assert!(artifacts.remaining_txs.is_empty());
+    assert_eq!(artifacts.bouncer_weights.n_events, 4);
+
+    // The state diff includes both the block pre-processing and the executed transactions.
+    let state_diff = artifacts.state_diff;
+    let block_hash_contract_address = ContractAddress::from(constants::BLOCK_HASH_CONTRACT_ADDRESS);
+    assert!(state_diff.storage_updates[&block_hash_contract_address]
+        .values()
+        .any(|value| *value == stark_felt!(OLD_BLOCK_HASH)));
+    assert_eq!(
+        state_diff.address_to_nonce[&account.get_instance_address(0)],
+        Nonce(stark_felt!(2_u8))
+    );
+    assert_eq!(
+        state_diff.address_to_nonce[&account.get_instance_address(1)],
+        Nonce(stark_felt!(2_u8))
+    );
+}
+
+#[rstest]
+fn test_block_full(block_context: BlockContext) {
+    let account_address =
+        FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0).get_instance_address(0);
+    let mut tx_provider =
+        QueueTxProvider((0..5_u8).map(|nonce| invoke_tx(0, nonce, MAX_FEE)).collect());
+
+    // Each transaction emits a single event; the third one does not fit into the block.
+    let bouncer_config = BouncerConfig { max_n_events: 2, ..BouncerConfig::max() };
+    let artifacts = block_builder(block_context, bouncer_config, Duration::MAX)
+        .build_block(&mut tx_provider)
+        .unwrap();
+    assert_eq!(artifacts.close_reason, BlockCloseReason::BlockFull);
+    assert_eq!(artifacts.execution_infos.len(), 2);
+    assert!(artifacts.rejected_txs.is_empty());
+    // The rest of the second chunk is returned; the third chunk is never requested.
+    assert_eq!(
+        senders_and_nonces(&artifacts.remaining_txs),
+        vec![
+            (account_address, Nonce(stark_felt!(2_u8))),
+            (account_address, Nonce(stark_felt!(3_u8)))
+        ]
+    );
+    assert_eq!(tx_provider.0.len(), 1);
+}
+
+#[rstest]
+fn test_deadline(block_context: BlockContext) {
+    let mut tx_provider = QueueTxProvider([invoke_tx(0, 0, MAX_FEE)].into());
+
+    let artifacts = block_builder(block_context, BouncerConfig::max(), Duration::ZERO)
+        .build_block(&mut tx_provider)
+        .unwrap();
+    assert_eq!(artifacts.close_reason, BlockCloseReason::Deadline);
+    assert!(artifacts.execution_infos.is_empty());
+    assert_eq!(tx_provider.0.len(), 1);
+}
+
+#[rstest]
+fn test_requeue_remaining_txs(block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state =
+        test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let mempool_config =
+        MempoolConfig { max_n_txs: 10, max_calldata_length: 10, max_signature_length: 2 };
+    let mut mempool = Mempool::new(mempool_config);
+    for nonce in 0..4_u8 {
+        mempool
+            .add_tx(test_contract_invoke_tx(0, nonce, MAX_FEE), &mut state, &block_context)
+            .unwrap();
+    }
+
+    // Each transaction emits a single event; the third one does not fit into the block.
+    let bouncer_config = BouncerConfig { max_n_events: 2, ..BouncerConfig::max() };
+    let artifacts = block_builder(block_context, bouncer_config, Duration::MAX)
+        .build_block(&mut mempool)
+        .unwrap();
+    assert_eq!(artifacts.close_reason, BlockCloseReason::BlockFull);
+    assert_eq!(mempool.n_txs(), 0);
+
+    // The remaining transactions are returned to the mempool, and are provided again once the
+    // block is committed.
+    mempool.requeue_txs(artifacts.remaining_txs);
+    mempool.update_nonces(&artifacts.state_diff.address_to_nonce);
+    assert_eq!(mempool.n_txs(), 2);
+    let account_address = account.get_instance_address(0);
+    assert_eq!(
+        senders_and_nonces(&mempool.get_txs(10)),
+        vec![
+            (account_address, Nonce(stark_felt!(2_u8))),
+            (account_address, Nonce(stark_felt!(3_u8)))
+        ]
+    );
+}
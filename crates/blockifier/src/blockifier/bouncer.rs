@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::ops::AddAssign;
 
 use cairo_vm::serde::deserialize_program::BuiltinName;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
@@ -6,7 +7,11 @@ use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResour
 use crate::abi::constants;
 use crate::transaction::objects::{ResourcesMapping, TransactionExecutionResult};
 
-#[derive(Clone, Default)]
+#[cfg(test)]
+#[path = "bouncer_test.rs"]
+mod test;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct BouncerInfo {
     pub state_diff_size: usize, // The number of felts needed to store the state diff.
     pub l1_gas_amount: usize,
@@ -76,3 +81,86 @@ impl BouncerInfo {
         })
     }
 }
+
+impl AddAssign<&BouncerInfo> for BouncerInfo {
+    fn add_assign(&mut self, other: &BouncerInfo) {
+        self.state_diff_size += other.state_diff_size;
+        self.l1_gas_amount += other.l1_gas_amount;
+        self.message_segment_length += other.message_segment_length;
+        self.execution_resources += &other.execution_resources;
+        self.n_events += other.n_events;
+    }
+}
+
+/// The capacity of a block, per bouncer weight.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BouncerConfig {
+    pub max_state_diff_size: usize,
+    pub max_l1_gas_amount: usize,
+    pub max_message_segment_length: usize,
+    pub max_n_steps: usize,
+    /// Builtins missing from the mapping are not limited.
+    pub max_builtin_instance_counter: HashMap<String, usize>,
+    pub max_n_events: usize,
+}
+
+impl BouncerConfig {
+    /// Returns a configuration that does not limit the block.
+    pub fn max() -> Self {
+        Self {
+            max_state_diff_size: usize::MAX,
+            max_l1_gas_amount: usize::MAX,
+            max_message_segment_length: usize::MAX,
+            max_n_steps: usize::MAX,
+            max_builtin_instance_counter: HashMap::new(),
+            max_n_events: usize::MAX,
+        }
+    }
+
+    /// Returns whether the given weights are within the block capacity.
+    pub fn has_room(&self, weights: &BouncerInfo) -> bool {
+        weights.state_diff_size <= self.max_state_diff_size
+            && weights.l1_gas_amount <= self.max_l1_gas_amount
+            && weights.message_segment_length <= self.max_message_segment_length
+            && weights.execution_resources.n_steps <= self.max_n_steps
+            && weights.n_events <= self.max_n_events
+            && weights.execution_resources.builtin_instance_counter.iter().all(
+                |(builtin_name, count)| match self.max_builtin_instance_counter.get(builtin_name) {
+                    Some(max_count) => count <= max_count,
+                    None => true,
+                },
+            )
+    }
+}
+
+/// Accumulates the weights of the transactions committed to a block, and decides whether further
+/// transactions fit into it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bouncer {
+    pub config: BouncerConfig,
+    accumulated_weights: BouncerInfo,
+}
+
+impl Bouncer {
+    pub fn new(config: BouncerConfig) -> Self {
+        Self { config, accumulated_weights: BouncerInfo::default() }
+    }
+
+    /// Returns the weights of the transactions added so far.
+    pub fn accumulated_weights(&self) -> &BouncerInfo {
+        &self.accumulated_weights
+    }
+
+    /// Returns whether a transaction of the given weights fits into the block, on top of the
+    /// transactions added so far.
+    pub fn has_room(&self, tx_weights: &BouncerInfo) -> bool {
+        let mut weights = self.accumulated_weights.clone();
+        weights += tx_weights;
+        self.config.has_room(&weights)
+    }
+
+    /// Adds the weights of a transaction committed to the block.
+    pub fn add(&mut self, tx_weights: &BouncerInfo) {
+        self.accumulated_weights += tx_weights;
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use crate::blockifier::bouncer::{Bouncer, BouncerConfig, BouncerInfo};
+
+fn bouncer_info(n_steps: usize, n_pedersen: usize, n_events: usize) -> BouncerInfo {
+    BouncerInfo {
+        state_diff_size: 1,
+        l1_gas_amount: 10,
+        message_segment_length: 0,
+        execution_resources: VmExecutionResources {
+            n_steps,
+            n_memory_holes: 0,
+            builtin_instance_counter: HashMap::from([("pedersen_builtin".to_string(), n_pedersen)]),
+        },
+        n_events,
+    }
+}
+
+fn bouncer_config() -> BouncerConfig {
+    BouncerConfig {
+        max_n_steps: 100,
+        max_builtin_instance_counter: HashMap::from([("pedersen_builtin".to_string(), 10)]),
+        max_n_events: 3,
+        ..BouncerConfig::max()
+    }
+}
+
+#[rstest]
+#[case::within_capacity(bouncer_info(100, 10, 3), true)]
+#[case::too_many_steps(bouncer_info(101, 0, 0), false)]
+#[case::too_many_builtin_instances(bouncer_info(0, 11, 0), false)]
+#[case::too_many_events(bouncer_info(0, 0, 4), false)]
+fn test_config_has_room(#[case] weights: BouncerInfo, #[case] expected_has_room: bool) {
+    assert_eq!(bouncer_config().has_room(&weights), expected_has_room);
+}
+
+#[test]
+fn test_unlimited_builtins() {
+    let mut weights = bouncer_info(0, 0, 0);
+    weights.execution_resources.builtin_instance_counter.insert("poseidon_builtin".to_string(), 50);
+    assert!(bouncer_config().has_room(&weights));
+}
+
+#[test]
+fn test_bouncer_accumulates_weights() {
+    let mut bouncer = Bouncer::new(bouncer_config());
+    let tx_weights = bouncer_info(40, 4, 1);
+
+    bouncer.add(&tx_weights);
+    assert!(bouncer.has_room(&tx_weights));
+    bouncer.add(&tx_weights);
+    let accumulated_weights = bouncer.accumulated_weights();
+    assert_eq!(accumulated_weights.execution_resources, bouncer_info(80, 8, 2).execution_resources);
+    assert_eq!((accumulated_weights.state_diff_size, accumulated_weights.n_events), (2, 2));
+    // A third transaction exceeds the step and builtin limits, though not the events one.
+    assert!(!bouncer.has_room(&tx_weights));
+    assert!(bouncer.has_room(&bouncer_info(20, 2, 1)));
+}
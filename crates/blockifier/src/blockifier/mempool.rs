@@ -0,0 +1,365 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+
+use indexmap::IndexMap;
+use starknet_api::core::{ContractAddress, Nonce};
+use thiserror::Error;
+
+use crate::blockifier::block_builder::{tx_priority, TransactionProvider, TxPriority};
+use crate::context::BlockContext;
+use crate::state::cached_state::CachedState;
+use crate::state::errors::StateError;
+use crate::state::state_api::StateReader;
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::errors::{TransactionExecutionError, TransactionPreValidationError};
+use crate::transaction::objects::{TransactionInfo, TransactionInfoCreator};
+use crate::transaction::transaction_execution::Transaction;
+
+#[cfg(test)]
+#[path = "mempool_test.rs"]
+pub mod mempool_test;
+
+#[derive(Debug, Error)]
+pub enum MempoolError {
+    #[error("Calldata length {length} exceeds the maximum of {max_length}.")]
+    CalldataTooLong { length: usize, max_length: usize },
+    #[error("Mempool is full, and the transaction does not outbid any pooled transaction.")]
+    MempoolFull,
+    #[error(
+        "A transaction of account {address:?} with nonce {nonce:?} and max L1 gas price \
+         {existing_max_price} is already pooled; got max L1 gas price {incoming_max_price}."
+    )]
+    ReplacementMaxL1GasPriceTooLow {
+        address: ContractAddress,
+        nonce: Nonce,
+        existing_max_price: u128,
+        incoming_max_price: u128,
+    },
+    #[error(
+        "A transaction of account {address:?} with nonce {nonce:?} and priority \
+         {existing_priority:?} is already pooled; got priority {incoming_priority:?}."
+    )]
+    ReplacementPriorityTooLow {
+        address: ContractAddress,
+        nonce: Nonce,
+        existing_priority: TxPriority,
+        incoming_priority: TxPriority,
+    },
+    #[error("Signature length {length} exceeds the maximum of {max_length}.")]
+    SignatureTooLong { length: usize, max_length: usize },
+    #[error(transparent)]
+    StateError(#[from] StateError),
+    #[error(transparent)]
+    TransactionExecutionError(#[from] TransactionExecutionError),
+    #[error(transparent)]
+    TransactionPreValidationError(#[from] TransactionPreValidationError),
+}
+
+pub type MempoolResult<T> = Result<T, MempoolError>;
+
+#[derive(Clone, Debug)]
+pub struct MempoolConfig {
+    /// The maximal number of pooled transactions; once reached, a transaction is admitted only
+    /// by evicting a lower-priority one.
+    pub max_n_txs: usize,
+    pub max_calldata_length: usize,
+    pub max_signature_length: usize,
+}
+
+#[derive(Debug)]
+struct PooledTransaction {
+    priority: TxPriority,
+    tx: AccountTransaction,
+}
+
+/// The pooled transactions of an account, by nonce.
+#[derive(Debug)]
+struct AccountQueue {
+    /// The account's nonce as of the last committed block (or the state, if no block was
+    /// committed since the account was pooled).
+    committed_nonce: Nonce,
+    /// The nonce of the account's next transaction to be provided; transactions of lower nonces
+    /// (down to the committed one) were provided and await commit, and transactions of higher
+    /// nonces are held until the gap fills.
+    next_nonce: Nonce,
+    txs: BTreeMap<Nonce, PooledTransaction>,
+}
+
+impl AccountQueue {
+    fn new(nonce: Nonce) -> Self {
+        Self { committed_nonce: nonce, next_nonce: nonce, txs: BTreeMap::new() }
+    }
+
+    /// Returns the account's next transaction, if it is pooled.
+    fn ready_tx(&self) -> Option<&PooledTransaction> {
+        self.txs.get(&self.next_nonce)
+    }
+
+    /// Returns whether the account has neither pooled transactions nor ones awaiting commit.
+    fn is_empty(&self) -> bool {
+        self.txs.is_empty() && self.next_nonce == self.committed_nonce
+    }
+}
+
+/// Pools account transactions awaiting inclusion in a block, and provides them in nonce order.
+#[derive(Debug)]
+pub struct Mempool {
+    pub config: MempoolConfig,
+    accounts: HashMap<ContractAddress, AccountQueue>,
+    n_txs: usize,
+}
+
+impl Mempool {
+    pub fn new(config: MempoolConfig) -> Self {
+        Self { config, accounts: HashMap::new(), n_txs: 0 }
+    }
+
+    /// Returns the number of pooled transactions, including ones held due to a nonce gap.
+    pub fn n_txs(&self) -> usize {
+        self.n_txs
+    }
+
+    /// Admits the given transaction into the pool, after checking its validity against the given
+    /// state; a pooled transaction of the same account and nonce is replaced only by a
+    /// higher-priority one or, between version 3 transactions, by one of a higher max L1 gas
+    /// price (and no lower priority).
+    pub fn add_tx<S: StateReader>(
+        &mut self,
+        tx: AccountTransaction,
+        state: &mut CachedState<S>,
+        block_context: &BlockContext,
+    ) -> MempoolResult<()> {
+        self.perform_stateless_validations(&tx)?;
+
+        let tx_context = block_context.to_tx_context(&tx);
+        let tx_info = &tx_context.tx_info;
+        let (address, nonce) = (tx_info.sender_address(), tx_info.nonce());
+        // Note: the pre-validation stage increments the nonce; the changes are discarded.
+        let mut transactional_state = CachedState::create_transactional(state);
+        let charge_fee = true;
+        let strict_nonce_check = false;
+        tx.perform_pre_validation_stage(
+            &mut transactional_state,
+            &tx_context,
+            charge_fee,
+            strict_nonce_check,
+        )?;
+        transactional_state.abort();
+
+        let next_nonce = match self.accounts.get(&address) {
+            Some(account_queue) => account_queue.next_nonce,
+            None => state.get_nonce_at(address)?,
+        };
+        // Transactions preceding the account's next one were already provided (or committed).
+        if nonce < next_nonce {
+            return Err(TransactionPreValidationError::InvalidNonce {
+                address,
+                account_nonce: next_nonce,
+                incoming_tx_nonce: nonce,
+            })?;
+        }
+
+        let priority = tx_priority(tx_info);
+        let pooled_tx =
+            self.accounts.get(&address).and_then(|account_queue| account_queue.txs.get(&nonce));
+        if let Some(pooled_tx) = pooled_tx {
+            validate_replacement(pooled_tx, tx_info, priority)?;
+        } else if self.n_txs >= self.config.max_n_txs {
+            self.evict_lowest_priority_tx(address, priority)?;
+        }
+
+        let account_queue =
+            self.accounts.entry(address).or_insert_with(|| AccountQueue::new(next_nonce));
+        if account_queue.txs.insert(nonce, PooledTransaction { priority, tx }).is_none() {
+            self.n_txs += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Returns provided transactions that were not included in a block (e.g., the remaining
+    /// transactions of a closed block) to the pool; to be called before the nonces of the block
+    /// are committed, after which they are provided again.
+    /// Only transactions awaiting commit are requeued; others are ignored. Requeued transactions
+    /// were already admitted, so the pool may temporarily exceed its capacity.
+    pub fn requeue_txs(&mut self, txs: impl IntoIterator<Item = Transaction>) {
+        for tx in txs {
+            // Non-account transactions are not pooled.
+            let Transaction::AccountTransaction(tx) = tx else {
+                continue;
+            };
+            let tx_info = tx.create_tx_info();
+            let (address, nonce) = (tx_info.sender_address(), tx_info.nonce());
+            let Some(account_queue) = self.accounts.get_mut(&address) else {
+                continue;
+            };
+            if nonce < account_queue.committed_nonce || nonce >= account_queue.next_nonce {
+                continue;
+            }
+
+            let priority = tx_priority(&tx_info);
+            if account_queue.txs.insert(nonce, PooledTransaction { priority, tx }).is_none() {
+                self.n_txs += 1;
+            }
+        }
+    }
+
+    /// Commits the nonces of the given accounts (e.g., by the state diff of a committed block),
+    /// and drops their pooled transactions of lower nonces.
+    /// Provided transactions that were not committed no longer await commit: their nonces are
+    /// provided again once pooled.
+    pub fn update_nonces(&mut self, address_to_nonce: &IndexMap<ContractAddress, Nonce>) {
+        let mut n_dropped_txs = 0;
+        self.accounts.retain(|address, account_queue| {
+            if let Some(nonce) = address_to_nonce.get(address) {
+                let remaining_txs = account_queue.txs.split_off(nonce);
+                n_dropped_txs += account_queue.txs.len();
+                account_queue.txs = remaining_txs;
+                account_queue.committed_nonce = *nonce;
+            }
+            account_queue.next_nonce = account_queue.committed_nonce;
+
+            !account_queue.is_empty()
+        });
+        self.n_txs -= n_dropped_txs;
+    }
+
+    fn perform_stateless_validations(&self, tx: &AccountTransaction) -> MempoolResult<()> {
+        tx.verify_tx_version(tx.create_tx_info().version())?;
+
+        let calldata_length = tx.calldata_length();
+        if calldata_length > self.config.max_calldata_length {
+            return Err(MempoolError::CalldataTooLong {
+                length: calldata_length,
+                max_length: self.config.max_calldata_length,
+            });
+        }
+        let signature_length = tx.signature_length();
+        if signature_length > self.config.max_signature_length {
+            return Err(MempoolError::SignatureTooLong {
+                length: signature_length,
+                max_length: self.config.max_signature_length,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Makes room for a transaction of the given account and priority, by evicting the
+    /// lowest-priority transaction among the last (highest-nonce) ones of each other account;
+    /// evicting a last transaction leaves no nonce gap behind, and the incoming transaction may
+    /// depend on the last transaction of its own account.
+    /// Priorities are compared as in the block: see [TxPriority].
+    fn evict_lowest_priority_tx(
+        &mut self,
+        incoming_address: ContractAddress,
+        incoming_priority: TxPriority,
+    ) -> MempoolResult<()> {
+        let lowest_priority_tail = self
+            .accounts
+            .iter()
+            .filter(|(address, _)| **address != incoming_address)
+            .filter_map(|(address, account_queue)| {
+                let (nonce, pooled_tx) = account_queue.txs.last_key_value()?;
+                Some((pooled_tx.priority, *address, *nonce))
+            })
+            .min();
+        let Some((priority, address, nonce)) = lowest_priority_tail else {
+            return Err(MempoolError::MempoolFull);
+        };
+        if priority >= incoming_priority {
+            return Err(MempoolError::MempoolFull);
+        }
+
+        let account_queue = self.accounts.get_mut(&address).expect("Account queue exists.");
+        account_queue.txs.remove(&nonce);
+        if account_queue.is_empty() {
+            self.accounts.remove(&address);
+        }
+        self.n_txs -= 1;
+        Ok(())
+    }
+}
+
+/// Checks that the incoming transaction may replace the pooled one of the same account and nonce:
+/// it must have a higher priority. Between version 3 transactions, it must also not lower the max
+/// L1 gas price, and a higher max L1 gas price suffices in place of a higher priority; otherwise,
+/// a higher tip could replace a transaction by one that may pay less per gas unit.
+fn validate_replacement(
+    pooled_tx: &PooledTransaction,
+    incoming_tx_info: &TransactionInfo,
+    incoming_priority: TxPriority,
+) -> MempoolResult<()> {
+    let (address, nonce) = (incoming_tx_info.sender_address(), incoming_tx_info.nonce());
+    let pooled_tx_info = pooled_tx.tx.create_tx_info();
+    if let (TransactionInfo::Current(pooled_context), TransactionInfo::Current(incoming_context)) =
+        (&pooled_tx_info, incoming_tx_info)
+    {
+        let existing_max_price = pooled_context
+            .l1_resource_bounds()
+            .map_err(TransactionPreValidationError::from)?
+            .max_price_per_unit;
+        let incoming_max_price = incoming_context
+            .l1_resource_bounds()
+            .map_err(TransactionPreValidationError::from)?
+            .max_price_per_unit;
+        if incoming_max_price < existing_max_price {
+            return Err(MempoolError::ReplacementMaxL1GasPriceTooLow {
+                address,
+                nonce,
+                existing_max_price,
+                incoming_max_price,
+            });
+        }
+        if incoming_max_price > existing_max_price && incoming_priority >= pooled_tx.priority {
+            return Ok(());
+        }
+    }
+
+    if incoming_priority <= pooled_tx.priority {
+        return Err(MempoolError::ReplacementPriorityTooLow {
+            address,
+            nonce,
+            existing_priority: pooled_tx.priority,
+            incoming_priority,
+        });
+    }
+    Ok(())
+}
+
+impl TransactionProvider for Mempool {
+    /// Returns up to `n_txs` ready transactions, i.e., ones that follow their account's previous
+    /// transaction. Each account's transactions are provided in nonce order, and the accounts
+    /// are interleaved by the priority of their next transaction.
+    fn get_txs(&mut self, n_txs: usize) -> Vec<Transaction> {
+        let mut txs = Vec::with_capacity(n_txs.min(self.n_txs));
+        let mut heads: BinaryHeap<(TxPriority, Reverse<ContractAddress>)> = self
+            .accounts
+            .iter()
+            .filter_map(|(address, account_queue)| {
+                Some((account_queue.ready_tx()?.priority, Reverse(*address)))
+            })
+            .collect();
+
+        while txs.len() < n_txs {
+            let Some((_, Reverse(address))) = heads.pop() else {
+                break;
+            };
+            let account_queue = self.accounts.get_mut(&address).expect("Account queue exists.");
+            let PooledTransaction { tx, .. } = account_queue
+                .txs
+                .remove(&account_queue.next_nonce)
+                .expect("Ready transaction exists.");
+            account_queue.next_nonce =
+                account_queue.next_nonce.try_increment().expect("Nonce does not overflow.");
+            self.n_txs -= 1;
+            txs.push(Transaction::AccountTransaction(tx));
+
+            heads.extend(
+                account_queue.ready_tx().map(|pooled_tx| (pooled_tx.priority, Reverse(address))),
+            );
+        }
+
+        txs
+    }
+}
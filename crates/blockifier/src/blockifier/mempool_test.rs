@@ -0,0 +1,287 @@
+use assert_matches::assert_matches;
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::{Fee, TransactionSignature};
+
+use crate::blockifier::block_builder::{tx_priority, TransactionProvider, TxPriority};
+use crate::blockifier::mempool::{Mempool, MempoolConfig, MempoolError};
+use crate::context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::{State, StateReader};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{CairoVersion, BALANCE, MAX_FEE, MAX_L1_GAS_PRICE};
+use crate::transaction::errors::{TransactionFeeError, TransactionPreValidationError};
+use crate::transaction::objects::{TransactionInfo, TransactionInfoCreator};
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, test_contract_invoke_tx as invoke_tx,
+    test_contract_invoke_tx_v3 as invoke_tx_v3,
+};
+use crate::transaction::transaction_execution::Transaction;
+
+fn mempool_config(max_n_txs: usize) -> MempoolConfig {
+    MempoolConfig { max_n_txs, max_calldata_length: 10, max_signature_length: 2 }
+}
+
+#[fixture]
+fn state(block_context: BlockContext) -> CachedState<DictStateReader> {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    test_state(&block_context.chain_info, BALANCE, &[(account, 3), (test_contract, 1)])
+}
+
+fn account_address(account_instance: u8) -> ContractAddress {
+    FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0)
+        .get_instance_address(account_instance)
+}
+
+/// Returns the sender address, nonce and max fee of each of the given transactions.
+fn tx_summaries(txs: &[Transaction]) -> Vec<(ContractAddress, Nonce, Fee)> {
+    txs.iter()
+        .map(|tx| {
+            let tx_info = tx.create_tx_info();
+            let TransactionInfo::Deprecated(context) = &tx_info else {
+                panic!("Expected a deprecated transaction.");
+            };
+            (tx_info.sender_address(), tx_info.nonce(), context.max_fee)
+        })
+        .collect()
+}
+
+/// Returns the sender address, nonce and priority of each of the given transactions.
+fn tx_priorities(txs: &[Transaction]) -> Vec<(ContractAddress, Nonce, TxPriority)> {
+    txs.iter()
+        .map(|tx| {
+            let tx_info = tx.create_tx_info();
+            (tx_info.sender_address(), tx_info.nonce(), tx_priority(&tx_info))
+        })
+        .collect()
+}
+
+#[rstest]
+fn test_nonce_gap(block_context: BlockContext, mut state: CachedState<DictStateReader>) {
+    let mut mempool = Mempool::new(mempool_config(10));
+    for (account_instance, nonce) in [(0, 0), (0, 2), (1, 0)] {
+        mempool
+            .add_tx(invoke_tx(account_instance, nonce, MAX_FEE), &mut state, &block_context)
+            .unwrap();
+    }
+
+    // The nonce-2 transaction is held until the nonce-1 one arrives.
+    let txs = mempool.get_txs(10);
+    assert_eq!(txs.len(), 2);
+    assert_eq!(mempool.n_txs(), 1);
+    assert_eq!(mempool.get_txs(10).len(), 0);
+
+    mempool.add_tx(invoke_tx(0, 1, MAX_FEE), &mut state, &block_context).unwrap();
+    let expected_txs = vec![
+        (account_address(0), Nonce(stark_felt!(1_u8)), Fee(MAX_FEE)),
+        (account_address(0), Nonce(stark_felt!(2_u8)), Fee(MAX_FEE)),
+    ];
+    assert_eq!(tx_summaries(&mempool.get_txs(10)), expected_txs);
+    assert_eq!(mempool.n_txs(), 0);
+
+    // Provided transactions may not be resubmitted.
+    assert_matches!(
+        mempool.add_tx(invoke_tx(0, 1, MAX_FEE), &mut state, &block_context),
+        Err(MempoolError::TransactionPreValidationError(
+            TransactionPreValidationError::InvalidNonce { .. }
+        ))
+    );
+
+    // Once a block is committed, its nonces are taken from the state diff; transactions that
+    // were not included may be resubmitted.
+    for _ in 0..2 {
+        state.increment_nonce(account_address(0)).unwrap();
+    }
+    mempool.update_nonces(&IndexMap::from([(account_address(0), Nonce(stark_felt!(2_u8)))]));
+    assert!(mempool.accounts.is_empty());
+    mempool.add_tx(invoke_tx(0, 2, MAX_FEE), &mut state, &block_context).unwrap();
+    mempool.add_tx(invoke_tx(1, 0, MAX_FEE), &mut state, &block_context).unwrap();
+    assert_eq!(mempool.get_txs(10).len(), 2);
+}
+
+#[rstest]
+fn test_provided_by_priority(block_context: BlockContext, mut state: CachedState<DictStateReader>) {
+    let mut mempool = Mempool::new(mempool_config(10));
+    for (account_instance, nonce, max_fee) in
+        [(0, 0, MAX_FEE), (0, 1, MAX_FEE + 2), (1, 0, MAX_FEE + 1)]
+    {
+        mempool
+            .add_tx(invoke_tx(account_instance, nonce, max_fee), &mut state, &block_context)
+            .unwrap();
+    }
+
+    let expected_txs = vec![
+        (account_address(1), Nonce(stark_felt!(0_u8)), Fee(MAX_FEE + 1)),
+        (account_address(0), Nonce(stark_felt!(0_u8)), Fee(MAX_FEE)),
+    ];
+    assert_eq!(tx_summaries(&mempool.get_txs(2)), expected_txs);
+    assert_eq!(mempool.n_txs(), 1);
+}
+
+#[rstest]
+fn test_replacement(block_context: BlockContext, mut state: CachedState<DictStateReader>) {
+    let mut mempool = Mempool::new(mempool_config(10));
+    mempool.add_tx(invoke_tx(0, 0, MAX_FEE), &mut state, &block_context).unwrap();
+
+    assert_matches!(
+        mempool.add_tx(invoke_tx(0, 0, MAX_FEE), &mut state, &block_context),
+        Err(MempoolError::ReplacementPriorityTooLow { .. })
+    );
+    mempool.add_tx(invoke_tx(0, 0, MAX_FEE + 1), &mut state, &block_context).unwrap();
+    assert_eq!(mempool.n_txs(), 1);
+    assert_eq!(
+        tx_summaries(&mempool.get_txs(10)),
+        vec![(account_address(0), Nonce(stark_felt!(0_u8)), Fee(MAX_FEE + 1))]
+    );
+}
+
+#[rstest]
+fn test_eviction(block_context: BlockContext, mut state: CachedState<DictStateReader>) {
+    let mut mempool = Mempool::new(mempool_config(2));
+    mempool.add_tx(invoke_tx(0, 0, MAX_FEE), &mut state, &block_context).unwrap();
+    mempool.add_tx(invoke_tx(1, 0, MAX_FEE + 2), &mut state, &block_context).unwrap();
+
+    // The lowest-priority transaction is evicted in favor of a higher-priority one.
+    mempool.add_tx(invoke_tx(1, 1, MAX_FEE + 1), &mut state, &block_context).unwrap();
+    assert_eq!(mempool.n_txs(), 2);
+    assert_matches!(
+        mempool.add_tx(invoke_tx(0, 0, MAX_FEE), &mut state, &block_context),
+        Err(MempoolError::MempoolFull)
+    );
+
+    let expected_txs = vec![
+        (account_address(1), Nonce(stark_felt!(0_u8)), Fee(MAX_FEE + 2)),
+        (account_address(1), Nonce(stark_felt!(1_u8)), Fee(MAX_FEE + 1)),
+    ];
+    assert_eq!(tx_summaries(&mempool.get_txs(10)), expected_txs);
+}
+
+#[rstest]
+fn test_eviction_of_other_accounts(
+    block_context: BlockContext,
+    mut state: CachedState<DictStateReader>,
+) {
+    let mut mempool = Mempool::new(mempool_config(2));
+    mempool.add_tx(invoke_tx(0, 0, MAX_FEE), &mut state, &block_context).unwrap();
+    mempool.add_tx(invoke_tx(0, 1, MAX_FEE), &mut state, &block_context).unwrap();
+
+    // An account's transactions are not evicted in favor of its next one, which follows them.
+    assert_matches!(
+        mempool.add_tx(invoke_tx(0, 2, MAX_FEE + 1), &mut state, &block_context),
+        Err(MempoolError::MempoolFull)
+    );
+
+    // Once all of its transactions are evicted, an account is no longer tracked.
+    mempool.add_tx(invoke_tx(1, 0, MAX_FEE + 1), &mut state, &block_context).unwrap();
+    mempool.add_tx(invoke_tx(1, 1, MAX_FEE + 1), &mut state, &block_context).unwrap();
+    assert_eq!(mempool.n_txs(), 2);
+    assert_eq!(mempool.accounts.keys().collect::<Vec<_>>(), vec![&account_address(1)]);
+}
+
+#[rstest]
+fn test_replacement_of_mixed_versions(
+    block_context: BlockContext,
+    mut state: CachedState<DictStateReader>,
+) {
+    let mut mempool = Mempool::new(mempool_config(10));
+    let price = MAX_L1_GAS_PRICE;
+    mempool.add_tx(invoke_tx(0, 0, MAX_FEE), &mut state, &block_context).unwrap();
+
+    // A tip, however low, outbids a max fee, and not vice versa.
+    mempool.add_tx(invoke_tx_v3(0, 0, 0, 2 * price), &mut state, &block_context).unwrap();
+    assert_matches!(
+        mempool.add_tx(invoke_tx(0, 0, 2 * MAX_FEE), &mut state, &block_context),
+        Err(MempoolError::ReplacementPriorityTooLow { .. })
+    );
+
+    // A higher tip does not make up for a lower max L1 gas price; a higher max L1 gas price
+    // suffices on its own.
+    assert_matches!(
+        mempool.add_tx(invoke_tx_v3(0, 0, 1, price), &mut state, &block_context),
+        Err(MempoolError::ReplacementMaxL1GasPriceTooLow {
+            existing_max_price, incoming_max_price, ..
+        }) if existing_max_price == 2 * price && incoming_max_price == price
+    );
+    assert_matches!(
+        mempool.add_tx(invoke_tx_v3(0, 0, 0, 2 * price), &mut state, &block_context),
+        Err(MempoolError::ReplacementPriorityTooLow { .. })
+    );
+    mempool.add_tx(invoke_tx_v3(0, 0, 0, 3 * price), &mut state, &block_context).unwrap();
+    mempool.add_tx(invoke_tx_v3(0, 0, 1, 3 * price), &mut state, &block_context).unwrap();
+
+    assert_eq!(mempool.n_txs(), 1);
+    assert_eq!(
+        tx_priorities(&mempool.get_txs(10)),
+        vec![(account_address(0), Nonce(stark_felt!(0_u8)), TxPriority::Tip(1))]
+    );
+}
+
+#[rstest]
+fn test_eviction_of_mixed_versions(
+    block_context: BlockContext,
+    mut state: CachedState<DictStateReader>,
+) {
+    let mut mempool = Mempool::new(mempool_config(2));
+    mempool.add_tx(invoke_tx_v3(0, 0, 0, MAX_L1_GAS_PRICE), &mut state, &block_context).unwrap();
+    mempool.add_tx(invoke_tx(1, 0, MAX_FEE), &mut state, &block_context).unwrap();
+
+    // Transactions with a tip outrank ones with a max fee, however high.
+    mempool.add_tx(invoke_tx(2, 0, 2 * MAX_FEE), &mut state, &block_context).unwrap();
+    mempool.add_tx(invoke_tx_v3(1, 0, 0, MAX_L1_GAS_PRICE), &mut state, &block_context).unwrap();
+    assert_matches!(
+        mempool.add_tx(invoke_tx(2, 0, 3 * MAX_FEE), &mut state, &block_context),
+        Err(MempoolError::MempoolFull)
+    );
+
+    let mut txs = tx_priorities(&mempool.get_txs(10));
+    txs.sort_by_key(|(address, ..)| *address);
+    let expected_txs = vec![
+        (account_address(0), Nonce(stark_felt!(0_u8)), TxPriority::Tip(0)),
+        (account_address(1), Nonce(stark_felt!(0_u8)), TxPriority::Tip(0)),
+    ];
+    assert_eq!(txs, expected_txs);
+}
+
+#[rstest]
+fn test_admission_errors(block_context: BlockContext, mut state: CachedState<DictStateReader>) {
+    let mut mempool = Mempool::new(mempool_config(10));
+
+    let long_signature_tx = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account_address(0),
+        signature: TransactionSignature(vec![StarkFelt::ONE; 3]),
+    });
+    assert_matches!(
+        mempool.add_tx(long_signature_tx, &mut state, &block_context),
+        Err(MempoolError::SignatureTooLong { length: 3, max_length: 2 })
+    );
+
+    assert_matches!(
+        mempool.add_tx(invoke_tx(0, 0, 1), &mut state, &block_context),
+        Err(MempoolError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::MaxFeeTooLow { .. }
+            )
+        ))
+    );
+    assert_matches!(
+        mempool.add_tx(invoke_tx(0, 0, BALANCE + 1), &mut state, &block_context),
+        Err(MempoolError::TransactionPreValidationError(
+            TransactionPreValidationError::TransactionFeeError(
+                TransactionFeeError::MaxFeeExceedsBalance { .. }
+            )
+        ))
+    );
+
+    // Rejected transactions leave the mempool and the state untouched.
+    assert_eq!(mempool.n_txs(), 0);
+    assert_eq!(state.get_nonce_at(account_address(0)).unwrap(), Nonce::default());
+}
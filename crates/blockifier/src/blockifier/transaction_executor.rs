@@ -7,14 +7,17 @@ use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use starknet_api::core::ClassHash;
 use thiserror::Error;
 
-use crate::blockifier::bouncer::BouncerInfo;
+use crate::blockifier::bouncer::{Bouncer, BouncerConfig, BouncerInfo};
+use crate::concurrency::parallel_executor::{
+    ExecutionOutput, ExecutionTaskOutput, ParallelExecutor,
+};
 use crate::context::BlockContext;
 use crate::execution::call_info::{CallInfo, MessageL1CostInfo};
 use crate::fee::actual_cost::ActualCost;
 use crate::fee::gas_usage::get_onchain_data_segment_length;
 use crate::state::cached_state::{
-    CachedState, CommitmentStateDiff, StagedTransactionalState, StateChangesKeys, StorageEntry,
-    TransactionalState,
+    CachedState, CommitmentStateDiff, SavepointId, StagedTransactionalState, StateAccesses,
+    StateChangesKeys, StorageEntry,
 };
 use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateReader};
@@ -24,12 +27,20 @@ use crate::transaction::objects::TransactionExecutionInfo;
 use crate::transaction::transaction_execution::Transaction;
 use crate::transaction::transactions::{ExecutableTransaction, ValidatableTransaction};
 
+#[cfg(test)]
+#[path = "transaction_executor_test.rs"]
+pub mod transaction_executor_test;
+
 #[derive(Debug, Error)]
 pub enum TransactionExecutorError {
+    #[error("Transaction cannot be added to the current block, block capacity reached.")]
+    BlockFull,
     #[error(transparent)]
     StateError(#[from] StateError),
     #[error(transparent)]
     TransactionExecutionError(#[from] TransactionExecutionError),
+    #[error("Transaction exceeds the capacity of an empty block.")]
+    TransactionTooLarge,
 }
 
 pub type TransactionExecutorResult<T> = Result<T, TransactionExecutorError>;
@@ -43,6 +54,8 @@ pub struct TransactionExecutor<S: StateReader> {
     pub visited_storage_entries: HashSet<StorageEntry>,
     // This member should be consistent with the state's modified keys.
     state_changes_keys: StateChangesKeys,
+    // Holds the weights of the committed transactions; decides whether further ones fit the block.
+    pub bouncer: Bouncer,
 
     // State-related fields.
     pub state: CachedState<S>,
@@ -51,10 +64,16 @@ pub struct TransactionExecutor<S: StateReader> {
     // Is `Some` only after transaction has finished executing, and before commit/revert have been
     // called. `None` while a transaction is being executed and in between transactions.
     pub staged_for_commit_state: Option<StagedTransactionalState>,
+    // The bouncer info of the staged transaction; added to the bouncer on commit.
+    staged_bouncer_info: Option<BouncerInfo>,
 }
 
 impl<S: StateReader> TransactionExecutor<S> {
-    pub fn new(state: CachedState<S>, block_context: BlockContext) -> Self {
+    pub fn new(
+        state: CachedState<S>,
+        block_context: BlockContext,
+        bouncer_config: BouncerConfig,
+    ) -> Self {
         log::debug!("Initializing Transaction Executor...");
         let tx_executor = Self {
             block_context,
@@ -63,8 +82,10 @@ impl<S: StateReader> TransactionExecutor<S> {
             // Note: the state might not be empty even at this point; it is the creator's
             // responsibility to tune the bouncer according to pre and post block process.
             state_changes_keys: StateChangesKeys::default(),
+            bouncer: Bouncer::new(bouncer_config),
             state,
             staged_for_commit_state: None,
+            staged_bouncer_info: None,
         };
         log::debug!("Initialized Transaction Executor.");
 
@@ -72,21 +93,16 @@ impl<S: StateReader> TransactionExecutor<S> {
     }
 
     /// Executes the given transaction on the state maintained by the executor.
-    /// Returns the execution trace, together with the compiled class hashes of executed classes
-    /// (used for counting purposes).
+    /// Returns the execution trace, together with the bouncer info (used for counting purposes)
+    /// and the cells read and written by the transaction.
+    /// A transaction that does not fit into the block is rolled back, and is not staged for
+    /// commit.
     pub fn execute(
         &mut self,
         tx: Transaction,
         charge_fee: bool,
-    ) -> TransactionExecutorResult<(TransactionExecutionInfo, BouncerInfo)> {
-        let l1_handler_payload_size: Option<usize> =
-            if let Transaction::L1HandlerTransaction(l1_handler_tx) = &tx {
-                Some(l1_handler_tx.payload_size())
-            } else {
-                None
-            };
-        let mut tx_executed_class_hashes = HashSet::<ClassHash>::new();
-        let mut tx_visited_storage_entries = HashSet::<StorageEntry>::new();
+    ) -> TransactionExecutorResult<(TransactionExecutionInfo, BouncerInfo, StateAccesses)> {
+        let l1_handler_payload_size = get_l1_handler_payload_size(&tx);
         let mut transactional_state = CachedState::create_transactional(&mut self.state);
         let validate = true;
 
@@ -96,61 +112,24 @@ impl<S: StateReader> TransactionExecutor<S> {
             Ok(tx_execution_info) => {
                 // Prepare bouncer info; the countings here should be linear in the transactional
                 // state changes and execution info rather than the cumulative state attributes.
-
-                // TODO(Elin, 01/06/2024): consider traversing the calls to collect data once.
-                // TODO(Elin, 01/06/2024): consider moving Bouncer logic to a function.
-                tx_executed_class_hashes.extend(tx_execution_info.get_executed_class_hashes());
-                tx_visited_storage_entries.extend(tx_execution_info.get_visited_storage_entries());
-                let n_events = tx_execution_info.get_number_of_events();
-
-                // Count message to L1 resources.
-                let call_infos: IntoIter<&CallInfo> =
-                    [&tx_execution_info.validate_call_info, &tx_execution_info.execute_call_info]
-                        .iter()
-                        .filter_map(|&call_info| call_info.as_ref())
-                        .collect::<Vec<&CallInfo>>()
-                        .into_iter();
-                let MessageL1CostInfo { l2_to_l1_payload_lengths: _, message_segment_length } =
-                    MessageL1CostInfo::calculate(call_infos, l1_handler_payload_size)?;
-
-                // Count additional OS resources.
-                let mut additional_os_resources = get_casm_hash_calculation_resources(
-                    &mut transactional_state,
-                    &self.executed_class_hashes,
-                    &tx_executed_class_hashes,
-                )?;
-                additional_os_resources += &get_particia_update_resources(
-                    &self.visited_storage_entries,
-                    &tx_visited_storage_entries,
-                )?;
-
-                // Count residual state diff size (w.r.t. the OS output encoding).
                 let tx_state_changes_keys =
                     transactional_state.get_actual_state_changes()?.into_keys();
-                let tx_unique_state_changes_keys =
-                    tx_state_changes_keys.difference(&self.state_changes_keys);
-                // Note: block-constant felts are not counted here. so the bouncer needs to
-                // tune the size limit accordingly. E.g., the felt that encodes the number of
-                // modified contracts in a block.
-                let state_diff_size =
-                    get_onchain_data_segment_length(tx_unique_state_changes_keys.count());
-
-                // Finalize counting logic.
-                let actual_resources = &tx_execution_info.actual_resources;
-                let bouncer_info = BouncerInfo::calculate(
-                    actual_resources,
-                    additional_os_resources,
-                    message_segment_length,
-                    state_diff_size,
-                    n_events,
+                let state_accesses = transactional_state.get_state_accesses()?;
+                let staged_state = transactional_state.stage(
+                    tx_execution_info.get_executed_class_hashes(),
+                    tx_execution_info.get_visited_storage_entries(),
+                    tx_state_changes_keys.difference(&self.state_changes_keys),
+                );
+                let bouncer_info = self.calculate_bouncer_info(
+                    &tx_execution_info,
+                    &staged_state,
+                    l1_handler_payload_size,
                 )?;
-                self.staged_for_commit_state = Some(transactional_state.stage(
-                    tx_executed_class_hashes,
-                    tx_visited_storage_entries,
-                    tx_unique_state_changes_keys,
-                ));
+                self.verify_block_capacity(&bouncer_info)?;
+                self.staged_for_commit_state = Some(staged_state);
+                self.staged_bouncer_info = Some(bouncer_info.clone());
 
-                Ok((tx_execution_info, bouncer_info))
+                Ok((tx_execution_info, bouncer_info, state_accesses))
             }
             Err(error) => {
                 transactional_state.abort();
@@ -159,6 +138,70 @@ impl<S: StateReader> TransactionExecutor<S> {
         }
     }
 
+    /// Returns the bouncer info of the given transaction, whose execution yielded the given
+    /// staged state; the countings are relative to the transactions committed so far.
+    fn calculate_bouncer_info(
+        &self,
+        tx_execution_info: &TransactionExecutionInfo,
+        staged_state: &StagedTransactionalState,
+        l1_handler_payload_size: Option<usize>,
+    ) -> TransactionExecutorResult<BouncerInfo> {
+        // TODO(Elin, 01/06/2024): consider traversing the calls to collect data once.
+        let n_events = tx_execution_info.get_number_of_events();
+
+        // Count message to L1 resources.
+        let call_infos: IntoIter<&CallInfo> =
+            [&tx_execution_info.validate_call_info, &tx_execution_info.execute_call_info]
+                .iter()
+                .filter_map(|&call_info| call_info.as_ref())
+                .collect::<Vec<&CallInfo>>()
+                .into_iter();
+        let MessageL1CostInfo { l2_to_l1_payload_lengths: _, message_segment_length } =
+            MessageL1CostInfo::calculate(call_infos, l1_handler_payload_size)?;
+
+        // Count additional OS resources.
+        // Note: executed classes cannot be declared by the executing transaction, so they are
+        // read from the committed state.
+        let mut additional_os_resources = get_casm_hash_calculation_resources(
+            &self.state,
+            &self.executed_class_hashes,
+            &staged_state.tx_executed_class_hashes,
+        )?;
+        additional_os_resources += &get_particia_update_resources(
+            &self.visited_storage_entries,
+            &staged_state.tx_visited_storage_entries,
+        )?;
+
+        // Count residual state diff size (w.r.t. the OS output encoding).
+        // Note: block-constant felts are not counted here. so the bouncer needs to
+        // tune the size limit accordingly. E.g., the felt that encodes the number of
+        // modified contracts in a block.
+        let state_diff_size =
+            get_onchain_data_segment_length(staged_state.tx_unique_state_changes_keys.count());
+
+        // Finalize counting logic.
+        let actual_resources = &tx_execution_info.actual_resources;
+        Ok(BouncerInfo::calculate(
+            actual_resources,
+            additional_os_resources,
+            message_segment_length,
+            state_diff_size,
+            n_events,
+        )?)
+    }
+
+    /// Verifies that a transaction of the given bouncer info fits into the block.
+    fn verify_block_capacity(&self, bouncer_info: &BouncerInfo) -> TransactionExecutorResult<()> {
+        if !self.bouncer.config.has_room(bouncer_info) {
+            return Err(TransactionExecutorError::TransactionTooLarge);
+        }
+        if !self.bouncer.has_room(bouncer_info) {
+            return Err(TransactionExecutorError::BlockFull);
+        }
+
+        Ok(())
+    }
+
     pub fn validate(
         &mut self,
         account_tx: &AccountTransaction,
@@ -225,6 +268,9 @@ impl<S: StateReader> TransactionExecutor<S> {
         let Some(finalized_transactional_state) = self.staged_for_commit_state.take() else {
             panic!("commit called without a transactional state")
         };
+        if let Some(bouncer_info) = self.staged_bouncer_info.take() {
+            self.bouncer.add(&bouncer_info);
+        }
 
         let child_cache = finalized_transactional_state.cache;
         self.state.update_cache(child_cache);
@@ -246,14 +292,154 @@ impl<S: StateReader> TransactionExecutor<S> {
     }
 
     pub fn abort(&mut self) {
-        self.staged_for_commit_state = None
+        self.staged_for_commit_state = None;
+        self.staged_bouncer_info = None;
+    }
+
+    /// Takes a savepoint of the executor, to which it can later be rolled back; e.g., to drop the
+    /// transactions committed after it.
+    pub fn checkpoint(&mut self) -> TransactionExecutorSavepoint {
+        assert!(
+            self.staged_for_commit_state.is_none(),
+            "Cannot take a savepoint while a transaction is staged for commit."
+        );
+
+        TransactionExecutorSavepoint {
+            state_savepoint_id: self.state.checkpoint(),
+            executed_class_hashes: self.executed_class_hashes.clone(),
+            visited_storage_entries: self.visited_storage_entries.clone(),
+            state_changes_keys: self.state_changes_keys.clone(),
+            bouncer: self.bouncer.clone(),
+        }
+    }
+
+    /// Undoes the transactions committed since the given savepoint was taken, and aborts the
+    /// staged one (if any). The savepoint remains valid.
+    pub fn rollback_to(&mut self, savepoint: &TransactionExecutorSavepoint) {
+        self.abort();
+        self.state.rollback_to(savepoint.state_savepoint_id);
+        self.executed_class_hashes.clone_from(&savepoint.executed_class_hashes);
+        self.visited_storage_entries.clone_from(&savepoint.visited_storage_entries);
+        self.state_changes_keys.clone_from(&savepoint.state_changes_keys);
+        self.bouncer.clone_from(&savepoint.bouncer);
+    }
+}
+
+/// A savepoint of a `TransactionExecutor`; holds the executor's counting state at the time it was
+/// taken.
+pub struct TransactionExecutorSavepoint {
+    state_savepoint_id: SavepointId,
+    executed_class_hashes: HashSet<ClassHash>,
+    visited_storage_entries: HashSet<StorageEntry>,
+    state_changes_keys: StateChangesKeys,
+    bouncer: Bouncer,
+}
+
+impl<S: StateReader + Sync> TransactionExecutor<S> {
+    /// Executes the given transactions concurrently, using the given number of worker threads,
+    /// and commits the successful ones to the state maintained by the executor, in order.
+    /// The results are identical to the ones of executing (and committing) the transactions
+    /// sequentially.
+    pub fn execute_txs_in_parallel(
+        &mut self,
+        txs: &[Transaction],
+        charge_fee: bool,
+        n_workers: usize,
+    ) -> Vec<TransactionExecutorResult<(TransactionExecutionInfo, BouncerInfo, StateAccesses)>>
+    {
+        assert!(
+            self.staged_for_commit_state.is_none(),
+            "Cannot execute transactions while a transaction is staged for commit."
+        );
+
+        let global_contract_cache = self.state.global_class_hash_to_class.clone();
+        // If the state fails to be read during the validation of the batch, execute it
+        // sequentially instead; the error then surfaces in the results of the transactions
+        // reading the failing cells.
+        let execution_outputs = ParallelExecutor::new(
+            self.state.view(),
+            txs,
+            &self.block_context,
+            global_contract_cache,
+            charge_fee,
+        )
+        .run(n_workers)
+        .unwrap_or_default();
+
+        let mut results = Vec::with_capacity(txs.len());
+        for (tx, execution_output) in txs.iter().zip(execution_outputs) {
+            if execution_output.reads_sequencer_balance(&self.block_context) {
+                break;
+            }
+            let result = self.commit_execution_output(tx, execution_output);
+            let is_rejected_by_bouncer = matches!(
+                result,
+                Err(TransactionExecutorError::BlockFull
+                    | TransactionExecutorError::TransactionTooLarge)
+            );
+            results.push(result);
+            if is_rejected_by_bouncer {
+                break;
+            }
+        }
+
+        // The outputs of the transactions following a transaction rejected by the bouncer assume
+        // it was committed, and the ones of transactions reading the sequencer balance miss the
+        // fees of their predecessors; re-execute them (and their successors) sequentially instead.
+        for tx in &txs[results.len()..] {
+            let result = self.execute(tx.clone(), charge_fee);
+            if result.is_ok() {
+                self.commit();
+            }
+            results.push(result);
+        }
+
+        results
+    }
+
+    fn commit_execution_output(
+        &mut self,
+        tx: &Transaction,
+        execution_output: ExecutionTaskOutput,
+    ) -> TransactionExecutorResult<(TransactionExecutionInfo, BouncerInfo, StateAccesses)> {
+        let mut execution_output = execution_output.result?;
+        execution_output.add_fee_to_sequencer_balance(&mut self.state, &self.block_context)?;
+        let ExecutionOutput {
+            tx_execution_info,
+            mut staged_state,
+            state_changes_keys,
+            state_accesses,
+        } = execution_output;
+
+        staged_state.tx_executed_class_hashes = tx_execution_info.get_executed_class_hashes();
+        staged_state.tx_visited_storage_entries = tx_execution_info.get_visited_storage_entries();
+        staged_state.tx_unique_state_changes_keys =
+            state_changes_keys.difference(&self.state_changes_keys);
+        let bouncer_info = self.calculate_bouncer_info(
+            &tx_execution_info,
+            &staged_state,
+            get_l1_handler_payload_size(tx),
+        )?;
+        self.verify_block_capacity(&bouncer_info)?;
+        self.staged_for_commit_state = Some(staged_state);
+        self.staged_bouncer_info = Some(bouncer_info.clone());
+        self.commit();
+
+        Ok((tx_execution_info, bouncer_info, state_accesses))
+    }
+}
+
+fn get_l1_handler_payload_size(tx: &Transaction) -> Option<usize> {
+    match tx {
+        Transaction::L1HandlerTransaction(l1_handler_tx) => Some(l1_handler_tx.payload_size()),
+        Transaction::AccountTransaction(_) | Transaction::DeployTransaction(_) => None,
     }
 }
 
 /// Returns the estimated VM resources for Casm hash calculation (done by the OS), of the newly
 /// executed classes by the current transaction.
-pub fn get_casm_hash_calculation_resources<S: StateReader>(
-    state: &mut TransactionalState<'_, S>,
+pub fn get_casm_hash_calculation_resources(
+    state: &impl StateReader,
     block_executed_class_hashes: &HashSet<ClassHash>,
     tx_executed_class_hashes: &HashSet<ClassHash>,
 ) -> TransactionExecutorResult<ExecutionResources> {
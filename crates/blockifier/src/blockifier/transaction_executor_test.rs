@@ -0,0 +1,247 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::Nonce;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::Fee;
+
+use crate::blockifier::bouncer::{BouncerConfig, BouncerInfo};
+use crate::blockifier::transaction_executor::{
+    TransactionExecutor, TransactionExecutorError, TransactionExecutorResult,
+};
+use crate::context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::cached_state::{CachedState, StateAccesses};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, CairoVersion, NonceManager, BALANCE, MAX_FEE};
+use crate::transaction::constants::TRANSFER_ENTRY_POINT_NAME;
+use crate::transaction::objects::{FeeType, TransactionExecutionInfo};
+use crate::transaction::test_utils::{account_invoke_tx, block_context};
+use crate::transaction::transaction_execution::Transaction;
+
+fn create_state(block_context: &BlockContext) -> CachedState<DictStateReader> {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    test_state(&block_context.chain_info, BALANCE, &[(account, 2), (test_contract, 1)])
+}
+
+/// Returns a batch of invoke transactions, sent by two accounts, that write to partially
+/// overlapping storage keys; the batch includes a transaction with an invalid nonce.
+fn create_txs() -> Vec<Transaction> {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract_address =
+        FeatureContract::TestContract(CairoVersion::Cairo0).get_instance_address(0);
+    let mut nonce_manager = NonceManager::default();
+
+    let mut txs: Vec<Transaction> = (0..8_u8)
+        .map(|i| {
+            let sender_address = account.get_instance_address(i % 2);
+            let calldata = create_calldata(
+                test_contract_address,
+                "test_storage_read_write",
+                &[stark_felt!(i % 3), stark_felt!(i)],
+            );
+            account_invoke_tx(invoke_tx_args! {
+                max_fee: Fee(MAX_FEE),
+                sender_address,
+                calldata,
+                nonce: nonce_manager.next(sender_address),
+            })
+            .into()
+        })
+        .collect();
+
+    let invalid_nonce_tx = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account.get_instance_address(0),
+        calldata: create_calldata(test_contract_address, "test_storage_read_write", &[]),
+        nonce: Nonce(stark_felt!(100_u8)),
+    });
+    txs.insert(3, invalid_nonce_tx.into());
+
+    txs
+}
+
+/// Returns a bouncer configuration limiting only the number of events in a block; each of the
+/// transactions above emits a single event (of its fee transfer).
+fn events_bouncer_config(max_n_events: usize) -> BouncerConfig {
+    BouncerConfig { max_n_events, ..BouncerConfig::max() }
+}
+
+fn unwrap_results(
+    results: Vec<TransactionExecutorResult<(TransactionExecutionInfo, BouncerInfo, StateAccesses)>>,
+) -> Vec<Option<(TransactionExecutionInfo, BouncerInfo, StateAccesses)>> {
+    results.into_iter().map(Result::ok).collect()
+}
+
+#[rstest]
+fn test_parallel_execution_matches_sequential(
+    block_context: BlockContext,
+    #[values(1, 4)] n_workers: usize,
+    #[values(BouncerConfig::max(), events_bouncer_config(5))] bouncer_config: BouncerConfig,
+) {
+    let charge_fee = true;
+
+    let mut sequential_executor = TransactionExecutor::new(
+        create_state(&block_context),
+        block_context.clone(),
+        bouncer_config.clone(),
+    );
+    let sequential_results = create_txs()
+        .into_iter()
+        .map(|tx| {
+            let result = sequential_executor.execute(tx, charge_fee);
+            if result.is_ok() {
+                sequential_executor.commit();
+            }
+            result
+        })
+        .collect();
+
+    let mut parallel_executor = TransactionExecutor::new(
+        create_state(&block_context),
+        block_context.clone(),
+        bouncer_config,
+    );
+    let parallel_results =
+        parallel_executor.execute_txs_in_parallel(&create_txs(), charge_fee, n_workers);
+
+    let sequential_results = unwrap_results(sequential_results);
+    assert!(sequential_results[3].is_none());
+    assert_eq!(unwrap_results(parallel_results), sequential_results);
+    assert_eq!(parallel_executor.bouncer, sequential_executor.bouncer);
+    assert_eq!(parallel_executor.finalize(true), sequential_executor.finalize(true));
+}
+
+#[rstest]
+fn test_parallel_execution_with_sequencer_balance_reads(block_context: BlockContext) {
+    let charge_fee = true;
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let fee_token_address = block_context.chain_info.fee_token_address(&FeeType::Eth);
+    let sequencer_address = block_context.block_info.sequencer_address;
+    let mut nonce_manager = NonceManager::default();
+
+    // The second transfer reads the balance of the sequencer, which misses the fee of the first
+    // transfer until it is committed.
+    let txs: Vec<Transaction> =
+        [(0, stark_felt!(1000_u16)), (1, *sequencer_address.0.key()), (0, stark_felt!(1001_u16))]
+            .into_iter()
+            .map(|(instance_id, recipient)| {
+                let sender_address = account.get_instance_address(instance_id);
+                let calldata = create_calldata(
+                    fee_token_address,
+                    TRANSFER_ENTRY_POINT_NAME,
+                    &[recipient, stark_felt!(1_u8), StarkFelt::ZERO],
+                );
+                account_invoke_tx(invoke_tx_args! {
+                    max_fee: Fee(MAX_FEE),
+                    sender_address,
+                    calldata,
+                    nonce: nonce_manager.next(sender_address),
+                })
+                .into()
+            })
+            .collect();
+
+    let mut sequential_executor = TransactionExecutor::new(
+        create_state(&block_context),
+        block_context.clone(),
+        BouncerConfig::max(),
+    );
+    let sequential_results: Vec<_> = txs
+        .iter()
+        .map(|tx| {
+            let result = sequential_executor.execute(tx.clone(), charge_fee);
+            sequential_executor.commit();
+            result
+        })
+        .collect();
+
+    let mut parallel_executor =
+        TransactionExecutor::new(create_state(&block_context), block_context, BouncerConfig::max());
+    let n_workers = 4;
+    let parallel_results = parallel_executor.execute_txs_in_parallel(&txs, charge_fee, n_workers);
+
+    assert_eq!(unwrap_results(parallel_results), unwrap_results(sequential_results));
+    assert_eq!(parallel_executor.finalize(true), sequential_executor.finalize(true));
+}
+
+#[rstest]
+fn test_rollback_committed_txs(block_context: BlockContext) {
+    let charge_fee = true;
+    let txs = create_txs();
+
+    let mut expected_executor = TransactionExecutor::new(
+        create_state(&block_context),
+        block_context.clone(),
+        BouncerConfig::max(),
+    );
+    let expected_results: Vec<_> = txs[..2]
+        .iter()
+        .map(|tx| {
+            let result = expected_executor.execute(tx.clone(), charge_fee).unwrap();
+            expected_executor.commit();
+            result
+        })
+        .collect();
+
+    let mut executor =
+        TransactionExecutor::new(create_state(&block_context), block_context, BouncerConfig::max());
+    executor.execute(txs[0].clone(), charge_fee).unwrap();
+    executor.commit();
+    let savepoint = executor.checkpoint();
+    for tx in &txs[1..3] {
+        executor.execute(tx.clone(), charge_fee).unwrap();
+        executor.commit();
+    }
+    executor.execute(txs[4].clone(), charge_fee).unwrap();
+
+    // Drop the committed tail, as well as the staged transaction, and re-execute the second one.
+    executor.rollback_to(&savepoint);
+    let result = executor.execute(txs[1].clone(), charge_fee).unwrap();
+    executor.commit();
+
+    assert_eq!(result, expected_results[1]);
+    assert_eq!(executor.bouncer, expected_executor.bouncer);
+    assert_eq!(executor.finalize(true), expected_executor.finalize(true));
+}
+
+#[rstest]
+fn test_block_capacity(block_context: BlockContext) {
+    let charge_fee = true;
+    let txs = create_txs();
+    let mut executor = TransactionExecutor::new(
+        create_state(&block_context),
+        block_context.clone(),
+        events_bouncer_config(2),
+    );
+
+    for tx in &txs[..2] {
+        executor.execute(tx.clone(), charge_fee).unwrap();
+        executor.commit();
+    }
+    let state_diff_before_rejection = executor.state.to_state_diff();
+    assert_matches!(
+        executor.execute(txs[2].clone(), charge_fee),
+        Err(TransactionExecutorError::BlockFull)
+    );
+
+    // The rejected transaction is neither staged nor counted.
+    assert!(executor.staged_for_commit_state.is_none());
+    assert_eq!(executor.bouncer.accumulated_weights().n_events, 2);
+    assert_eq!(executor.state.to_state_diff(), state_diff_before_rejection);
+
+    // A transaction that does not fit into an empty block is rejected regardless.
+    let mut executor = TransactionExecutor::new(
+        create_state(&block_context),
+        block_context,
+        events_bouncer_config(0),
+    );
+    assert_matches!(
+        executor.execute(txs[0].clone(), charge_fee),
+        Err(TransactionExecutorError::TransactionTooLarge)
+    );
+}
@@ -0,0 +1,4 @@
+pub mod block_hash;
+pub mod errors;
+pub mod global_state;
+pub mod patricia_tree;
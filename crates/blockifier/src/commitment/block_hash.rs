@@ -0,0 +1,184 @@
+use indexmap::IndexMap;
+use starknet_api::block::BlockHash;
+use starknet_api::core::{ClassHash, EventCommitment, GlobalRoot, TransactionCommitment};
+use starknet_api::hash::{pedersen_hash, pedersen_hash_array, poseidon_hash_array, StarkFelt};
+use starknet_api::transaction::{TransactionHash, TransactionSignature};
+
+use crate::blockifier::block::BlockInfo;
+use crate::commitment::patricia_tree::{compute_root_hash, PedersenHashFunction};
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionInfoCreator};
+use crate::transaction::receipt::{ordered_events, Event};
+use crate::transaction::transaction_execution::Transaction;
+use crate::utils::{short_string, usize_to_felt};
+
+#[cfg(test)]
+#[path = "block_hash_test.rs"]
+pub mod test;
+
+/// The height of the transaction and event commitment trees; i.e., the number of bits in a
+/// transaction or event index.
+pub const COMMITMENT_TREE_HEIGHT: u8 = 64;
+pub const STATE_DIFF_HASH_VERSION: StarkFelt = short_string(b"STARKNET_STATE_DIFF0");
+
+/// The data of an executed transaction that is committed to by its block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionHashingData {
+    pub transaction_hash: TransactionHash,
+    pub transaction_signature: TransactionSignature,
+    /// The events emitted by the transaction, in emission order.
+    pub events: Vec<Event>,
+}
+
+impl TransactionHashingData {
+    pub fn new(tx: &Transaction, tx_execution_info: &TransactionExecutionInfo) -> Self {
+        let tx_info = tx.create_tx_info();
+        Self {
+            transaction_hash: tx_info.transaction_hash(),
+            transaction_signature: tx_info.signature(),
+            events: tx_execution_info.non_optional_call_infos().flat_map(ordered_events).collect(),
+        }
+    }
+}
+
+/// The commitments of a block to its transactions, events and state diff.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockCommitments {
+    pub n_txs: usize,
+    pub transaction_commitment: TransactionCommitment,
+    pub n_events: usize,
+    pub event_commitment: EventCommitment,
+    pub state_diff_commitment: StarkFelt,
+}
+
+impl BlockCommitments {
+    /// Computes the commitments of a block with the given transactions, in execution order, and
+    /// state diff. The classes declared by `Declare` transactions of versions 0 and 1 have no
+    /// compiled class hash, hence are not part of the state diff, and are given separately.
+    pub fn new(
+        txs: &[TransactionHashingData],
+        state_diff: &CommitmentStateDiff,
+        deprecated_declared_classes: &[ClassHash],
+    ) -> Self {
+        let tx_leaves = txs.iter().map(|tx| {
+            pedersen_hash(&tx.transaction_hash.0, &pedersen_hash_array(&tx.transaction_signature.0))
+        });
+        let event_leaves: Vec<StarkFelt> = txs
+            .iter()
+            .flat_map(|tx| &tx.events)
+            .map(|event| {
+                pedersen_hash_array(&[
+                    *event.from_address.0.key(),
+                    pedersen_hash_array(
+                        &event.content.keys.iter().map(|key| key.0).collect::<Vec<_>>(),
+                    ),
+                    pedersen_hash_array(&event.content.data.0),
+                ])
+            })
+            .collect();
+
+        Self {
+            n_txs: txs.len(),
+            transaction_commitment: TransactionCommitment(commitment_tree_root(tx_leaves)),
+            n_events: event_leaves.len(),
+            event_commitment: EventCommitment(commitment_tree_root(event_leaves)),
+            state_diff_commitment: calculate_state_diff_commitment(
+                state_diff,
+                deprecated_declared_classes,
+            ),
+        }
+    }
+}
+
+/// Computes the hash of a block with the given header fields and commitments, as defined up to
+/// Starknet 0.13.1; the state diff commitment is not part of it.
+pub fn calculate_block_hash(
+    block_info: &BlockInfo,
+    state_root: GlobalRoot,
+    parent_block_hash: BlockHash,
+    commitments: &BlockCommitments,
+) -> BlockHash {
+    BlockHash(pedersen_hash_array(&[
+        StarkFelt::from(block_info.block_number.0),
+        state_root.0,
+        *block_info.sequencer_address.0.key(),
+        StarkFelt::from(block_info.block_timestamp.0),
+        usize_to_felt(commitments.n_txs),
+        commitments.transaction_commitment.0,
+        usize_to_felt(commitments.n_events),
+        commitments.event_commitment.0,
+        // Protocol version.
+        StarkFelt::ZERO,
+        // Extra data.
+        StarkFelt::ZERO,
+        parent_block_hash.0,
+    ]))
+}
+
+/// Computes the Poseidon hash of the given state diff; all entries are hashed in ascending order
+/// of their keys.
+pub fn calculate_state_diff_commitment(
+    state_diff: &CommitmentStateDiff,
+    deprecated_declared_classes: &[ClassHash],
+) -> StarkFelt {
+    let mut hash_chain = vec![STATE_DIFF_HASH_VERSION];
+
+    // Deployed contracts and replaced classes.
+    extend_with_sorted_entries(
+        &mut hash_chain,
+        &state_diff.address_to_class_hash,
+        |address, class_hash| [*address.0.key(), class_hash.0],
+    );
+    extend_with_sorted_entries(
+        &mut hash_chain,
+        &state_diff.class_hash_to_compiled_class_hash,
+        |class_hash, compiled_class_hash| [class_hash.0, compiled_class_hash.0],
+    );
+    let mut deprecated_declared_classes = deprecated_declared_classes.to_vec();
+    deprecated_declared_classes.sort();
+    deprecated_declared_classes.dedup();
+    hash_chain.push(usize_to_felt(deprecated_declared_classes.len()));
+    hash_chain.extend(deprecated_declared_classes.iter().map(|class_hash| class_hash.0));
+
+    // Placeholders for the data availability modes.
+    hash_chain.extend([StarkFelt::ONE, StarkFelt::ZERO]);
+
+    let mut storage_updates: Vec<_> = state_diff
+        .storage_updates
+        .iter()
+        .filter(|(_, contract_storage_updates)| !contract_storage_updates.is_empty())
+        .collect();
+    storage_updates.sort_by_key(|(address, _)| **address);
+    hash_chain.push(usize_to_felt(storage_updates.len()));
+    for (address, contract_storage_updates) in storage_updates {
+        hash_chain.push(*address.0.key());
+        extend_with_sorted_entries(&mut hash_chain, contract_storage_updates, |key, value| {
+            [*key.0.key(), *value]
+        });
+    }
+
+    extend_with_sorted_entries(&mut hash_chain, &state_diff.address_to_nonce, |address, nonce| {
+        [*address.0.key(), nonce.0]
+    });
+
+    poseidon_hash_array(&hash_chain).0
+}
+
+/// Returns the root of a Pedersen commitment tree, whose leaves are the given values, by order.
+fn commitment_tree_root(leaves: impl IntoIterator<Item = StarkFelt>) -> StarkFelt {
+    let leaves = leaves.into_iter().enumerate().map(|(index, leaf)| (usize_to_felt(index), leaf));
+    compute_root_hash::<PedersenHashFunction>(COMMITMENT_TREE_HEIGHT, leaves)
+}
+
+/// Appends the number of entries in the given map, followed by its entries sorted by key.
+fn extend_with_sorted_entries<K: Ord, V>(
+    hash_chain: &mut Vec<StarkFelt>,
+    map: &IndexMap<K, V>,
+    entry_to_felts: impl Fn(&K, &V) -> [StarkFelt; 2],
+) {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by_key(|(key, _)| *key);
+
+    hash_chain.push(usize_to_felt(entries.len()));
+    hash_chain.extend(entries.into_iter().flat_map(|(key, value)| entry_to_felts(key, value)));
+}
@@ -0,0 +1,209 @@
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::block::{BlockHash, BlockNumber, BlockTimestamp};
+use starknet_api::core::{
+    ClassHash, CompiledClassHash, ContractAddress, GlobalRoot, Nonce, PatriciaKey,
+};
+use starknet_api::hash::{
+    pedersen_hash, pedersen_hash_array, poseidon_hash_array, StarkFelt, StarkHash,
+};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{
+    EventContent, EventData, EventKey, TransactionHash, TransactionSignature,
+};
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+use starknet_crypto::FieldElement;
+
+use crate::blockifier::block::BlockInfo;
+use crate::commitment::block_hash::{
+    calculate_block_hash, calculate_state_diff_commitment, BlockCommitments,
+    TransactionHashingData, COMMITMENT_TREE_HEIGHT, STATE_DIFF_HASH_VERSION,
+};
+use crate::context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::test_utils::{create_calldata, CairoVersion};
+use crate::transaction::receipt::Event;
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, create_test_init_data, TestInitData,
+};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
+
+fn edge_hash(child: StarkFelt, path: u8, length: u8) -> StarkFelt {
+    (FieldElement::from(pedersen_hash(&child, &stark_felt!(path))) + FieldElement::from(length))
+        .into()
+}
+
+#[test]
+fn test_empty_block_commitments() {
+    let commitments = BlockCommitments::new(&[], &CommitmentStateDiff::default(), &[]);
+
+    assert_eq!(commitments.n_txs, 0);
+    assert_eq!(commitments.transaction_commitment.0, StarkFelt::ZERO);
+    assert_eq!(commitments.n_events, 0);
+    assert_eq!(commitments.event_commitment.0, StarkFelt::ZERO);
+    // The version, the counts of the (empty) sections, and the data availability placeholders.
+    let [zero, one] = [StarkFelt::ZERO, StarkFelt::ONE];
+    assert_eq!(
+        commitments.state_diff_commitment,
+        poseidon_hash_array(&[STATE_DIFF_HASH_VERSION, zero, zero, zero, one, zero, zero, zero]).0
+    );
+}
+
+#[test]
+fn test_transaction_and_event_commitments() {
+    let event = Event {
+        from_address: contract_address!("0x100"),
+        content: EventContent {
+            keys: vec![EventKey(stark_felt!(1_u8))],
+            data: EventData(vec![stark_felt!(2_u8), stark_felt!(3_u8)]),
+        },
+    };
+    let txs = [
+        TransactionHashingData {
+            transaction_hash: TransactionHash(stark_felt!("0xaa")),
+            transaction_signature: TransactionSignature::default(),
+            events: vec![],
+        },
+        TransactionHashingData {
+            transaction_hash: TransactionHash(stark_felt!("0xbb")),
+            transaction_signature: TransactionSignature(vec![stark_felt!(4_u8), stark_felt!(5_u8)]),
+            events: vec![event],
+        },
+    ];
+    let commitments = BlockCommitments::new(&txs, &CommitmentStateDiff::default(), &[]);
+
+    // Sibling leaves at indices 0 and 1, under an edge from the root.
+    let tx_leaves = [
+        pedersen_hash(&stark_felt!("0xaa"), &pedersen_hash_array(&[])),
+        pedersen_hash(
+            &stark_felt!("0xbb"),
+            &pedersen_hash_array(&[stark_felt!(4_u8), stark_felt!(5_u8)]),
+        ),
+    ];
+    assert_eq!(commitments.n_txs, 2);
+    assert_eq!(
+        commitments.transaction_commitment.0,
+        edge_hash(pedersen_hash(&tx_leaves[0], &tx_leaves[1]), 0, COMMITMENT_TREE_HEIGHT - 1)
+    );
+
+    // A single leaf at index 0.
+    let event_leaf = pedersen_hash_array(&[
+        stark_felt!("0x100"),
+        pedersen_hash_array(&[stark_felt!(1_u8)]),
+        pedersen_hash_array(&[stark_felt!(2_u8), stark_felt!(3_u8)]),
+    ]);
+    assert_eq!(commitments.n_events, 1);
+    assert_eq!(commitments.event_commitment.0, edge_hash(event_leaf, 0, COMMITMENT_TREE_HEIGHT));
+}
+
+#[test]
+fn test_state_diff_commitment() {
+    let (address_0, address_1) = (contract_address!("0x10"), contract_address!("0x20"));
+    let (class_hash_0, class_hash_1) = (class_hash!("0x30"), class_hash!("0x40"));
+    let storage_key = StorageKey(patricia_key!("0x50"));
+    let state_diff = CommitmentStateDiff {
+        address_to_class_hash: IndexMap::from([
+            (address_1, class_hash_1),
+            (address_0, class_hash_0),
+        ]),
+        address_to_nonce: IndexMap::from([(address_0, Nonce(stark_felt!(1_u8)))]),
+        storage_updates: IndexMap::from([
+            (address_1, IndexMap::from([(storage_key, stark_felt!(2_u8))])),
+            (address_0, IndexMap::new()),
+        ]),
+        class_hash_to_compiled_class_hash: IndexMap::from([(
+            class_hash_1,
+            CompiledClassHash(stark_felt!("0x60")),
+        )]),
+    };
+
+    // Entries are hashed by ascending order of keys; contracts with no storage updates are
+    // omitted.
+    let expected_hash_chain = [
+        STATE_DIFF_HASH_VERSION,
+        // Deployed contracts and replaced classes.
+        stark_felt!(2_u8),
+        stark_felt!("0x10"),
+        stark_felt!("0x30"),
+        stark_felt!("0x20"),
+        stark_felt!("0x40"),
+        // Declared classes.
+        stark_felt!(1_u8),
+        stark_felt!("0x40"),
+        stark_felt!("0x60"),
+        // Deprecated declared classes.
+        stark_felt!(1_u8),
+        stark_felt!("0x70"),
+        // Data availability placeholders.
+        StarkFelt::ONE,
+        StarkFelt::ZERO,
+        // Storage updates.
+        stark_felt!(1_u8),
+        stark_felt!("0x20"),
+        stark_felt!(1_u8),
+        stark_felt!("0x50"),
+        stark_felt!(2_u8),
+        // Nonces.
+        stark_felt!(1_u8),
+        stark_felt!("0x10"),
+        stark_felt!(1_u8),
+    ];
+    assert_eq!(
+        calculate_state_diff_commitment(&state_diff, &[class_hash!("0x70"), class_hash!("0x70")]),
+        poseidon_hash_array(&expected_hash_chain).0
+    );
+}
+
+// TODO: Add a pre-0.13.2 block recorded from mainnet, to check the hash and the commitments
+// computed here against its known ones.
+#[rstest]
+fn test_block_hash(block_context: BlockContext) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        sender_address: account_address,
+        calldata: create_calldata(
+            contract_address,
+            "test_emit_events",
+            &[stark_felt!(1_u8), stark_felt!(0_u8), stark_felt!(0_u8)],
+        ),
+        signature: TransactionSignature(vec![stark_felt!(7_u8)]),
+        nonce: nonce_manager.next(account_address),
+    }));
+    let tx_execution_info = tx.clone().execute(&mut state, &block_context, false, true).unwrap();
+    let tx_hashing_data = TransactionHashingData::new(&tx, &tx_execution_info);
+    assert_eq!(tx_hashing_data.transaction_signature.0, vec![stark_felt!(7_u8)]);
+    assert_eq!(tx_hashing_data.events.len(), 1);
+    assert_eq!(tx_hashing_data.events[0].from_address, contract_address);
+
+    let commitments =
+        BlockCommitments::new(&[tx_hashing_data], &state.to_state_diff(), &[ClassHash::default()]);
+    let block_info = BlockInfo {
+        block_number: BlockNumber(7),
+        block_timestamp: BlockTimestamp(8),
+        ..block_context.block_info
+    };
+    let state_root = GlobalRoot(stark_felt!("0x123"));
+    let parent_block_hash = BlockHash(stark_felt!("0x456"));
+
+    let expected_block_hash = pedersen_hash_array(&[
+        stark_felt!(7_u8),
+        state_root.0,
+        *block_info.sequencer_address.0.key(),
+        stark_felt!(8_u8),
+        stark_felt!(1_u8),
+        commitments.transaction_commitment.0,
+        stark_felt!(1_u8),
+        commitments.event_commitment.0,
+        StarkFelt::ZERO,
+        StarkFelt::ZERO,
+        parent_block_hash.0,
+    ]);
+    assert_eq!(
+        calculate_block_hash(&block_info, state_root, parent_block_hash, &commitments),
+        BlockHash(expected_block_hash)
+    );
+}
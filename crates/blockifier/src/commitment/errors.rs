@@ -0,0 +1,21 @@
+use starknet_api::core::GlobalRoot;
+use starknet_api::hash::StarkFelt;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ProofVerificationError {
+    #[error("Global root mismatch: expected {expected:?}, computed {actual:?}.")]
+    GlobalRootMismatch { expected: GlobalRoot, actual: GlobalRoot },
+    #[error("Proof is incomplete; it ends at height {height}.")]
+    IncompleteProof { height: u8 },
+    #[error("Invalid edge of length {length} at height {height}.")]
+    InvalidEdgeLength { height: u8, length: u8 },
+    #[error("Leaf value mismatch: expected {expected:?}, proven {actual:?}.")]
+    LeafValueMismatch { expected: StarkFelt, actual: StarkFelt },
+    #[error("Node hash mismatch at height {height}: expected {expected:?}, computed {actual:?}.")]
+    NodeHashMismatch { height: u8, expected: StarkFelt, actual: StarkFelt },
+    #[error("Proof contains nodes beyond the proven leaf.")]
+    RedundantProofNodes,
+}
+
+pub type ProofVerificationResult<T> = Result<T, ProofVerificationError>;
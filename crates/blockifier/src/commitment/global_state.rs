@@ -0,0 +1,243 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, GlobalRoot, Nonce};
+use starknet_api::hash::{pedersen_hash, poseidon_hash_array, StarkFelt};
+use starknet_api::state::StorageKey;
+
+use crate::commitment::errors::{ProofVerificationError, ProofVerificationResult};
+use crate::commitment::patricia_tree::{
+    verify_proof, PatriciaTree, PedersenHashFunction, PoseidonHashFunction, ProofNode,
+    TreeHashFunction,
+};
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::utils::short_string;
+
+#[cfg(test)]
+#[path = "global_state_test.rs"]
+pub mod test;
+
+pub const CONTRACT_STATE_HASH_VERSION: StarkFelt = StarkFelt::ZERO;
+pub const CONTRACT_CLASS_LEAF_VERSION: StarkFelt = short_string(b"CONTRACT_CLASS_LEAF_V0");
+pub const GLOBAL_STATE_VERSION: StarkFelt = short_string(b"STARKNET_STATE_V0");
+
+pub type StorageTrie = PatriciaTree<PedersenHashFunction>;
+pub type ContractsTrie = PatriciaTree<PedersenHashFunction>;
+pub type ClassesTrie = PatriciaTree<PoseidonHashFunction>;
+
+/// The state of a contract instance, as committed to by its leaf in the contracts trie.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ContractState {
+    pub class_hash: ClassHash,
+    pub nonce: Nonce,
+    pub storage_trie: StorageTrie,
+}
+
+impl ContractState {
+    pub fn hash(&self) -> StarkFelt {
+        self.leaf().hash()
+    }
+
+    pub fn leaf(&self) -> ContractLeaf {
+        ContractLeaf {
+            class_hash: self.class_hash,
+            nonce: self.nonce,
+            storage_root: self.storage_trie.root_hash(),
+        }
+    }
+}
+
+/// The preimage of a leaf in the contracts trie.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ContractLeaf {
+    pub class_hash: ClassHash,
+    pub nonce: Nonce,
+    pub storage_root: StarkFelt,
+}
+
+impl ContractLeaf {
+    pub fn hash(&self) -> StarkFelt {
+        let hash = pedersen_hash(&self.class_hash.0, &self.storage_root);
+        let hash = pedersen_hash(&hash, &self.nonce.0);
+        pedersen_hash(&hash, &CONTRACT_STATE_HASH_VERSION)
+    }
+}
+
+/// A proof of the leaf of a contract in the contracts trie, w.r.t. the global root.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ContractProof {
+    pub contracts_trie_root: StarkFelt,
+    pub classes_trie_root: StarkFelt,
+    /// `None` if the contract has no leaf; i.e., for a non-membership proof.
+    pub contract_leaf: Option<ContractLeaf>,
+    pub contract_leaf_proof: Vec<ProofNode>,
+}
+
+impl ContractProof {
+    /// Verifies the proof of the leaf of the given contract, against the given global root.
+    pub fn verify(
+        &self,
+        global_root: GlobalRoot,
+        contract_address: ContractAddress,
+    ) -> ProofVerificationResult<()> {
+        let proven_global_root =
+            compute_global_root(self.contracts_trie_root, self.classes_trie_root);
+        if proven_global_root != global_root {
+            return Err(ProofVerificationError::GlobalRootMismatch {
+                expected: global_root,
+                actual: proven_global_root,
+            });
+        }
+
+        verify_proof::<PedersenHashFunction>(
+            self.contracts_trie_root,
+            *contract_address.0.key(),
+            self.contract_leaf.map(|contract_leaf| contract_leaf.hash()).unwrap_or_default(),
+            &self.contract_leaf_proof,
+        )
+    }
+}
+
+/// A proof of a storage value of a contract, w.r.t. the global root.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StorageProof {
+    pub contract_proof: ContractProof,
+    pub value: StarkFelt,
+    pub storage_proof: Vec<ProofNode>,
+}
+
+impl StorageProof {
+    /// Verifies the proof of the value at the given storage entry, against the given global root.
+    pub fn verify(
+        &self,
+        global_root: GlobalRoot,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> ProofVerificationResult<()> {
+        self.contract_proof.verify(global_root, contract_address)?;
+        let storage_root = self
+            .contract_proof
+            .contract_leaf
+            .map(|contract_leaf| contract_leaf.storage_root)
+            .unwrap_or_default();
+
+        verify_proof::<PedersenHashFunction>(
+            storage_root,
+            *key.0.key(),
+            self.value,
+            &self.storage_proof,
+        )
+    }
+}
+
+/// The tries committing to the Starknet global state: the contracts trie, whose leaves commit to
+/// the contract states (including their storage tries), and the classes trie, whose leaves commit
+/// to the compiled class hashes of the declared (Cairo 1) classes.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GlobalStateTries {
+    contract_states: HashMap<ContractAddress, ContractState>,
+    contracts_trie: ContractsTrie,
+    classes_trie: ClassesTrie,
+}
+
+impl GlobalStateTries {
+    pub fn global_root(&self) -> GlobalRoot {
+        compute_global_root(self.contracts_trie.root_hash(), self.classes_trie.root_hash())
+    }
+
+    /// Returns a proof of the leaf of the given contract (or of its absence).
+    pub fn get_contract_proof(&self, contract_address: ContractAddress) -> ContractProof {
+        ContractProof {
+            contracts_trie_root: self.contracts_trie.root_hash(),
+            classes_trie_root: self.classes_trie.root_hash(),
+            contract_leaf: self.contract_state(contract_address).map(ContractState::leaf),
+            contract_leaf_proof: self.contracts_trie.get_proof(*contract_address.0.key()),
+        }
+    }
+
+    /// Returns a proof of the value at the given storage entry.
+    pub fn get_storage_proof(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StorageProof {
+        let (value, storage_proof) = match self.contract_state(contract_address) {
+            Some(contract_state) => (
+                contract_state.storage_trie.get(*key.0.key()),
+                contract_state.storage_trie.get_proof(*key.0.key()),
+            ),
+            None => (StarkFelt::default(), Vec::new()),
+        };
+
+        StorageProof {
+            contract_proof: self.get_contract_proof(contract_address),
+            value,
+            storage_proof,
+        }
+    }
+
+    pub fn contract_state(&self, contract_address: ContractAddress) -> Option<&ContractState> {
+        self.contract_states.get(&contract_address)
+    }
+
+    pub fn contracts_trie(&self) -> &ContractsTrie {
+        &self.contracts_trie
+    }
+
+    pub fn classes_trie(&self) -> &ClassesTrie {
+        &self.classes_trie
+    }
+
+    /// Applies the given state diff to the tries, and returns the new global root.
+    pub fn apply_state_diff(&mut self, state_diff: &CommitmentStateDiff) -> GlobalRoot {
+        let modified_contracts: HashSet<ContractAddress> = state_diff
+            .address_to_class_hash
+            .keys()
+            .chain(state_diff.address_to_nonce.keys())
+            .chain(state_diff.storage_updates.keys())
+            .copied()
+            .collect();
+
+        let mut contract_leaf_modifications = Vec::with_capacity(modified_contracts.len());
+        for contract_address in modified_contracts {
+            let contract_state = self.contract_states.entry(contract_address).or_default();
+            if let Some(class_hash) = state_diff.address_to_class_hash.get(&contract_address) {
+                contract_state.class_hash = *class_hash;
+            }
+            if let Some(nonce) = state_diff.address_to_nonce.get(&contract_address) {
+                contract_state.nonce = *nonce;
+            }
+            if let Some(storage_updates) = state_diff.storage_updates.get(&contract_address) {
+                contract_state
+                    .storage_trie
+                    .update(storage_updates.iter().map(|(key, value)| (*key.0.key(), *value)));
+            }
+
+            contract_leaf_modifications.push((*contract_address.0.key(), contract_state.hash()));
+        }
+        self.contracts_trie.update(contract_leaf_modifications);
+
+        self.classes_trie.update(state_diff.class_hash_to_compiled_class_hash.iter().map(
+            |(class_hash, compiled_class_hash)| {
+                (class_hash.0, class_leaf_hash(compiled_class_hash))
+            },
+        ));
+
+        self.global_root()
+    }
+}
+
+fn compute_global_root(contracts_trie_root: StarkFelt, classes_trie_root: StarkFelt) -> GlobalRoot {
+    // The classes trie is omitted from the commitment as long as it is empty.
+    if classes_trie_root == StarkFelt::default() {
+        return GlobalRoot(contracts_trie_root);
+    }
+
+    GlobalRoot(
+        poseidon_hash_array(&[GLOBAL_STATE_VERSION, contracts_trie_root, classes_trie_root]).0,
+    )
+}
+
+pub fn class_leaf_hash(compiled_class_hash: &CompiledClassHash) -> StarkFelt {
+    PoseidonHashFunction::hash(&CONTRACT_CLASS_LEAF_VERSION, &compiled_class_hash.0)
+}
@@ -0,0 +1,200 @@
+use assert_matches::assert_matches;
+use indexmap::{indexmap, IndexMap};
+use pretty_assertions::assert_eq;
+use starknet_api::core::{
+    ClassHash, CompiledClassHash, ContractAddress, GlobalRoot, Nonce, PatriciaKey,
+};
+use starknet_api::hash::{pedersen_hash, poseidon_hash_array, StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+use starknet_crypto::FieldElement;
+
+use crate::commitment::errors::ProofVerificationError;
+use crate::commitment::global_state::{
+    class_leaf_hash, ContractLeaf, ContractState, GlobalStateTries, CONTRACT_CLASS_LEAF_VERSION,
+    GLOBAL_STATE_VERSION,
+};
+use crate::commitment::patricia_tree::{PatriciaTree, PedersenHashFunction};
+use crate::state::cached_state::CommitmentStateDiff;
+
+fn state_diff(
+    address_to_class_hash: IndexMap<ContractAddress, ClassHash>,
+    address_to_nonce: IndexMap<ContractAddress, Nonce>,
+    storage_updates: IndexMap<ContractAddress, IndexMap<StorageKey, StarkFelt>>,
+    class_hash_to_compiled_class_hash: IndexMap<ClassHash, CompiledClassHash>,
+) -> CommitmentStateDiff {
+    CommitmentStateDiff {
+        address_to_class_hash,
+        address_to_nonce,
+        storage_updates,
+        class_hash_to_compiled_class_hash,
+    }
+}
+
+#[test]
+fn test_versions() {
+    assert_eq!(
+        CONTRACT_CLASS_LEAF_VERSION,
+        FieldElement::from_byte_slice_be(b"CONTRACT_CLASS_LEAF_V0").unwrap().into()
+    );
+    assert_eq!(
+        GLOBAL_STATE_VERSION,
+        FieldElement::from_byte_slice_be(b"STARKNET_STATE_V0").unwrap().into()
+    );
+}
+
+#[test]
+fn test_global_root() {
+    let mut tries = GlobalStateTries::default();
+    assert_eq!(tries.global_root(), GlobalRoot::default());
+
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let class_hash = class_hash!("0x300");
+    let compiled_class_hash = CompiledClassHash(stark_felt!("0x301"));
+
+    let contracts_only_root = tries.apply_state_diff(&state_diff(
+        indexmap! { contract_address => class_hash },
+        indexmap! { contract_address => Nonce(stark_felt!("0x1")) },
+        indexmap! { contract_address => indexmap! { key => stark_felt!("0x5") } },
+        IndexMap::new(),
+    ));
+
+    let mut storage_trie = PatriciaTree::<PedersenHashFunction>::default();
+    storage_trie.update([(*key.0.key(), stark_felt!("0x5"))]);
+    let expected_contract_state =
+        ContractState { class_hash, nonce: Nonce(stark_felt!("0x1")), storage_trie };
+    assert_eq!(tries.contract_state(contract_address), Some(&expected_contract_state));
+
+    let mut expected_contracts_trie = PatriciaTree::<PedersenHashFunction>::default();
+    expected_contracts_trie.update([(*contract_address.0.key(), expected_contract_state.hash())]);
+    assert_eq!(contracts_only_root, GlobalRoot(expected_contracts_trie.root_hash()));
+
+    // Once a class is declared, the global root commits to both tries.
+    let global_root = tries.apply_state_diff(&state_diff(
+        IndexMap::new(),
+        IndexMap::new(),
+        IndexMap::new(),
+        indexmap! { class_hash => compiled_class_hash },
+    ));
+    assert_eq!(tries.classes_trie().get(class_hash.0), class_leaf_hash(&compiled_class_hash));
+    let expected_global_root = poseidon_hash_array(&[
+        GLOBAL_STATE_VERSION,
+        expected_contracts_trie.root_hash(),
+        tries.classes_trie().root_hash(),
+    ])
+    .0;
+    assert_eq!(global_root, GlobalRoot(expected_global_root));
+}
+
+#[test]
+fn test_contract_state_hash() {
+    let contract_state = ContractState {
+        class_hash: class_hash!("0x1"),
+        nonce: Nonce(stark_felt!("0x2")),
+        ..Default::default()
+    };
+    let expected_hash = pedersen_hash(
+        &pedersen_hash(
+            &pedersen_hash(&stark_felt!("0x1"), &StarkFelt::default()),
+            &stark_felt!("0x2"),
+        ),
+        &StarkFelt::default(),
+    );
+    assert_eq!(contract_state.hash(), expected_hash);
+}
+
+#[test]
+fn test_apply_state_diffs_incrementally() {
+    let contract_address0 = contract_address!("0x100");
+    let contract_address1 = contract_address!("0x200");
+    let key0 = StorageKey(patricia_key!("0x10"));
+    let key1 = StorageKey(patricia_key!("0x11"));
+
+    let first_diff = state_diff(
+        indexmap! { contract_address0 => class_hash!("0x1") },
+        IndexMap::new(),
+        indexmap! { contract_address0 => indexmap! { key0 => stark_felt!("0x1") } },
+        indexmap! { class_hash!("0x1") => CompiledClassHash(stark_felt!("0x2")) },
+    );
+    let second_diff = state_diff(
+        indexmap! { contract_address1 => class_hash!("0x1") },
+        indexmap! { contract_address0 => Nonce(stark_felt!("0x1")) },
+        indexmap! {
+            contract_address0 => indexmap! { key0 => StarkFelt::default(), key1 => stark_felt!("0x3") },
+        },
+        IndexMap::new(),
+    );
+    let merged_diff = state_diff(
+        indexmap! { contract_address0 => class_hash!("0x1"), contract_address1 => class_hash!("0x1") },
+        indexmap! { contract_address0 => Nonce(stark_felt!("0x1")) },
+        indexmap! { contract_address0 => indexmap! { key1 => stark_felt!("0x3") } },
+        indexmap! { class_hash!("0x1") => CompiledClassHash(stark_felt!("0x2")) },
+    );
+
+    let mut tries = GlobalStateTries::default();
+    tries.apply_state_diff(&first_diff);
+    let global_root = tries.apply_state_diff(&second_diff);
+
+    let mut expected_tries = GlobalStateTries::default();
+    assert_eq!(expected_tries.apply_state_diff(&merged_diff), global_root);
+    assert_eq!(tries, expected_tries);
+
+    let serialized_tries = serde_json::to_string(&tries).unwrap();
+    let deserialized_tries: GlobalStateTries = serde_json::from_str(&serialized_tries).unwrap();
+    assert_eq!(deserialized_tries, tries);
+}
+
+#[test]
+fn test_storage_proofs() {
+    let contract_address = contract_address!("0x100");
+    let undeployed_contract_address = contract_address!("0x101");
+    let key = StorageKey(patricia_key!("0x10"));
+    let empty_key = StorageKey(patricia_key!("0x11"));
+
+    let mut tries = GlobalStateTries::default();
+    let global_root = tries.apply_state_diff(&state_diff(
+        indexmap! { contract_address => class_hash!("0x1") },
+        indexmap! { contract_address => Nonce(stark_felt!("0x2")) },
+        indexmap! { contract_address => indexmap! { key => stark_felt!("0x5") } },
+        indexmap! { class_hash!("0x1") => CompiledClassHash(stark_felt!("0x3")) },
+    ));
+
+    let storage_proof = tries.get_storage_proof(contract_address, key);
+    assert_eq!(storage_proof.value, stark_felt!("0x5"));
+    assert_eq!(
+        storage_proof.contract_proof.contract_leaf,
+        Some(ContractLeaf {
+            class_hash: class_hash!("0x1"),
+            nonce: Nonce(stark_felt!("0x2")),
+            storage_root: tries.contract_state(contract_address).unwrap().storage_trie.root_hash(),
+        })
+    );
+    storage_proof.verify(global_root, contract_address, key).unwrap();
+
+    // Non-membership proofs.
+    let empty_storage_proof = tries.get_storage_proof(contract_address, empty_key);
+    assert_eq!(empty_storage_proof.value, StarkFelt::default());
+    empty_storage_proof.verify(global_root, contract_address, empty_key).unwrap();
+
+    let undeployed_contract_proof = tries.get_storage_proof(undeployed_contract_address, key);
+    assert_eq!(undeployed_contract_proof.contract_proof.contract_leaf, None);
+    undeployed_contract_proof.verify(global_root, undeployed_contract_address, key).unwrap();
+
+    // Invalid proofs; the proof of a storage entry shows that the other entry is empty.
+    assert_matches!(
+        storage_proof.verify(global_root, contract_address, empty_key),
+        Err(ProofVerificationError::LeafValueMismatch { .. })
+    );
+    assert_matches!(
+        storage_proof.verify(GlobalRoot(stark_felt!("0x7")), contract_address, key),
+        Err(ProofVerificationError::GlobalRootMismatch { .. })
+    );
+
+    let mut tampered_proof = storage_proof.clone();
+    tampered_proof.contract_proof.contract_leaf.as_mut().unwrap().nonce = Nonce(stark_felt!("0x3"));
+    assert_matches!(
+        tampered_proof.verify(global_root, contract_address, key),
+        Err(ProofVerificationError::LeafValueMismatch { .. })
+    );
+}
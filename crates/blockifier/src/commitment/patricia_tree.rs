@@ -0,0 +1,386 @@
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+use cairo_felt::Felt252;
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use starknet_api::hash::{pedersen_hash, StarkFelt};
+use starknet_crypto::{poseidon_hash, FieldElement};
+
+use crate::commitment::errors::{ProofVerificationError, ProofVerificationResult};
+use crate::execution::execution_utils::felt_to_stark_felt;
+
+#[cfg(test)]
+#[path = "patricia_tree_test.rs"]
+pub mod test;
+
+/// The height of the Starknet Patricia-Merkle trees; i.e., the number of bits in a leaf index.
+pub const TREE_HEIGHT: u8 = 251;
+
+/// The hash function used to compute the hashes of the inner nodes of a tree.
+pub trait TreeHashFunction {
+    fn hash(left: &StarkFelt, right: &StarkFelt) -> StarkFelt;
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PedersenHashFunction;
+
+impl TreeHashFunction for PedersenHashFunction {
+    fn hash(left: &StarkFelt, right: &StarkFelt) -> StarkFelt {
+        pedersen_hash(left, right)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PoseidonHashFunction;
+
+impl TreeHashFunction for PoseidonHashFunction {
+    fn hash(left: &StarkFelt, right: &StarkFelt) -> StarkFelt {
+        poseidon_hash(FieldElement::from(*left), FieldElement::from(*right)).into()
+    }
+}
+
+/// A node of a non-empty subtree. The hashes of inner nodes are computed lazily (see
+/// `PatriciaTree::update`), and are always set outside of an update.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+enum Node {
+    Leaf(StarkFelt),
+    Binary { left: Box<Node>, right: Box<Node>, hash: Option<StarkFelt> },
+    // A path of `length` bits (the most significant bit is the topmost), leading to a binary
+    // node or a leaf.
+    Edge { path: StarkFelt, length: u8, child: Box<Node>, hash: Option<StarkFelt> },
+}
+
+impl Node {
+    fn hash(&self) -> StarkFelt {
+        match self {
+            Node::Leaf(value) => *value,
+            Node::Binary { hash, .. } | Node::Edge { hash, .. } => {
+                hash.expect("Node hashes must be computed upon update.")
+            }
+        }
+    }
+
+    /// Computes the missing hashes in the subtree rooted at this node, and returns its hash.
+    fn compute_hash<H: TreeHashFunction>(&mut self) -> StarkFelt {
+        match self {
+            Node::Leaf(value) => *value,
+            Node::Binary { left, right, hash } => *hash.get_or_insert_with(|| {
+                H::hash(&left.compute_hash::<H>(), &right.compute_hash::<H>())
+            }),
+            Node::Edge { path, length, child, hash } => *hash
+                .get_or_insert_with(|| edge_hash::<H>(&child.compute_hash::<H>(), path, *length)),
+        }
+    }
+
+    fn to_proof_node(&self) -> ProofNode {
+        match self {
+            Node::Leaf(_) => panic!("Leaves are not part of a proof."),
+            Node::Binary { left, right, .. } => {
+                ProofNode::Binary { left: left.hash(), right: right.hash() }
+            }
+            Node::Edge { path, length, child, .. } => {
+                ProofNode::Edge { child: child.hash(), path: *path, length: *length }
+            }
+        }
+    }
+}
+
+/// A node on the path from the root of a tree to a leaf, as given in a Merkle proof.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ProofNode {
+    Binary { left: StarkFelt, right: StarkFelt },
+    Edge { child: StarkFelt, path: StarkFelt, length: u8 },
+}
+
+impl ProofNode {
+    pub fn hash<H: TreeHashFunction>(&self) -> StarkFelt {
+        match self {
+            ProofNode::Binary { left, right } => H::hash(left, right),
+            ProofNode::Edge { child, path, length } => edge_hash::<H>(child, path, *length),
+        }
+    }
+}
+
+/// An in-memory binary Patricia-Merkle tree of height `TREE_HEIGHT`, as used by the Starknet
+/// global state commitment. Zero-valued leaves are considered empty; the root hash of an empty
+/// tree is zero.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(bound = "")]
+pub struct PatriciaTree<H: TreeHashFunction> {
+    root: Option<Box<Node>>,
+    #[serde(skip)]
+    hash_function: PhantomData<H>,
+}
+
+impl<H: TreeHashFunction> Default for PatriciaTree<H> {
+    fn default() -> Self {
+        Self { root: None, hash_function: PhantomData }
+    }
+}
+
+impl<H: TreeHashFunction> PatriciaTree<H> {
+    pub fn root_hash(&self) -> StarkFelt {
+        self.root.as_ref().map(|root| root.hash()).unwrap_or_default()
+    }
+
+    /// Returns the value of the leaf at the given index; zero if the leaf is empty.
+    pub fn get(&self, leaf_index: StarkFelt) -> StarkFelt {
+        let leaf_index = to_biguint(leaf_index);
+        let mut height = TREE_HEIGHT;
+        let mut node = self.root.as_deref();
+        while let Some(current_node) = node {
+            node = match current_node {
+                Node::Leaf(value) => return *value,
+                Node::Binary { left, right, .. } => {
+                    height -= 1;
+                    Some(if leaf_index.bit(height.into()) { right } else { left })
+                }
+                Node::Edge { path, length, child, .. } => {
+                    height -= length;
+                    (edge_path(&leaf_index, height, *length) == to_biguint(*path)).then_some(child)
+                }
+            }
+            .map(|child| child.as_ref());
+        }
+
+        StarkFelt::default()
+    }
+
+    /// Returns a proof of the value of the leaf at the given index: the nodes on the path from the
+    /// root to the leaf, or, if the leaf is empty, to the point where the path diverges from the
+    /// tree.
+    pub fn get_proof(&self, leaf_index: StarkFelt) -> Vec<ProofNode> {
+        let leaf_index = to_biguint(leaf_index);
+        let mut proof = Vec::new();
+        let mut height = TREE_HEIGHT;
+        let mut node = self.root.as_deref();
+        while let Some(current_node) = node {
+            node = match current_node {
+                Node::Leaf(_) => break,
+                Node::Binary { left, right, .. } => {
+                    height -= 1;
+                    Some(if leaf_index.bit(height.into()) { right } else { left })
+                }
+                Node::Edge { path, length, child, .. } => {
+                    height -= length;
+                    (edge_path(&leaf_index, height, *length) == to_biguint(*path)).then_some(child)
+                }
+            }
+            .map(|child| child.as_ref());
+            proof.push(current_node.to_proof_node());
+        }
+
+        proof
+    }
+
+    /// Sets the values of the given leaves (a zero value empties the leaf), and updates the hashes
+    /// of the affected nodes.
+    pub fn update(&mut self, leaf_modifications: impl IntoIterator<Item = (StarkFelt, StarkFelt)>) {
+        let leaf_modifications = sort_leaf_modifications(leaf_modifications, TREE_HEIGHT);
+        self.root = update_subtree(self.root.take(), TREE_HEIGHT, &leaf_modifications);
+        if let Some(root) = self.root.as_mut() {
+            root.compute_hash::<H>();
+        }
+    }
+}
+
+/// Returns the root hash of a tree of the given height, holding the given leaves (zero-valued
+/// leaves are considered empty). Unlike `PatriciaTree`, the tree itself is not kept; used for
+/// one-off commitments, such as those of a block.
+pub fn compute_root_hash<H: TreeHashFunction>(
+    height: u8,
+    leaves: impl IntoIterator<Item = (StarkFelt, StarkFelt)>,
+) -> StarkFelt {
+    let leaves = sort_leaf_modifications(leaves, height);
+    update_subtree(None, height, &leaves)
+        .map(|mut root| root.compute_hash::<H>())
+        .unwrap_or_default()
+}
+
+/// Sorts the given modifications by leaf index; for repeated indices, the last value prevails.
+/// Panics if a leaf index exceeds a tree of the given height.
+fn sort_leaf_modifications(
+    leaf_modifications: impl IntoIterator<Item = (StarkFelt, StarkFelt)>,
+    height: u8,
+) -> Vec<(BigUint, StarkFelt)> {
+    leaf_modifications
+        .into_iter()
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .map(|(leaf_index, value)| {
+            let leaf_index = to_biguint(leaf_index);
+            assert!(
+                leaf_index.bits() <= height.into(),
+                "Leaf index {leaf_index:#x} is out of range."
+            );
+            (leaf_index, value)
+        })
+        .collect()
+}
+
+/// Applies the given modifications, sorted by leaf index, to the subtree of the given height;
+/// all leaf indices must lie within the subtree. Returns the root of the modified subtree, whose
+/// hash (as well as the hashes of all modified nodes) is not computed.
+fn update_subtree(
+    node: Option<Box<Node>>,
+    height: u8,
+    leaf_modifications: &[(BigUint, StarkFelt)],
+) -> Option<Box<Node>> {
+    let Some((_, value)) = leaf_modifications.last() else {
+        return node;
+    };
+    if height == 0 {
+        return (*value != StarkFelt::default()).then(|| Box::new(Node::Leaf(*value)));
+    }
+
+    let (left, right) = match node.map(|node| *node) {
+        None => (None, None),
+        Some(Node::Binary { left, right, .. }) => (Some(left), Some(right)),
+        Some(Node::Edge { path, length, child, .. }) => split_edge(path, length, child),
+        Some(Node::Leaf(_)) => panic!("A leaf must be at height 0."),
+    };
+
+    let height = height - 1;
+    let n_left_modifications =
+        leaf_modifications.partition_point(|(leaf_index, _)| !leaf_index.bit(height.into()));
+    let (left_modifications, right_modifications) =
+        leaf_modifications.split_at(n_left_modifications);
+
+    match (
+        update_subtree(left, height, left_modifications),
+        update_subtree(right, height, right_modifications),
+    ) {
+        (None, None) => None,
+        (Some(left), Some(right)) => Some(Box::new(Node::Binary { left, right, hash: None })),
+        (Some(child), None) => Some(extend_edge(child, false)),
+        (None, Some(child)) => Some(extend_edge(child, true)),
+    }
+}
+
+/// Splits the topmost bit off the given edge; returns the left and right children of the edge's
+/// top node (exactly one of them is non-empty).
+fn split_edge(
+    path: StarkFelt,
+    length: u8,
+    child: Box<Node>,
+) -> (Option<Box<Node>>, Option<Box<Node>>) {
+    let mut path = to_biguint(path);
+    let length = length - 1;
+    let is_right = path.bit(length.into());
+    path.set_bit(length.into(), false);
+
+    let child = match length {
+        0 => child,
+        _ => Box::new(Node::Edge { path: from_biguint(path), length, child, hash: None }),
+    };
+    if is_right {
+        (None, Some(child))
+    } else {
+        (Some(child), None)
+    }
+}
+
+/// Returns an edge from a new top node to the given child, through the given bit; merges the
+/// new edge with the child, if it is an edge itself.
+fn extend_edge(child: Box<Node>, is_right: bool) -> Box<Node> {
+    let bit = BigUint::from(u8::from(is_right));
+    let node = match *child {
+        Node::Edge { path, length, child, .. } => Node::Edge {
+            path: from_biguint(to_biguint(path) + (bit << length)),
+            length: length + 1,
+            child,
+            hash: None,
+        },
+        _ => Node::Edge { path: from_biguint(bit), length: 1, child, hash: None },
+    };
+
+    Box::new(node)
+}
+
+/// Verifies the given proof of the value of the leaf at the given index (zero for an empty leaf),
+/// against the given root.
+pub fn verify_proof<H: TreeHashFunction>(
+    root: StarkFelt,
+    leaf_index: StarkFelt,
+    value: StarkFelt,
+    proof: &[ProofNode],
+) -> ProofVerificationResult<()> {
+    let leaf_index = to_biguint(leaf_index);
+    let mut expected_hash = root;
+    let mut height = TREE_HEIGHT;
+    let mut proof_nodes = proof.iter();
+
+    let proven_value = loop {
+        if height == 0 {
+            break expected_hash;
+        }
+        let Some(node) = proof_nodes.next() else {
+            if height == TREE_HEIGHT && root == StarkFelt::default() {
+                // An empty tree.
+                break StarkFelt::default();
+            }
+            return Err(ProofVerificationError::IncompleteProof { height });
+        };
+
+        let node_hash = node.hash::<H>();
+        if node_hash != expected_hash {
+            return Err(ProofVerificationError::NodeHashMismatch {
+                height,
+                expected: expected_hash,
+                actual: node_hash,
+            });
+        }
+
+        match node {
+            ProofNode::Binary { left, right } => {
+                height -= 1;
+                expected_hash = if leaf_index.bit(height.into()) { *right } else { *left };
+            }
+            ProofNode::Edge { child, path, length } => {
+                if *length == 0 || *length > height {
+                    return Err(ProofVerificationError::InvalidEdgeLength {
+                        height,
+                        length: *length,
+                    });
+                }
+                height -= length;
+                if edge_path(&leaf_index, height, *length) != to_biguint(*path) {
+                    // The path to the leaf diverges from the tree; i.e., the leaf is empty.
+                    break StarkFelt::default();
+                }
+                expected_hash = *child;
+            }
+        }
+    };
+
+    if proof_nodes.next().is_some() {
+        return Err(ProofVerificationError::RedundantProofNodes);
+    }
+    if proven_value != value {
+        return Err(ProofVerificationError::LeafValueMismatch {
+            expected: value,
+            actual: proven_value,
+        });
+    }
+
+    Ok(())
+}
+
+fn edge_hash<H: TreeHashFunction>(child: &StarkFelt, path: &StarkFelt, length: u8) -> StarkFelt {
+    (FieldElement::from(H::hash(child, path)) + FieldElement::from(length)).into()
+}
+
+/// Returns the `length` bits of the given leaf index above the given height; i.e., the path of an
+/// edge, from `height + length` to `height`, that leads to the leaf.
+fn edge_path(leaf_index: &BigUint, height: u8, length: u8) -> BigUint {
+    (leaf_index >> height) % (BigUint::from(1_u8) << length)
+}
+
+fn to_biguint(felt: StarkFelt) -> BigUint {
+    BigUint::from_bytes_be(felt.bytes())
+}
+
+fn from_biguint(value: BigUint) -> StarkFelt {
+    felt_to_stark_felt(&Felt252::from(value))
+}
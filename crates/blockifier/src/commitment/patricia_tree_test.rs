@@ -0,0 +1,225 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::hash::{pedersen_hash, StarkFelt};
+use starknet_api::stark_felt;
+use starknet_crypto::{poseidon_hash, FieldElement};
+
+use crate::commitment::errors::ProofVerificationError;
+use crate::commitment::patricia_tree::{
+    compute_root_hash, verify_proof, PatriciaTree, PedersenHashFunction, PoseidonHashFunction,
+    ProofNode, TREE_HEIGHT,
+};
+
+type PedersenTree = PatriciaTree<PedersenHashFunction>;
+
+fn add(felt: StarkFelt, value: u8) -> StarkFelt {
+    (FieldElement::from(felt) + FieldElement::from(value)).into()
+}
+
+#[test]
+fn test_empty_tree() {
+    let mut tree = PedersenTree::default();
+    assert_eq!(tree.root_hash(), StarkFelt::default());
+    assert_eq!(tree.get(stark_felt!(7_u8)), StarkFelt::default());
+
+    // Setting a leaf to zero keeps it empty.
+    tree.update([(stark_felt!(7_u8), StarkFelt::default())]);
+    assert_eq!(tree, PedersenTree::default());
+}
+
+#[test]
+fn test_single_leaf_root() {
+    let (leaf_index, value) = (stark_felt!("0x1234"), stark_felt!("0x99"));
+
+    let mut pedersen_tree = PedersenTree::default();
+    pedersen_tree.update([(leaf_index, value)]);
+    // An edge from the root to the leaf, whose path is the leaf index.
+    assert_eq!(pedersen_tree.root_hash(), add(pedersen_hash(&value, &leaf_index), TREE_HEIGHT));
+
+    let mut poseidon_tree = PatriciaTree::<PoseidonHashFunction>::default();
+    poseidon_tree.update([(leaf_index, value)]);
+    let expected_hash: StarkFelt =
+        poseidon_hash(FieldElement::from(value), FieldElement::from(leaf_index)).into();
+    assert_eq!(poseidon_tree.root_hash(), add(expected_hash, TREE_HEIGHT));
+}
+
+#[test]
+fn test_sibling_leaves_root() {
+    let (left_value, right_value) = (stark_felt!("0x5"), stark_felt!("0x6"));
+    let mut tree = PedersenTree::default();
+    tree.update([(stark_felt!(2_u8), left_value), (stark_felt!(3_u8), right_value)]);
+
+    // An edge from the root to the binary node above the leaves.
+    let binary_node_hash = pedersen_hash(&left_value, &right_value);
+    let expected_root = add(pedersen_hash(&binary_node_hash, &stark_felt!(1_u8)), TREE_HEIGHT - 1);
+    assert_eq!(tree.root_hash(), expected_root);
+}
+
+#[test]
+fn test_compute_root_hash() {
+    let leaves =
+        [(stark_felt!(2_u8), stark_felt!("0x5")), (stark_felt!(1000_u128), stark_felt!("0x6"))];
+    let mut tree = PedersenTree::default();
+    tree.update(leaves);
+    assert_eq!(compute_root_hash::<PedersenHashFunction>(TREE_HEIGHT, leaves), tree.root_hash());
+
+    // Lower trees are shorter by their edges from the root.
+    let (leaf_index, value) = leaves[0];
+    assert_eq!(
+        compute_root_hash::<PedersenHashFunction>(64, [(leaf_index, value)]),
+        add(pedersen_hash(&value, &leaf_index), 64)
+    );
+    assert_eq!(compute_root_hash::<PedersenHashFunction>(64, []), StarkFelt::default());
+}
+
+#[rstest]
+#[case::single_batch(vec![vec![(1, 1), (2, 2), (1000, 3), (1001, 4), (u128::MAX, 5)]])]
+#[case::multiple_batches(vec![
+    vec![(1000, 3), (7, 8)],
+    vec![(u128::MAX, 5), (1, 1), (7, 0)],
+    vec![(1001, 4), (2, 2), (3, 0)],
+])]
+#[case::overwrites(vec![
+    vec![(1, 10), (2, 20), (1000, 30), (1001, 40), (u128::MAX, 50)],
+    vec![(1, 1), (2, 2), (1000, 3), (1001, 4), (u128::MAX, 5)],
+])]
+fn test_update_is_order_independent(#[case] batches: Vec<Vec<(u128, u128)>>) {
+    let mut expected_tree = PedersenTree::default();
+    expected_tree.update(
+        [(1_u128, 1_u128), (2, 2), (1000, 3), (1001, 4), (u128::MAX, 5)]
+            .map(|(leaf_index, value)| (stark_felt!(leaf_index), stark_felt!(value))),
+    );
+
+    let mut tree = PedersenTree::default();
+    for batch in batches {
+        tree.update(
+            batch
+                .into_iter()
+                .map(|(leaf_index, value)| (stark_felt!(leaf_index), stark_felt!(value))),
+        );
+    }
+
+    assert_eq!(tree, expected_tree);
+    assert_eq!(tree.get(stark_felt!(1001_u128)), stark_felt!(4_u8));
+    assert_eq!(tree.get(stark_felt!(1002_u128)), StarkFelt::default());
+}
+
+#[test]
+fn test_empty_leaves_are_removed() {
+    let mut tree = PedersenTree::default();
+    tree.update([(stark_felt!(1_u8), stark_felt!(1_u8)), (stark_felt!(2_u8), stark_felt!(2_u8))]);
+    let expected_tree = tree.clone();
+
+    tree.update([
+        (stark_felt!(3_u8), stark_felt!(3_u8)),
+        (stark_felt!(1000_u128), stark_felt!(4_u8)),
+    ]);
+    assert_ne!(tree.root_hash(), expected_tree.root_hash());
+
+    tree.update([
+        (stark_felt!(3_u8), StarkFelt::default()),
+        (stark_felt!(1000_u128), StarkFelt::default()),
+    ]);
+    assert_eq!(tree, expected_tree);
+}
+
+#[test]
+fn test_serialization_round_trip() {
+    let mut tree = PedersenTree::default();
+    tree.update([(stark_felt!(1_u8), stark_felt!(1_u8)), (stark_felt!(9_u8), stark_felt!(2_u8))]);
+
+    let serialized_tree = serde_json::to_string(&tree).unwrap();
+    let deserialized_tree: PedersenTree = serde_json::from_str(&serialized_tree).unwrap();
+    assert_eq!(deserialized_tree, tree);
+    assert_eq!(deserialized_tree.root_hash(), tree.root_hash());
+}
+
+fn create_tree() -> PedersenTree {
+    let mut tree = PedersenTree::default();
+    tree.update(
+        [(1_u128, 1_u128), (2, 2), (1000, 3), (1001, 4), (u128::MAX, 5)]
+            .map(|(leaf_index, value)| (stark_felt!(leaf_index), stark_felt!(value))),
+    );
+    tree
+}
+
+#[rstest]
+#[case::leaf(1, 1)]
+#[case::sibling_leaf(1001, 4)]
+#[case::lone_leaf(u128::MAX, 5)]
+#[case::empty_leaf_beside_leaf(3, 0)]
+#[case::empty_leaf_in_empty_subtree(u128::MAX - 1, 0)]
+#[case::empty_leaf_above_all_leaves(u128::MAX, 0)]
+fn test_verify_proof(#[case] leaf_index: u128, #[case] value: u128) {
+    let tree = create_tree();
+    let leaf_index = if value == 0 && leaf_index == u128::MAX {
+        // An index beyond 128 bits.
+        stark_felt!("0x400000000000000000000000000000000000000000000000000000000000000")
+    } else {
+        stark_felt!(leaf_index)
+    };
+    let value = stark_felt!(value);
+
+    let proof = tree.get_proof(leaf_index);
+    assert_eq!(tree.get(leaf_index), value);
+    verify_proof::<PedersenHashFunction>(tree.root_hash(), leaf_index, value, &proof).unwrap();
+
+    // A proof cannot be used to prove another value.
+    assert_matches!(
+        verify_proof::<PedersenHashFunction>(tree.root_hash(), leaf_index, stark_felt!(7_u8), &proof),
+        Err(ProofVerificationError::LeafValueMismatch { expected, actual })
+        if expected == stark_felt!(7_u8) && actual == value
+    );
+}
+
+#[test]
+fn test_empty_tree_proof() {
+    let tree = PedersenTree::default();
+    let proof = tree.get_proof(stark_felt!(1_u8));
+    assert_eq!(proof, vec![]);
+    verify_proof::<PedersenHashFunction>(
+        StarkFelt::default(),
+        stark_felt!(1_u8),
+        StarkFelt::default(),
+        &proof,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_invalid_proofs() {
+    let tree = create_tree();
+    let (leaf_index, value) = (stark_felt!(1000_u128), stark_felt!(3_u8));
+    let proof = tree.get_proof(leaf_index);
+    let verify = |root: StarkFelt, proof: &[ProofNode]| {
+        verify_proof::<PedersenHashFunction>(root, leaf_index, value, proof)
+    };
+
+    assert_matches!(
+        verify(stark_felt!(7_u8), &proof),
+        Err(ProofVerificationError::NodeHashMismatch { height: TREE_HEIGHT, .. })
+    );
+    assert_matches!(
+        verify(tree.root_hash(), &proof[..proof.len() - 1]),
+        Err(ProofVerificationError::IncompleteProof { .. })
+    );
+
+    let mut redundant_proof = proof.clone();
+    redundant_proof.push(proof[0].clone());
+    assert_matches!(
+        verify(tree.root_hash(), &redundant_proof),
+        Err(ProofVerificationError::RedundantProofNodes)
+    );
+
+    // Tamper with the sibling of the leaf.
+    let mut tampered_proof = proof.clone();
+    let Some(ProofNode::Binary { right, .. }) = tampered_proof.last_mut() else {
+        panic!("Leaves 1000 and 1001 are siblings.");
+    };
+    *right = stark_felt!(7_u8);
+    assert_matches!(
+        verify(tree.root_hash(), &tampered_proof),
+        Err(ProofVerificationError::NodeHashMismatch { .. })
+    );
+}
@@ -0,0 +1,6 @@
+pub mod parallel_executor;
+pub mod versioned_state;
+pub mod versioned_storage;
+
+/// The position of a transaction in the executed batch.
+pub type TxIndex = usize;
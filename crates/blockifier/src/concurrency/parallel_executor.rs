@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use starknet_api::hash::StarkFelt;
+
+use crate::abi::sierra_types::felt_to_u128;
+use crate::blockifier::transaction_executor::TransactionExecutorResult;
+use crate::concurrency::versioned_state::{
+    StateReads, StateWrites, VersionedState, VersionedStateProxy,
+};
+use crate::concurrency::TxIndex;
+use crate::context::BlockContext;
+use crate::execution::execution_utils::stark_felt_to_felt;
+use crate::fee::fee_utils::get_sequencer_balance_keys;
+use crate::state::cached_state::{
+    CachedState, GlobalContractCache, StagedTransactionalState, StateAccesses, StateChanges,
+    StateChangesKeys, ValueChange,
+};
+use crate::state::state_api::{StateReader, StateResult};
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[cfg(test)]
+#[path = "parallel_executor_test.rs"]
+pub mod test;
+
+/// The result of a successful (speculative) transaction execution.
+pub struct ExecutionOutput {
+    pub tx_execution_info: TransactionExecutionInfo,
+    // Note: the counting fields of the staged state are set upon commit, as they depend on the
+    // preceding transactions.
+    pub staged_state: StagedTransactionalState,
+    pub state_changes_keys: StateChangesKeys,
+    pub state_accesses: StateAccesses,
+}
+
+impl ExecutionOutput {
+    /// Adds the fee of the transaction to the balance of the sequencer in the given state, on
+    /// which the transaction is committed. Upon execution, the fee was transferred to a zero
+    /// sequencer balance, as the fees of the preceding transactions are not yet known; see
+    /// `AccountTransaction::concurrency_execute_fee_transfer`.
+    pub fn add_fee_to_sequencer_balance(
+        &mut self,
+        state: &mut impl StateReader,
+        block_context: &BlockContext,
+    ) -> StateResult<()> {
+        let Some(fee_transfer_call_info) = &mut self.tx_execution_info.fee_transfer_call_info
+        else {
+            return Ok(());
+        };
+        let fee_token_address = fee_transfer_call_info.call.storage_address;
+        let (low, high) = state
+            .get_fee_token_balance(block_context.block_info.sequencer_address, fee_token_address)?;
+
+        // The fee transfer reads the balance of the sender, then the one of the sequencer; each
+        // balance is read twice, as the (deprecated) storage writes read the overwritten values.
+        let sequencer_balance_reads = &mut fee_transfer_call_info.storage_read_values[4..];
+        assert_eq!(
+            sequencer_balance_reads,
+            [StarkFelt::ZERO; 4],
+            "The fee must be transferred to a zero sequencer balance."
+        );
+        sequencer_balance_reads.copy_from_slice(&[low, high, low, high]);
+
+        let to_u128 = |value| {
+            felt_to_u128(&stark_felt_to_felt(value)).expect("A balance part must fit in a u128.")
+        };
+        let (new_low, carry) = to_u128(low).overflowing_add(self.tx_execution_info.actual_fee.0);
+        let new_high = to_u128(high)
+            .checked_add(carry.into())
+            .expect("The sequencer balance overflowed when adding the fee.");
+
+        let (key_low, key_high) = get_sequencer_balance_keys(block_context);
+        let mut storage_updates = HashMap::new();
+        for (key, before, after) in
+            [(key_low, low, new_low.into()), (key_high, high, new_high.into())]
+        {
+            let storage_entry = (fee_token_address, key);
+            self.staged_state.cache.set_storage_value(fee_token_address, key, after);
+            self.state_accesses.read_set.storage.insert(storage_entry, before);
+            self.state_accesses
+                .write_set
+                .storage
+                .insert(storage_entry, ValueChange { before, after });
+            if before != after {
+                storage_updates.insert(storage_entry, after);
+            }
+        }
+        self.state_changes_keys
+            .extend(&StateChanges { storage_updates, ..Default::default() }.into_keys());
+
+        Ok(())
+    }
+}
+
+/// The outcome of the last execution of a transaction, together with the reads it was based on.
+pub struct ExecutionTaskOutput {
+    pub reads: StateReads,
+    pub result: TransactionExecutorResult<ExecutionOutput>,
+    /// The number of times the transaction was executed.
+    pub n_executions: usize,
+}
+
+impl ExecutionTaskOutput {
+    /// Returns whether the transaction read the balance of the sequencer, other than in its fee
+    /// transfer. As the fees of the preceding transactions are only added to the balance upon
+    /// commit, the values read are stale.
+    pub fn reads_sequencer_balance(&self, block_context: &BlockContext) -> bool {
+        let (key_low, key_high) = get_sequencer_balance_keys(block_context);
+        let fee_token_addresses = &block_context.chain_info.fee_token_addresses;
+        [fee_token_addresses.eth_fee_token_address, fee_token_addresses.strk_fee_token_address]
+            .into_iter()
+            .any(|fee_token_address| {
+                [key_low, key_high]
+                    .into_iter()
+                    .any(|key| self.reads.storage.contains_key(&(fee_token_address, key)))
+            })
+    }
+}
+
+/// Executes a batch of transactions optimistically, in the spirit of Block-STM.
+///
+/// All transactions are first executed concurrently over a shared, multi-version state; each
+/// execution records the values it read. Then, transactions whose reads were invalidated by the
+/// writes of preceding transactions are re-executed, until all reads are consistent. The
+/// resulting outputs are identical to the ones of a sequential execution of the batch, once
+/// committed in order (see `ExecutionOutput::add_fee_to_sequencer_balance`).
+///
+/// The fees are transferred to the sequencer without accessing its balance, which would make
+/// every transaction depend on all of its predecessors. Hence, the outputs of transactions that
+/// otherwise read the sequencer balance are stale, and must be discarded; see
+/// `ExecutionTaskOutput::reads_sequencer_balance`.
+pub struct ParallelExecutor<'a, S: StateReader> {
+    versioned_state: VersionedState<S>,
+    txs: &'a [Transaction],
+    block_context: BlockContext,
+    global_contract_cache: GlobalContractCache,
+    charge_fee: bool,
+    outputs: Vec<Mutex<Option<ExecutionTaskOutput>>>,
+}
+
+impl<'a, S: StateReader + Sync> ParallelExecutor<'a, S> {
+    pub fn new(
+        initial_state: S,
+        txs: &'a [Transaction],
+        block_context: &BlockContext,
+        global_contract_cache: GlobalContractCache,
+        charge_fee: bool,
+    ) -> Self {
+        // The records of a tracer are shared by all of its clones, and would thus interleave the
+        // calls of the concurrently executed transactions.
+        assert!(
+            block_context.vm_tracer.is_none(),
+            "VM tracing is not supported in parallel execution."
+        );
+
+        Self {
+            versioned_state: VersionedState::new(initial_state),
+            txs,
+            block_context: BlockContext { concurrency_mode: true, ..block_context.clone() },
+            global_contract_cache,
+            charge_fee,
+            outputs: txs.iter().map(|_| Mutex::new(None)).collect(),
+        }
+    }
+
+    /// Executes the batch using the given number of worker threads, and returns the outputs of
+    /// the transactions, in order.
+    /// Fails if the validation of the reads of a transaction fails to read the state.
+    pub fn run(self, n_workers: usize) -> StateResult<Vec<ExecutionTaskOutput>> {
+        assert!(n_workers > 0, "At least one worker is required.");
+
+        let n_txs = self.txs.len();
+        let mut execution_tasks: Vec<TxIndex> = (0..n_txs).collect();
+        while let Some(&first_executed_tx) = execution_tasks.first() {
+            self.run_tasks(n_workers, &execution_tasks, |tx_index| self.execute(tx_index));
+
+            // The first executed transaction is up to date: all of its predecessors are valid,
+            // and were not re-executed in this round.
+            let validation_tasks: Vec<TxIndex> = (first_executed_tx + 1..n_txs).collect();
+            let validation_results = Mutex::new(Vec::new());
+            self.run_tasks(n_workers, &validation_tasks, |tx_index| {
+                let is_valid = self.validate(tx_index);
+                validation_results
+                    .lock()
+                    .expect("Failed to collect validation results.")
+                    .push((tx_index, is_valid));
+            });
+
+            execution_tasks = Vec::new();
+            for (tx_index, is_valid) in
+                validation_results.into_inner().expect("Failed to collect validation results.")
+            {
+                if !is_valid? {
+                    execution_tasks.push(tx_index);
+                }
+            }
+            execution_tasks.sort_unstable();
+        }
+
+        Ok(self
+            .outputs
+            .into_iter()
+            .map(|output| {
+                output
+                    .into_inner()
+                    .expect("Execution output is poisoned.")
+                    .expect("All transactions must be executed.")
+            })
+            .collect())
+    }
+
+    /// Distributes the given tasks among the workers, and waits for all of them to finish.
+    fn run_tasks(&self, n_workers: usize, tasks: &[TxIndex], run_task: impl Fn(TxIndex) + Sync) {
+        let next_task = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..n_workers {
+                scope.spawn(|| {
+                    while let Some(&tx_index) = tasks.get(next_task.fetch_add(1, Ordering::Relaxed))
+                    {
+                        run_task(tx_index);
+                    }
+                });
+            }
+        });
+    }
+
+    fn execute(&self, tx_index: TxIndex) {
+        let tx = self.txs[tx_index].clone();
+        let mut tx_state = CachedState::new(
+            VersionedStateProxy::new(tx_index, &self.versioned_state),
+            self.global_contract_cache.clone(),
+        );
+
+        let mut writes = StateWrites::default();
+        let result = self.execute_tx(&mut tx_state, tx, &mut writes);
+        self.versioned_state.apply_writes(tx_index, writes);
+
+        let reads = tx_state.state.into_reads();
+        let mut output = self.outputs[tx_index].lock().expect("Execution output is poisoned.");
+        let n_executions = output.as_ref().map_or(0, |output| output.n_executions) + 1;
+        *output = Some(ExecutionTaskOutput { reads, result, n_executions });
+    }
+
+    fn execute_tx(
+        &self,
+        tx_state: &mut CachedState<VersionedStateProxy<'_, S>>,
+        tx: Transaction,
+        writes: &mut StateWrites,
+    ) -> TransactionExecutorResult<ExecutionOutput> {
+        let mut transactional_state = CachedState::create_transactional(tx_state);
+        let validate = true;
+        let tx_execution_info = tx.execute_raw(
+            &mut transactional_state,
+            &self.block_context,
+            self.charge_fee,
+            validate,
+        )?;
+
+        let state_changes = transactional_state.get_actual_state_changes()?;
+        let state_accesses = transactional_state.get_state_accesses()?;
+        let staged_state =
+            transactional_state.stage(HashSet::new(), HashSet::new(), StateChangesKeys::default());
+        *writes = StateWrites::new(state_changes.clone(), staged_state.class_hash_to_class.clone());
+
+        Ok(ExecutionOutput {
+            tx_execution_info,
+            staged_state,
+            state_changes_keys: state_changes.into_keys(),
+            state_accesses,
+        })
+    }
+
+    fn validate(&self, tx_index: TxIndex) -> StateResult<bool> {
+        let output = self.outputs[tx_index].lock().expect("Execution output is poisoned.");
+        let reads =
+            &output.as_ref().expect("Transaction must be executed before validation.").reads;
+
+        self.versioned_state.validate_reads(tx_index, reads)
+    }
+}
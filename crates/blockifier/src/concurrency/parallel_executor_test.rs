@@ -0,0 +1,78 @@
+use rstest::rstest;
+use starknet_api::core::Nonce;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::Fee;
+
+use crate::concurrency::parallel_executor::ParallelExecutor;
+use crate::context::BlockContext;
+use crate::execution::vm_tracer::VmTracer;
+use crate::invoke_tx_args;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::constants::TRANSFER_ENTRY_POINT_NAME;
+use crate::transaction::objects::FeeType;
+use crate::transaction::test_utils::{account_invoke_tx, block_context};
+use crate::transaction::transaction_execution::Transaction;
+
+#[rstest]
+fn test_independent_transfers_are_executed_once(
+    block_context: BlockContext,
+    #[values(1, 4)] n_workers: usize,
+) {
+    let n_txs = 8;
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let mut state = test_state(&block_context.chain_info, BALANCE, &[(account, n_txs)]);
+    let fee_token_address = block_context.chain_info.fee_token_address(&FeeType::Eth);
+
+    // Each transfer is sent by a different account, to a different recipient.
+    let txs: Vec<Transaction> = (0..n_txs)
+        .map(|i| {
+            let calldata = create_calldata(
+                fee_token_address,
+                TRANSFER_ENTRY_POINT_NAME,
+                &[stark_felt!(1000_u16 + u16::from(i)), stark_felt!(1_u8), StarkFelt::ZERO],
+            );
+            account_invoke_tx(invoke_tx_args! {
+                max_fee: Fee(MAX_FEE),
+                sender_address: account.get_instance_address(i),
+                calldata,
+                nonce: Nonce::default(),
+            })
+            .into()
+        })
+        .collect();
+
+    let charge_fee = true;
+    let global_contract_cache = state.global_class_hash_to_class.clone();
+    let outputs = ParallelExecutor::new(
+        state.view(),
+        &txs,
+        &block_context,
+        global_contract_cache,
+        charge_fee,
+    )
+    .run(n_workers)
+    .unwrap();
+
+    // The transfers only share the balance of the sequencer, which their fee transfers do not
+    // access; hence, none of them is re-executed.
+    for output in outputs {
+        assert!(output.result.is_ok());
+        assert!(!output.reads_sequencer_balance(&block_context));
+        assert_eq!(output.n_executions, 1);
+    }
+}
+
+#[rstest]
+#[should_panic(expected = "VM tracing is not supported in parallel execution.")]
+fn test_vm_tracer_is_rejected(block_context: BlockContext) {
+    let block_context = block_context.with_vm_tracer(VmTracer::default());
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let mut state = test_state(&block_context.chain_info, BALANCE, &[(account, 1)]);
+    let global_contract_cache = state.global_class_hash_to_class.clone();
+
+    let charge_fee = true;
+    ParallelExecutor::new(state.view(), &[], &block_context, global_contract_cache, charge_fee);
+}
@@ -0,0 +1,282 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use crate::concurrency::versioned_storage::VersionedStorage;
+use crate::concurrency::TxIndex;
+use crate::execution::contract_class::ContractClass;
+use crate::state::cached_state::{ContractClassMapping, StateChanges, StorageEntry};
+use crate::state::errors::StateError;
+use crate::state::state_api::{StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "versioned_state_test.rs"]
+pub mod test;
+
+/// The state cells read by a transaction, together with the values it observed.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct StateReads {
+    pub storage: HashMap<StorageEntry, StarkFelt>,
+    pub nonces: HashMap<ContractAddress, Nonce>,
+    pub class_hashes: HashMap<ContractAddress, ClassHash>,
+    pub compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+    // Whether the class was declared at the time of the read.
+    pub declared_contracts: HashMap<ClassHash, bool>,
+}
+
+/// The state cells written by a transaction, together with the written values.
+#[derive(Debug, Default)]
+pub struct StateWrites {
+    pub storage: HashMap<StorageEntry, StarkFelt>,
+    pub nonces: HashMap<ContractAddress, Nonce>,
+    pub class_hashes: HashMap<ContractAddress, ClassHash>,
+    pub compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+    pub contract_classes: ContractClassMapping,
+}
+
+impl StateWrites {
+    pub fn new(state_changes: StateChanges, contract_classes: ContractClassMapping) -> Self {
+        Self {
+            storage: state_changes.storage_updates,
+            nonces: state_changes.nonce_updates,
+            class_hashes: state_changes.class_hash_updates,
+            compiled_class_hashes: state_changes.compiled_class_hash_updates,
+            contract_classes,
+        }
+    }
+}
+
+/// A multi-version view of the state, shared by the transactions of a batch.
+///
+/// Each transaction reads the values written by the transactions preceding it in the batch; cells
+/// that were not written by any of them are read from the initial state (and cached).
+///
+/// Each storage map is locked separately, so that transactions only contend on the maps they
+/// access concurrently; the initial state is not locked, and is read by all transactions
+/// concurrently (hence, sharing the versioned state between threads requires it to be `Sync`).
+/// Writes of a transaction are thus not applied atomically across maps; this is sound, as reads
+/// are validated only after all executions of a round are done.
+#[derive(Debug)]
+pub struct VersionedState<S: StateReader> {
+    initial_state: S,
+    storage: Mutex<VersionedStorage<StorageEntry, StarkFelt>>,
+    nonces: Mutex<VersionedStorage<ContractAddress, Nonce>>,
+    class_hashes: Mutex<VersionedStorage<ContractAddress, ClassHash>>,
+    compiled_class_hashes: Mutex<VersionedStorage<ClassHash, CompiledClassHash>>,
+    compiled_contract_classes: Mutex<VersionedStorage<ClassHash, ContractClass>>,
+}
+
+impl<S: StateReader> VersionedState<S> {
+    pub fn new(initial_state: S) -> Self {
+        Self {
+            initial_state,
+            storage: Mutex::default(),
+            nonces: Mutex::default(),
+            class_hashes: Mutex::default(),
+            compiled_class_hashes: Mutex::default(),
+            compiled_contract_classes: Mutex::default(),
+        }
+    }
+
+    pub fn get_storage_at(
+        &self,
+        tx_index: TxIndex,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        let storage_entry = (contract_address, key);
+        if let Some(value) = lock(&self.storage).read(tx_index, &storage_entry) {
+            return Ok(value);
+        }
+
+        let initial_value = self.initial_state.get_storage_at(contract_address, key)?;
+        lock(&self.storage).set_initial_value(storage_entry, initial_value);
+        Ok(initial_value)
+    }
+
+    pub fn get_nonce_at(
+        &self,
+        tx_index: TxIndex,
+        contract_address: ContractAddress,
+    ) -> StateResult<Nonce> {
+        if let Some(nonce) = lock(&self.nonces).read(tx_index, &contract_address) {
+            return Ok(nonce);
+        }
+
+        let initial_nonce = self.initial_state.get_nonce_at(contract_address)?;
+        lock(&self.nonces).set_initial_value(contract_address, initial_nonce);
+        Ok(initial_nonce)
+    }
+
+    pub fn get_class_hash_at(
+        &self,
+        tx_index: TxIndex,
+        contract_address: ContractAddress,
+    ) -> StateResult<ClassHash> {
+        if let Some(class_hash) = lock(&self.class_hashes).read(tx_index, &contract_address) {
+            return Ok(class_hash);
+        }
+
+        let initial_class_hash = self.initial_state.get_class_hash_at(contract_address)?;
+        lock(&self.class_hashes).set_initial_value(contract_address, initial_class_hash);
+        Ok(initial_class_hash)
+    }
+
+    pub fn get_compiled_class_hash(
+        &self,
+        tx_index: TxIndex,
+        class_hash: ClassHash,
+    ) -> StateResult<CompiledClassHash> {
+        if let Some(compiled_class_hash) =
+            lock(&self.compiled_class_hashes).read(tx_index, &class_hash)
+        {
+            return Ok(compiled_class_hash);
+        }
+
+        let initial_compiled_class_hash = self.initial_state.get_compiled_class_hash(class_hash)?;
+        lock(&self.compiled_class_hashes)
+            .set_initial_value(class_hash, initial_compiled_class_hash);
+        Ok(initial_compiled_class_hash)
+    }
+
+    pub fn get_compiled_contract_class(
+        &self,
+        tx_index: TxIndex,
+        class_hash: ClassHash,
+    ) -> StateResult<ContractClass> {
+        if let Some(contract_class) =
+            lock(&self.compiled_contract_classes).read(tx_index, &class_hash)
+        {
+            return Ok(contract_class);
+        }
+
+        // Note: undeclared classes are not cached, as there is no value to cache.
+        let initial_contract_class = self.initial_state.get_compiled_contract_class(class_hash)?;
+        lock(&self.compiled_contract_classes)
+            .set_initial_value(class_hash, initial_contract_class.clone());
+        Ok(initial_contract_class)
+    }
+
+    /// Sets the writes of the transaction at the given index, discarding the writes of its
+    /// previous execution (if any).
+    pub fn apply_writes(&self, tx_index: TxIndex, writes: StateWrites) {
+        lock(&self.storage).set_writes(tx_index, writes.storage);
+        lock(&self.nonces).set_writes(tx_index, writes.nonces);
+        lock(&self.class_hashes).set_writes(tx_index, writes.class_hashes);
+        lock(&self.compiled_class_hashes).set_writes(tx_index, writes.compiled_class_hashes);
+        lock(&self.compiled_contract_classes).set_writes(tx_index, writes.contract_classes);
+    }
+
+    /// Returns whether the given reads, made by the transaction at the given index, are still
+    /// consistent with the values currently visible to it.
+    pub fn validate_reads(&self, tx_index: TxIndex, reads: &StateReads) -> StateResult<bool> {
+        for (&(contract_address, key), &value) in &reads.storage {
+            if self.get_storage_at(tx_index, contract_address, key)? != value {
+                return Ok(false);
+            }
+        }
+
+        for (&contract_address, &nonce) in &reads.nonces {
+            if self.get_nonce_at(tx_index, contract_address)? != nonce {
+                return Ok(false);
+            }
+        }
+
+        for (&contract_address, &class_hash) in &reads.class_hashes {
+            if self.get_class_hash_at(tx_index, contract_address)? != class_hash {
+                return Ok(false);
+            }
+        }
+
+        for (&class_hash, &compiled_class_hash) in &reads.compiled_class_hashes {
+            if self.get_compiled_class_hash(tx_index, class_hash)? != compiled_class_hash {
+                return Ok(false);
+            }
+        }
+
+        for (&class_hash, &declared) in &reads.declared_contracts {
+            let is_declared = match self.get_compiled_contract_class(tx_index, class_hash) {
+                Ok(_) => true,
+                Err(StateError::UndeclaredClassHash(_)) => false,
+                Err(error) => return Err(error),
+            };
+            if is_declared != declared {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().expect("Versioned state is poisoned.")
+}
+
+/// Exposes the versioned state, as visible to a single transaction in the batch, through the
+/// `StateReader` API; records the reads made through it.
+pub struct VersionedStateProxy<'a, S: StateReader> {
+    pub tx_index: TxIndex,
+    pub state: &'a VersionedState<S>,
+    reads: RefCell<StateReads>,
+}
+
+impl<'a, S: StateReader> VersionedStateProxy<'a, S> {
+    pub fn new(tx_index: TxIndex, state: &'a VersionedState<S>) -> Self {
+        Self { tx_index, state, reads: RefCell::default() }
+    }
+
+    /// Drops the proxy, returning the reads made through it.
+    pub fn into_reads(self) -> StateReads {
+        self.reads.into_inner()
+    }
+}
+
+impl<'a, S: StateReader> StateReader for VersionedStateProxy<'a, S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        let value = self.state.get_storage_at(self.tx_index, contract_address, key)?;
+        self.reads.borrow_mut().storage.insert((contract_address, key), value);
+        Ok(value)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        let nonce = self.state.get_nonce_at(self.tx_index, contract_address)?;
+        self.reads.borrow_mut().nonces.insert(contract_address, nonce);
+        Ok(nonce)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        let class_hash = self.state.get_class_hash_at(self.tx_index, contract_address)?;
+        self.reads.borrow_mut().class_hashes.insert(contract_address, class_hash);
+        Ok(class_hash)
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        let result = self.state.get_compiled_contract_class(self.tx_index, class_hash);
+        match &result {
+            Ok(_) => {
+                self.reads.borrow_mut().declared_contracts.insert(class_hash, true);
+            }
+            Err(StateError::UndeclaredClassHash(_)) => {
+                self.reads.borrow_mut().declared_contracts.insert(class_hash, false);
+            }
+            Err(_) => {}
+        }
+
+        result
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        let compiled_class_hash = self.state.get_compiled_class_hash(self.tx_index, class_hash)?;
+        self.reads.borrow_mut().compiled_class_hashes.insert(class_hash, compiled_class_hash);
+        Ok(compiled_class_hash)
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::concurrency::versioned_state::{
+    StateReads, StateWrites, VersionedState, VersionedStateProxy,
+};
+use crate::state::state_api::StateReader;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::{get_test_contract_class, TEST_CLASS_HASH};
+
+fn initial_state() -> DictStateReader {
+    DictStateReader {
+        storage_view: HashMap::from([(
+            (contract_address!("0x1"), StorageKey(patricia_key!("0x10"))),
+            stark_felt!("0x5"),
+        )]),
+        address_to_nonce: HashMap::from([(contract_address!("0x1"), Nonce(stark_felt!("0x2")))]),
+        class_hash_to_class: HashMap::from([(
+            class_hash!(TEST_CLASS_HASH),
+            get_test_contract_class(),
+        )]),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_proxy_records_reads() {
+    let contract_address = contract_address!("0x1");
+    let key = StorageKey(patricia_key!("0x10"));
+    let declared_class_hash = class_hash!(TEST_CLASS_HASH);
+    let undeclared_class_hash = class_hash!("0x101");
+    let versioned_state = VersionedState::new(initial_state());
+
+    let proxy = VersionedStateProxy::new(0, &versioned_state);
+    assert_eq!(proxy.get_storage_at(contract_address, key).unwrap(), stark_felt!("0x5"));
+    assert_eq!(proxy.get_nonce_at(contract_address).unwrap(), Nonce(stark_felt!("0x2")));
+    assert!(proxy.get_compiled_contract_class(declared_class_hash).is_ok());
+    assert!(proxy.get_compiled_contract_class(undeclared_class_hash).is_err());
+
+    let expected_reads = StateReads {
+        storage: HashMap::from([((contract_address, key), stark_felt!("0x5"))]),
+        nonces: HashMap::from([(contract_address, Nonce(stark_felt!("0x2")))]),
+        declared_contracts: HashMap::from([
+            (declared_class_hash, true),
+            (undeclared_class_hash, false),
+        ]),
+        ..Default::default()
+    };
+    assert_eq!(proxy.into_reads(), expected_reads);
+}
+
+#[test]
+fn test_reads_see_preceding_writes() {
+    let contract_address = contract_address!("0x1");
+    let key = StorageKey(patricia_key!("0x10"));
+    let versioned_state = VersionedState::new(initial_state());
+
+    let writes = StateWrites {
+        storage: HashMap::from([((contract_address, key), stark_felt!("0x7"))]),
+        ..Default::default()
+    };
+    versioned_state.apply_writes(1, writes);
+
+    for (tx_index, expected_value) in
+        [(0, stark_felt!("0x5")), (1, stark_felt!("0x5")), (2, stark_felt!("0x7"))]
+    {
+        let proxy = VersionedStateProxy::new(tx_index, &versioned_state);
+        assert_eq!(proxy.get_storage_at(contract_address, key).unwrap(), expected_value);
+    }
+}
+
+#[test]
+fn test_validate_reads() {
+    let contract_address = contract_address!("0x1");
+    let key = StorageKey(patricia_key!("0x10"));
+    let class_hash = class_hash!("0x101");
+    let versioned_state = VersionedState::new(initial_state());
+
+    let proxy = VersionedStateProxy::new(2, &versioned_state);
+    proxy.get_storage_at(contract_address, key).unwrap();
+    proxy.get_compiled_contract_class(class_hash).unwrap_err();
+    let reads = proxy.into_reads();
+
+    let state = versioned_state;
+    assert!(state.validate_reads(2, &reads).unwrap());
+
+    // Writes of succeeding transactions do not invalidate the reads.
+    let storage_writes = StateWrites {
+        storage: HashMap::from([((contract_address, key), stark_felt!("0x7"))]),
+        ..Default::default()
+    };
+    state.apply_writes(3, storage_writes);
+    assert!(state.validate_reads(2, &reads).unwrap());
+
+    // A preceding transaction declares the class read as undeclared.
+    let declare_writes = StateWrites {
+        contract_classes: HashMap::from([(class_hash, get_test_contract_class())]),
+        ..Default::default()
+    };
+    state.apply_writes(1, declare_writes);
+    assert!(!state.validate_reads(2, &reads).unwrap());
+
+    // A re-execution of the preceding transaction no longer declares it.
+    state.apply_writes(1, StateWrites::default());
+    assert!(state.validate_reads(2, &reads).unwrap());
+
+    let storage_writes = StateWrites {
+        storage: HashMap::from([((contract_address, key), stark_felt!("0x7"))]),
+        ..Default::default()
+    };
+    state.apply_writes(0, storage_writes);
+    assert!(!state.validate_reads(2, &reads).unwrap());
+}
+
+#[test]
+fn test_initial_values_are_cached() {
+    let contract_address: ContractAddress = contract_address!("0x1");
+    let mut state = VersionedState::new(initial_state());
+    assert_eq!(state.get_class_hash_at(0, contract_address).unwrap(), ClassHash::default());
+
+    // Changes to the initial state after the first read are not visible.
+    state.initial_state.address_to_class_hash.insert(contract_address, class_hash!("0x3"));
+    assert_eq!(state.get_class_hash_at(1, contract_address).unwrap(), ClassHash::default());
+}
@@ -0,0 +1,70 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::concurrency::TxIndex;
+
+#[cfg(test)]
+#[path = "versioned_storage_test.rs"]
+pub mod test;
+
+/// A storage unit that holds, per key, the values written by each transaction in the batch (the
+/// key's versions), on top of the cached initial values (i.e., the values before the batch).
+///
+/// Reading a key on behalf of a transaction returns the value written by the closest preceding
+/// transaction; or, if no preceding transaction wrote to it, the initial value (if cached).
+#[derive(Debug)]
+pub struct VersionedStorage<K, V> {
+    cached_initial_values: HashMap<K, V>,
+    writes: HashMap<K, BTreeMap<TxIndex, V>>,
+    // Used to discard the writes of a previous execution of a transaction.
+    written_keys: HashMap<TxIndex, HashSet<K>>,
+}
+
+impl<K, V> Default for VersionedStorage<K, V> {
+    fn default() -> Self {
+        Self {
+            cached_initial_values: HashMap::default(),
+            writes: HashMap::default(),
+            written_keys: HashMap::default(),
+        }
+    }
+}
+
+impl<K, V> VersionedStorage<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    /// Returns the value of the given key, as visible to the transaction at the given index.
+    /// Returns `None` if the key was neither written by a preceding transaction, nor cached.
+    pub fn read(&self, tx_index: TxIndex, key: &K) -> Option<V> {
+        let preceding_write = self
+            .writes
+            .get(key)
+            .and_then(|versions| versions.range(..tx_index).next_back())
+            .map(|(_, value)| value);
+
+        preceding_write.or_else(|| self.cached_initial_values.get(key)).cloned()
+    }
+
+    pub fn set_initial_value(&mut self, key: K, value: V) {
+        self.cached_initial_values.insert(key, value);
+    }
+
+    /// Sets the writes of the transaction at the given index, discarding the writes of its
+    /// previous execution (if any).
+    pub fn set_writes(&mut self, tx_index: TxIndex, writes: HashMap<K, V>) {
+        for key in self.written_keys.remove(&tx_index).unwrap_or_default() {
+            if let Some(versions) = self.writes.get_mut(&key) {
+                versions.remove(&tx_index);
+            }
+        }
+
+        let mut written_keys = HashSet::with_capacity(writes.len());
+        for (key, value) in writes {
+            written_keys.insert(key.clone());
+            self.writes.entry(key).or_default().insert(tx_index, value);
+        }
+        self.written_keys.insert(tx_index, written_keys);
+    }
+}
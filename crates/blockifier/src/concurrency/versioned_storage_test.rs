@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+
+use crate::concurrency::versioned_storage::VersionedStorage;
+
+#[test]
+fn test_read_versions() {
+    let mut storage: VersionedStorage<u8, u8> = VersionedStorage::default();
+    assert_eq!(storage.read(0, &1), None);
+
+    storage.set_initial_value(1, 10);
+    storage.set_writes(2, HashMap::from([(1, 12)]));
+    storage.set_writes(5, HashMap::from([(1, 15), (2, 25)]));
+
+    // Transactions see the initial value, or the write of the closest preceding transaction.
+    assert_eq!(storage.read(0, &1), Some(10));
+    assert_eq!(storage.read(2, &1), Some(10));
+    assert_eq!(storage.read(3, &1), Some(12));
+    assert_eq!(storage.read(5, &1), Some(12));
+    assert_eq!(storage.read(6, &1), Some(15));
+
+    // No initial value is cached for this key.
+    assert_eq!(storage.read(5, &2), None);
+    assert_eq!(storage.read(6, &2), Some(25));
+}
+
+#[test]
+fn test_rewrite_discards_previous_writes() {
+    let mut storage: VersionedStorage<u8, u8> = VersionedStorage::default();
+    storage.set_initial_value(1, 10);
+    storage.set_writes(2, HashMap::from([(1, 12), (2, 22)]));
+
+    // A re-execution of the transaction only wrote to the second key.
+    storage.set_writes(2, HashMap::from([(2, 23)]));
+    assert_eq!(storage.read(3, &1), Some(10));
+    assert_eq!(storage.read(3, &2), Some(23));
+
+    storage.set_writes(2, HashMap::new());
+    assert_eq!(storage.read(3, &2), None);
+}
@@ -1,6 +1,7 @@
 use starknet_api::core::{ChainId, ContractAddress};
 
 use crate::blockifier::block::BlockInfo;
+use crate::execution::vm_tracer::VmTracer;
 use crate::transaction::objects::{FeeType, TransactionInfo, TransactionInfoCreator};
 use crate::versioned_constants::VersionedConstants;
 
@@ -11,11 +12,21 @@ pub struct TransactionContext {
     pub tx_info: TransactionInfo,
 }
 
+impl TransactionContext {
+    pub fn is_sequencer_the_sender(&self) -> bool {
+        self.tx_info.sender_address() == self.block_context.block_info.sequencer_address
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct BlockContext {
     pub(crate) block_info: BlockInfo,
     pub(crate) chain_info: ChainInfo,
     pub(crate) versioned_constants: VersionedConstants,
+    pub(crate) vm_tracer: Option<VmTracer>,
+    // Whether the transactions are executed concurrently with the other transactions of the
+    // block; see `ParallelExecutor`.
+    pub(crate) concurrency_mode: bool,
 }
 
 impl BlockContext {
@@ -31,6 +42,8 @@ impl BlockContext {
             block_info: block_info.clone(),
             chain_info: chain_info.clone(),
             versioned_constants: versioned_constants.clone(),
+            vm_tracer: None,
+            concurrency_mode: false,
         }
     }
 
@@ -45,6 +58,12 @@ impl BlockContext {
     pub fn versioned_constants(&self) -> &VersionedConstants {
         &self.versioned_constants
     }
+
+    /// Returns this context, with the given tracer recording the VM traces of the transactions
+    /// executed with it; tracing is off by default, and is not supported in parallel execution.
+    pub fn with_vm_tracer(self, vm_tracer: VmTracer) -> Self {
+        Self { vm_tracer: Some(vm_tracer), ..self }
+    }
 }
 
 impl BlockContext {
@@ -1,4 +1,5 @@
 pub mod call_info;
+pub mod class_hash;
 pub mod common_hints;
 pub mod contract_address;
 pub mod contract_class;
@@ -10,3 +11,5 @@ pub mod errors;
 pub mod execution_utils;
 pub mod hint_code;
 pub mod syscalls;
+pub mod view_call;
+pub mod vm_tracer;
@@ -0,0 +1,299 @@
+use cairo_lang_starknet_classes::NestedIntList;
+use cairo_vm::types::errors::program_errors::ProgramError;
+use serde_json::Value;
+use starknet_api::core::{ClassHash, CompiledClassHash};
+use starknet_api::deprecated_contract_class::{
+    ContractClass as DeprecatedContractClass, EntryPointType as DeprecatedEntryPointType,
+};
+use starknet_api::hash::{pedersen_hash_array, poseidon_hash_array, StarkFelt};
+use starknet_api::state::{ContractClass as SierraContractClass, EntryPointType};
+use starknet_crypto::FieldElement;
+
+use crate::abi::abi_utils::starknet_keccak;
+use crate::execution::contract_class::{ContractClassResult, ContractClassV1};
+use crate::execution::errors::ContractClassError;
+use crate::execution::execution_utils::felt_to_stark_felt;
+use crate::utils::{short_string, usize_to_felt};
+
+#[cfg(test)]
+#[path = "class_hash_test.rs"]
+pub mod test;
+
+pub const COMPILED_CLASS_VERSION: StarkFelt = short_string(b"COMPILED_CLASS_V1");
+pub const CONTRACT_CLASS_VERSION: StarkFelt = short_string(b"CONTRACT_CLASS_V0.1.0");
+// The API version of deprecated (Cairo 0) classes.
+pub const DEPRECATED_CONTRACT_CLASS_VERSION: StarkFelt = StarkFelt::ZERO;
+
+/// Computes the compiled class hash of a Cairo 1 class: the Poseidon hash of its entry points and
+/// of its bytecode, which is hashed by its segments.
+pub fn calculate_compiled_class_hash(
+    contract_class: &ContractClassV1,
+) -> ContractClassResult<CompiledClassHash> {
+    let entry_points_hash = |entry_point_type| {
+        let hash_chain: Vec<StarkFelt> = contract_class
+            .entry_points_by_type
+            .get(&entry_point_type)
+            .into_iter()
+            .flatten()
+            .flat_map(|entry_point| {
+                // The entry points of the runnable class carry the full builtin names.
+                let builtins: Vec<StarkFelt> = entry_point
+                    .builtins
+                    .iter()
+                    .map(|builtin| {
+                        short_string(builtin.strip_suffix("_builtin").unwrap_or(builtin).as_bytes())
+                    })
+                    .collect();
+                [
+                    entry_point.selector.0,
+                    usize_to_felt(entry_point.offset.0),
+                    poseidon_hash_array(&builtins).0,
+                ]
+            })
+            .collect();
+        poseidon_hash_array(&hash_chain).0
+    };
+
+    let mut bytecode = contract_class.program.iter_data().map(|value| {
+        felt_to_stark_felt(value.get_int_ref().expect("Casm bytecode should consist of felts."))
+    });
+    let bytecode_length = contract_class.bytecode_length();
+    let bytecode_hash =
+        bytecode_hash_node(&mut bytecode, contract_class.bytecode_segment_lengths())
+            .filter(|(segments_length, _)| *segments_length == bytecode_length)
+            .map(|(_, bytecode_hash)| bytecode_hash)
+            .ok_or(ContractClassError::BytecodeSegmentLengthsMismatch { bytecode_length })?;
+
+    Ok(CompiledClassHash(
+        poseidon_hash_array(&[
+            COMPILED_CLASS_VERSION,
+            entry_points_hash(DeprecatedEntryPointType::External),
+            entry_points_hash(DeprecatedEntryPointType::L1Handler),
+            entry_points_hash(DeprecatedEntryPointType::Constructor),
+            bytecode_hash,
+        ])
+        .0,
+    ))
+}
+
+/// Computes the class hash of a Sierra class: the Poseidon hash of its entry points, ABI and
+/// program.
+pub fn calculate_class_hash(contract_class: &SierraContractClass) -> ClassHash {
+    let entry_points_hash = |entry_point_type| {
+        let hash_chain: Vec<StarkFelt> = contract_class
+            .entry_points_by_type
+            .get(&entry_point_type)
+            .into_iter()
+            .flatten()
+            .flat_map(|entry_point| {
+                [entry_point.selector.0, usize_to_felt(entry_point.function_idx.0)]
+            })
+            .collect();
+        poseidon_hash_array(&hash_chain).0
+    };
+
+    ClassHash(
+        poseidon_hash_array(&[
+            CONTRACT_CLASS_VERSION,
+            entry_points_hash(EntryPointType::External),
+            entry_points_hash(EntryPointType::L1Handler),
+            entry_points_hash(EntryPointType::Constructor),
+            felt_to_stark_felt(&starknet_keccak(contract_class.abi.as_bytes())),
+            poseidon_hash_array(&contract_class.sierra_program).0,
+        ])
+        .0,
+    )
+}
+
+/// Computes the class hash of a deprecated (Cairo 0) class: the Pedersen hash of its entry points,
+/// builtins, hinted class hash and bytecode.
+pub fn calculate_deprecated_class_hash(
+    contract_class: &DeprecatedContractClass,
+) -> ContractClassResult<ClassHash> {
+    let entry_points_hash = |entry_point_type| {
+        let hash_chain: Vec<StarkFelt> = contract_class
+            .entry_points_by_type
+            .get(&entry_point_type)
+            .into_iter()
+            .flatten()
+            .flat_map(|entry_point| [entry_point.selector.0, usize_to_felt(entry_point.offset.0)])
+            .collect();
+        pedersen_hash_array(&hash_chain)
+    };
+
+    let program = &contract_class.program;
+    let builtins: Vec<String> =
+        serde_json::from_value(program.builtins.clone()).map_err(ProgramError::Parse)?;
+    let builtins: Vec<StarkFelt> =
+        builtins.iter().map(|builtin| short_string(builtin.as_bytes())).collect();
+    let bytecode: Vec<StarkFelt> =
+        serde_json::from_value(program.data.clone()).map_err(ProgramError::Parse)?;
+
+    Ok(ClassHash(pedersen_hash_array(&[
+        DEPRECATED_CONTRACT_CLASS_VERSION,
+        entry_points_hash(DeprecatedEntryPointType::External),
+        entry_points_hash(DeprecatedEntryPointType::L1Handler),
+        entry_points_hash(DeprecatedEntryPointType::Constructor),
+        pedersen_hash_array(&builtins),
+        calculate_hinted_class_hash(contract_class),
+        pedersen_hash_array(&bytecode),
+    ])))
+}
+
+/// Computes the hinted class hash of a deprecated class: the Starknet Keccak of its ABI and
+/// program (sans debug info), in the JSON format of the Cairo 0 compiler, i.e., that of Python's
+/// `json.dumps(..., sort_keys=True)`.
+pub fn calculate_hinted_class_hash(contract_class: &DeprecatedContractClass) -> StarkFelt {
+    let mut program = serde_json::to_value(&contract_class.program)
+        .expect("Program serialization should not fail.");
+    let program_fields = program.as_object_mut().expect("A program should serialize to an object.");
+    program_fields.insert("debug_info".to_string(), Value::Null);
+
+    // Fields added to programs after the first classes were declared are omitted when empty, to
+    // keep the hashes of those classes.
+    match program_fields.get_mut("attributes") {
+        Some(Value::Array(attributes)) if !attributes.is_empty() => {
+            for attribute in attributes.iter_mut().filter_map(Value::as_object_mut) {
+                if attribute
+                    .get("accessible_scopes")
+                    .and_then(Value::as_array)
+                    .is_some_and(Vec::is_empty)
+                {
+                    attribute.remove("accessible_scopes");
+                }
+                if attribute.get("flow_tracking_data").is_some_and(Value::is_null) {
+                    attribute.remove("flow_tracking_data");
+                }
+            }
+        }
+        _ => {
+            program_fields.remove("attributes");
+        }
+    }
+    if matches!(program_fields.get("compiler_version"), None | Some(Value::Null)) {
+        program_fields.remove("compiler_version");
+        // Classes compiled before Cairo 0.10 were hashed with `(a : felt)`-style named tuples.
+        for field in ["identifiers", "reference_manager"] {
+            if let Some(value) = program_fields.get_mut(field) {
+                add_space_before_named_tuple_colons(value);
+            }
+        }
+    }
+
+    let abi =
+        serde_json::to_value(&contract_class.abi).expect("ABI serialization should not fail.");
+    let class = Value::Object(
+        [("abi".to_string(), abi), ("program".to_string(), program)].into_iter().collect(),
+    );
+    let mut serialized_class = String::new();
+    write_python_json(&class, &mut serialized_class);
+
+    felt_to_stark_felt(&starknet_keccak(serialized_class.as_bytes()))
+}
+
+/// Returns the length and hash of the given bytecode segment, or [None] if it exceeds the
+/// bytecode; see `bytecode_hash_node` in the Starknet OS.
+fn bytecode_hash_node(
+    bytecode: &mut impl Iterator<Item = StarkFelt>,
+    node: &NestedIntList,
+) -> Option<(usize, StarkFelt)> {
+    match node {
+        NestedIntList::Leaf(length) => {
+            let segment: Vec<StarkFelt> = bytecode.take(*length).collect();
+            if segment.len() != *length {
+                return None;
+            }
+            Some((*length, poseidon_hash_array(&segment).0))
+        }
+        NestedIntList::Node(nodes) => {
+            // The hash is `1 + poseidon(len0, hash0, len1, hash1, ...)`.
+            let inner_nodes: Vec<(usize, StarkFelt)> = nodes
+                .iter()
+                .map(|node| bytecode_hash_node(bytecode, node))
+                .collect::<Option<_>>()?;
+            let length = inner_nodes.iter().map(|(length, _)| length).sum();
+            let hash_chain: Vec<StarkFelt> = inner_nodes
+                .into_iter()
+                .flat_map(|(length, hash)| [usize_to_felt(length), hash])
+                .collect();
+            let hash = FieldElement::from(poseidon_hash_array(&hash_chain).0) + FieldElement::ONE;
+            Some((length, hash.into()))
+        }
+    }
+}
+
+fn add_space_before_named_tuple_colons(value: &mut Value) {
+    match value {
+        Value::Array(values) => values.iter_mut().for_each(add_space_before_named_tuple_colons),
+        Value::Object(fields) => {
+            for (key, value) in fields.iter_mut() {
+                match value {
+                    Value::String(string) if key == "cairo_type" || key == "value" => {
+                        // Colons already preceded by a space are restored after the first
+                        // replacement.
+                        *string = string.replace(": ", " : ").replace("  :", " :");
+                    }
+                    _ => add_space_before_named_tuple_colons(value),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Writes the given value as Python's `json.dumps(value, sort_keys=True)` does: with spaces after
+/// separators, and with non-ASCII characters escaped.
+fn write_python_json(value: &Value, output: &mut String) {
+    match value {
+        Value::Null => output.push_str("null"),
+        Value::Bool(boolean) => output.push_str(if *boolean { "true" } else { "false" }),
+        Value::Number(number) => output.push_str(&number.to_string()),
+        Value::String(string) => write_python_json_string(string, output),
+        Value::Array(values) => {
+            output.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    output.push_str(", ");
+                }
+                write_python_json(value, output);
+            }
+            output.push(']');
+        }
+        Value::Object(fields) => {
+            let mut fields: Vec<(&String, &Value)> = fields.iter().collect();
+            fields.sort_by_key(|(key, _)| *key);
+            output.push('{');
+            for (i, (key, value)) in fields.into_iter().enumerate() {
+                if i > 0 {
+                    output.push_str(", ");
+                }
+                write_python_json_string(key, output);
+                output.push_str(": ");
+                write_python_json(value, output);
+            }
+            output.push('}');
+        }
+    }
+}
+
+fn write_python_json_string(string: &str, output: &mut String) {
+    output.push('"');
+    for character in string.chars() {
+        match character {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\u{8}' => output.push_str("\\b"),
+            '\u{c}' => output.push_str("\\f"),
+            ' '..='~' => output.push(character),
+            _ => {
+                for code_unit in character.encode_utf16(&mut [0; 2]) {
+                    output.push_str(&format!("\\u{code_unit:04x}"));
+                }
+            }
+        }
+    }
+    output.push('"');
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::NestedIntList;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use serde_json::json;
+use starknet_api::core::{ClassHash, CompiledClassHash, EntryPointSelector};
+use starknet_api::deprecated_contract_class::{
+    ContractClass as DeprecatedContractClass, EntryPoint as DeprecatedEntryPoint, EntryPointOffset,
+    EntryPointType as DeprecatedEntryPointType, Program,
+};
+use starknet_api::hash::{pedersen_hash_array, poseidon_hash_array, StarkFelt};
+use starknet_api::stark_felt;
+use starknet_api::state::{
+    ContractClass as SierraContractClass, EntryPoint, EntryPointType, FunctionIndex,
+};
+
+use crate::abi::abi_utils::starknet_keccak;
+use crate::execution::class_hash::{
+    calculate_class_hash, calculate_compiled_class_hash, calculate_deprecated_class_hash,
+    calculate_hinted_class_hash, COMPILED_CLASS_VERSION, CONTRACT_CLASS_VERSION,
+};
+use crate::execution::contract_class::ContractClassV1;
+use crate::execution::errors::ContractClassError;
+use crate::execution::execution_utils::felt_to_stark_felt;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::{get_raw_contract_class, CairoVersion, MINIMAL_CONTRACT_CASM_PATH};
+
+// The account class of the Cairo compiler's test data (version 2.6.0), with bytecode segment
+// lengths; the compiler's tests pin its compiled class hash to a value computed by other
+// implementations.
+const CAIRO_LANG_ACCOUNT_CASM_PATH: &str = "./resources/test_data/cairo_lang_account.casm.json";
+const CAIRO_LANG_ACCOUNT_COMPILED_CLASS_HASH: &str =
+    "0x270073f38449c54ab27c253a8534f8db2060aacea7fc7d395fc4a22127432c4";
+
+fn keccak(data: &str) -> StarkFelt {
+    felt_to_stark_felt(&starknet_keccak(data.as_bytes()))
+}
+
+fn deprecated_class(program: serde_json::Value) -> DeprecatedContractClass {
+    DeprecatedContractClass {
+        abi: None,
+        program: serde_json::from_value(program).unwrap(),
+        entry_points_by_type: HashMap::from([(
+            DeprecatedEntryPointType::External,
+            vec![DeprecatedEntryPoint {
+                selector: EntryPointSelector(stark_felt!(7_u8)),
+                offset: EntryPointOffset(1),
+            }],
+        )]),
+    }
+}
+
+fn minimal_program() -> serde_json::Value {
+    json!({
+        "builtins": ["pedersen", "range_check"],
+        "data": ["0x1", "0x2"],
+        "debug_info": {"file_contents": {}},
+        "hints": {},
+        "identifiers": {},
+        "main_scope": "__main__",
+        "prime": "0x800000000000011000000000000000000000000000000000000000000000001",
+        "reference_manager": {"references": []},
+        "attributes": [],
+        "compiler_version": "0.10.3",
+    })
+}
+
+#[test]
+fn test_class_versions() {
+    assert_eq!(COMPILED_CLASS_VERSION, stark_felt!("0x434f4d50494c45445f434c4153535f5631"));
+    assert_eq!(CONTRACT_CLASS_VERSION, stark_felt!("0x434f4e54524143545f434c4153535f56302e312e30"));
+}
+
+#[rstest]
+fn test_compiled_class_hash(
+    #[values(
+        FeatureContract::TestContract(CairoVersion::Cairo1).get_raw_class(),
+        FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1).get_raw_class(),
+        FeatureContract::Empty(CairoVersion::Cairo1).get_raw_class(),
+        get_raw_contract_class(MINIMAL_CONTRACT_CASM_PATH),
+    )]
+    raw_class: String,
+) {
+    let casm_class: CasmContractClass = serde_json::from_str(&raw_class).unwrap();
+    let expected_compiled_class_hash =
+        CompiledClassHash(felt_to_stark_felt(&casm_class.compiled_class_hash()));
+
+    let contract_class = ContractClassV1::try_from(casm_class).unwrap();
+    assert_eq!(
+        calculate_compiled_class_hash(&contract_class).unwrap(),
+        expected_compiled_class_hash
+    );
+}
+
+#[test]
+fn test_compiled_class_hash_of_known_class() {
+    let raw_class = get_raw_contract_class(CAIRO_LANG_ACCOUNT_CASM_PATH);
+    let casm_class: CasmContractClass = serde_json::from_str(&raw_class).unwrap();
+    assert_matches!(casm_class.bytecode_segment_lengths, Some(NestedIntList::Node(_)));
+
+    let contract_class = ContractClassV1::try_from(casm_class).unwrap();
+    assert_eq!(
+        calculate_compiled_class_hash(&contract_class).unwrap(),
+        CompiledClassHash(stark_felt!(CAIRO_LANG_ACCOUNT_COMPILED_CLASS_HASH))
+    );
+}
+
+#[rstest]
+#[case::too_short(-1)]
+#[case::too_long(1)]
+fn test_compiled_class_hash_with_malformed_segment_lengths(#[case] length_offset: isize) {
+    let raw_class = FeatureContract::TestContract(CairoVersion::Cairo1).get_raw_class();
+    let mut casm_class: CasmContractClass = serde_json::from_str(&raw_class).unwrap();
+    let bytecode_length = casm_class.bytecode.len();
+    let segment_length = bytecode_length.checked_add_signed(length_offset).unwrap();
+    casm_class.bytecode_segment_lengths = Some(NestedIntList::Node(vec![
+        NestedIntList::Leaf(1),
+        NestedIntList::Leaf(segment_length - 1),
+    ]));
+
+    let contract_class = ContractClassV1::try_from(casm_class).unwrap();
+    assert_matches!(
+        calculate_compiled_class_hash(&contract_class),
+        Err(ContractClassError::BytecodeSegmentLengthsMismatch { bytecode_length: length })
+        if length == bytecode_length
+    );
+}
+
+#[test]
+fn test_class_hash() {
+    let contract_class = SierraContractClass {
+        sierra_program: vec![stark_felt!(1_u8), stark_felt!(2_u8)],
+        entry_points_by_type: HashMap::from([
+            (
+                EntryPointType::External,
+                vec![
+                    EntryPoint {
+                        function_idx: FunctionIndex(3),
+                        selector: EntryPointSelector(stark_felt!(4_u8)),
+                    },
+                    EntryPoint {
+                        function_idx: FunctionIndex(5),
+                        selector: EntryPointSelector(stark_felt!(6_u8)),
+                    },
+                ],
+            ),
+            (EntryPointType::Constructor, vec![]),
+        ]),
+        abi: "[]".to_string(),
+    };
+
+    let expected_class_hash = poseidon_hash_array(&[
+        CONTRACT_CLASS_VERSION,
+        poseidon_hash_array(&[
+            stark_felt!(4_u8),
+            stark_felt!(3_u8),
+            stark_felt!(6_u8),
+            stark_felt!(5_u8),
+        ])
+        .0,
+        poseidon_hash_array(&[]).0,
+        poseidon_hash_array(&[]).0,
+        keccak("[]"),
+        poseidon_hash_array(&[stark_felt!(1_u8), stark_felt!(2_u8)]).0,
+    ]);
+    assert_eq!(calculate_class_hash(&contract_class), ClassHash(expected_class_hash.0));
+}
+
+#[test]
+fn test_deprecated_class_hash() {
+    let contract_class = deprecated_class(minimal_program());
+
+    let expected_class_hash = pedersen_hash_array(&[
+        StarkFelt::ZERO,
+        pedersen_hash_array(&[stark_felt!(7_u8), StarkFelt::ONE]),
+        pedersen_hash_array(&[]),
+        pedersen_hash_array(&[]),
+        pedersen_hash_array(&[
+            stark_felt!("0x706564657273656e"),
+            stark_felt!("0x72616e67655f636865636b"),
+        ]),
+        calculate_hinted_class_hash(&contract_class),
+        pedersen_hash_array(&[stark_felt!(1_u8), stark_felt!(2_u8)]),
+    ]);
+    assert_eq!(
+        calculate_deprecated_class_hash(&contract_class).unwrap(),
+        ClassHash(expected_class_hash)
+    );
+}
+
+#[test]
+fn test_hinted_class_hash() {
+    let mut program = minimal_program();
+    program["identifiers"] = json!({"__main__.f": {"type": "function", "decorators": ["é\"\n"]}});
+    let contract_class = deprecated_class(program);
+
+    // The debug info is dropped, as are the empty attributes; the rest is serialized with sorted
+    // keys and escaped non-ASCII characters.
+    let expected_serialization = concat!(
+        r#"{"abi": null, "program": {"builtins": ["pedersen", "range_check"], "#,
+        r#""compiler_version": "0.10.3", "data": ["0x1", "0x2"], "debug_info": null, "#,
+        r#""hints": {}, "identifiers": {"__main__.f": {"decorators": ["\u00e9\"\n"], "#,
+        r#""type": "function"}}, "main_scope": "__main__", "#,
+        r#""prime": "0x800000000000011000000000000000000000000000000000000000000000001", "#,
+        r#""reference_manager": {"references": []}}}"#
+    );
+    assert_eq!(calculate_hinted_class_hash(&contract_class), keccak(expected_serialization));
+}
+
+#[test]
+fn test_hinted_class_hash_of_pre_cairo_0_10_class() {
+    let mut program = minimal_program();
+    program["compiler_version"] = serde_json::Value::Null;
+    program["identifiers"] = json!({
+        "__main__.f.Args": {"cairo_type": "(a: felt, b : felt*)", "type": "type_definition"},
+    });
+    program["reference_manager"] =
+        json!({"references": [{"pc": 0, "value": "cast(fp + (-3), (x: felt))"}]});
+    let contract_class = deprecated_class(program);
+
+    // Named tuples are serialized with a space before their colons, as the Cairo 0 compiler did
+    // before version 0.10.
+    let expected_serialization = concat!(
+        r#"{"abi": null, "program": {"builtins": ["pedersen", "range_check"], "#,
+        r#""data": ["0x1", "0x2"], "debug_info": null, "hints": {}, "#,
+        r#""identifiers": {"__main__.f.Args": {"cairo_type": "(a : felt, b : felt*)", "#,
+        r#""type": "type_definition"}}, "main_scope": "__main__", "#,
+        r#""prime": "0x800000000000011000000000000000000000000000000000000000000000001", "#,
+        r#""reference_manager": {"references": [{"pc": 0, "#,
+        r#""value": "cast(fp + (-3), (x : felt))"}]}}}"#
+    );
+    assert_eq!(calculate_hinted_class_hash(&contract_class), keccak(expected_serialization));
+}
+
+#[test]
+fn test_deprecated_class_hash_of_feature_contract() {
+    let contract_class: DeprecatedContractClass =
+        serde_json::from_str(&FeatureContract::TestContract(CairoVersion::Cairo0).get_raw_class())
+            .unwrap();
+    let program: &Program = &contract_class.program;
+    let bytecode: Vec<StarkFelt> = serde_json::from_value(program.data.clone()).unwrap();
+
+    // The class hash commits to the bytecode.
+    let mut modified_contract_class = contract_class.clone();
+    modified_contract_class.program.data =
+        serde_json::to_value(&bytecode[..bytecode.len() - 1]).unwrap();
+    assert_ne!(
+        calculate_deprecated_class_hash(&contract_class).unwrap(),
+        calculate_deprecated_class_hash(&modified_contract_class).unwrap()
+    );
+}
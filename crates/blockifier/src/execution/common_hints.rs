@@ -31,6 +31,9 @@ pub enum ExecutionMode {
     Execute,
     /// Validate execution mode.
     Validate,
+    /// Read-only execution mode; syscalls that modify the state or emit output (events and
+    /// messages to L1) fail.
+    ReadOnly,
 }
 
 pub type HintExecutionResult = Result<(), HintError>;
@@ -5,7 +5,9 @@ use std::sync::Arc;
 use cairo_felt::Felt252;
 use cairo_lang_casm;
 use cairo_lang_casm::hints::Hint;
+use cairo_lang_starknet_classes::allowed_libfuncs::ListSelector;
 use cairo_lang_starknet_classes::casm_contract_class::{CasmContractClass, CasmContractEntryPoint};
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
 use cairo_lang_starknet_classes::NestedIntList;
 use cairo_vm::serde::deserialize_program::{
     ApTracking, FlowTrackingData, HintParams, ReferenceManager,
@@ -16,8 +18,9 @@ use cairo_vm::types::relocatable::MaybeRelocatable;
 use cairo_vm::vm::runners::builtin_runner::{HASH_BUILTIN_NAME, POSEIDON_BUILTIN_NAME};
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use serde::de::Error as DeserializationError;
-use serde::{Deserialize, Deserializer};
-use starknet_api::core::EntryPointSelector;
+use serde::ser::Error as SerializationError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use starknet_api::core::{CompiledClassHash, EntryPointSelector};
 use starknet_api::deprecated_contract_class::{
     ContractClass as DeprecatedContractClass, EntryPoint, EntryPointOffset, EntryPointType,
     Program as DeprecatedProgram,
@@ -26,9 +29,15 @@ use starknet_api::deprecated_contract_class::{
 use super::execution_utils::poseidon_hash_many_cost;
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants::{self, CONSTRUCTOR_ENTRY_POINT_NAME};
+use crate::execution::class_hash::calculate_compiled_class_hash;
 use crate::execution::entry_point::CallEntryPoint;
 use crate::execution::errors::{ContractClassError, PreExecutionError};
 use crate::execution::execution_utils::{felt_to_stark_felt, sn_api_to_cairo_vm_program};
+
+#[cfg(test)]
+#[path = "contract_class_test.rs"]
+pub mod test;
+
 /// Represents a runnable Starknet contract class (meaning, the program is runnable by the VM).
 /// We wrap the actual class in an Arc to avoid cloning the program when cloning the class.
 // Note: when deserializing from a SN API class JSON string, the ABI field is ignored
@@ -65,6 +74,96 @@ impl ContractClass {
     }
 }
 
+// Unlike the compiled class formats, this representation is lossless; it is used to persist
+// classes fetched from the state (e.g., in state recordings).
+impl Serialize for ContractClass {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedContractClass::try_from(self)
+            .map_err(|err| SerializationError::custom(err.to_string()))?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ContractClass {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SerializedContractClass::deserialize(deserializer)?
+            .try_into()
+            .map_err(|err: ProgramError| DeserializationError::custom(err.to_string()))
+    }
+}
+
+/// The serialized form of a contract class; programs are kept in the Cairo VM program
+/// serialization format.
+#[derive(Deserialize, Serialize)]
+enum SerializedContractClass {
+    V0 {
+        program: serde_json::Value,
+        entry_points_by_type: HashMap<EntryPointType, Vec<EntryPoint>>,
+    },
+    V1 {
+        program: serde_json::Value,
+        entry_points_by_type: HashMap<EntryPointType, Vec<EntryPointV1>>,
+        hints: HashMap<String, Hint>,
+        bytecode_segment_lengths: NestedIntList,
+    },
+}
+
+/// The parts of a serialized Cairo 1 program that are not derived from the bytecode.
+#[derive(Deserialize)]
+struct SerializedProgramV1 {
+    data: Vec<MaybeRelocatable>,
+    hints: HashMap<usize, Vec<HintParams>>,
+}
+
+impl TryFrom<&ContractClass> for SerializedContractClass {
+    type Error = ProgramError;
+
+    fn try_from(class: &ContractClass) -> Result<Self, Self::Error> {
+        Ok(match class {
+            ContractClass::V0(class) => Self::V0 {
+                program: serde_json::from_slice(&class.program.serialize()?)?,
+                entry_points_by_type: class.entry_points_by_type.clone(),
+            },
+            ContractClass::V1(class) => Self::V1 {
+                program: serde_json::from_slice(&class.program.serialize()?)?,
+                entry_points_by_type: class.entry_points_by_type.clone(),
+                hints: class.hints.clone(),
+                bytecode_segment_lengths: class.bytecode_segment_lengths.clone(),
+            },
+        })
+    }
+}
+
+impl TryFrom<SerializedContractClass> for ContractClass {
+    type Error = ProgramError;
+
+    fn try_from(class: SerializedContractClass) -> Result<Self, Self::Error> {
+        Ok(match class {
+            SerializedContractClass::V0 { program, entry_points_by_type } => {
+                let program = Program::deserialize(&serde_json::to_vec(&program)?, None)?;
+                ContractClassV0(Arc::new(ContractClassV0Inner { program, entry_points_by_type }))
+                    .into()
+            }
+            SerializedContractClass::V1 {
+                program,
+                entry_points_by_type,
+                hints,
+                bytecode_segment_lengths,
+            } => {
+                let SerializedProgramV1 { data, hints: hint_params } =
+                    serde_json::from_value(program)?;
+                ContractClassV1(Arc::new(ContractClassV1Inner {
+                    program: program_v1(data, hint_params)?,
+                    entry_points_by_type,
+                    hints,
+                    bytecode_segment_lengths,
+                }))
+                .into()
+            }
+        })
+    }
+}
+
 // V0.
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
 pub struct ContractClassV0(pub Arc<ContractClassV0Inner>);
@@ -155,6 +254,26 @@ impl ContractClassV1 {
         self.program.data_len()
     }
 
+    pub fn bytecode_segment_lengths(&self) -> &NestedIntList {
+        &self.bytecode_segment_lengths
+    }
+
+    /// Checks that the compiled class hash of this class is the given (declared) one.
+    pub fn verify_compiled_class_hash(
+        &self,
+        compiled_class_hash: CompiledClassHash,
+    ) -> ContractClassResult<()> {
+        let actual_compiled_class_hash = calculate_compiled_class_hash(self)?;
+        if actual_compiled_class_hash != compiled_class_hash {
+            return Err(ContractClassError::CompiledClassHashMismatch {
+                expected: compiled_class_hash,
+                actual: actual_compiled_class_hash,
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn get_entry_point(
         &self,
         call: &CallEntryPoint,
@@ -259,7 +378,7 @@ pub struct ContractClassV1Inner {
     bytecode_segment_lengths: NestedIntList,
 }
 
-#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct EntryPointV1 {
     pub selector: EntryPointSelector,
     pub offset: EntryPointOffset,
@@ -298,23 +417,7 @@ impl TryFrom<CasmContractClass> for ContractClassV1 {
             }
         }
 
-        let builtins = vec![]; // The builtins are initialize later.
-        let main = Some(0);
-        let reference_manager = ReferenceManager { references: Vec::new() };
-        let identifiers = HashMap::new();
-        let error_message_attributes = vec![];
-        let instruction_locations = None;
-
-        let program = Program::new(
-            builtins,
-            data,
-            main,
-            hints,
-            reference_manager,
-            identifiers,
-            error_message_attributes,
-            instruction_locations,
-        )?;
+        let program = program_v1(data, hints)?;
 
         let mut entry_points_by_type = HashMap::new();
         entry_points_by_type.insert(
@@ -356,6 +459,30 @@ pub fn deserialize_program<'de, D: Deserializer<'de>>(
 
 // V1 utilities.
 
+/// Builds the runnable program of a Cairo 1 class from its bytecode and hints.
+fn program_v1(
+    data: Vec<MaybeRelocatable>,
+    hints: HashMap<usize, Vec<HintParams>>,
+) -> Result<Program, ProgramError> {
+    let builtins = vec![]; // The builtins are initialize later.
+    let main = Some(0);
+    let reference_manager = ReferenceManager { references: Vec::new() };
+    let identifiers = HashMap::new();
+    let error_message_attributes = vec![];
+    let instruction_locations = None;
+
+    Program::new(
+        builtins,
+        data,
+        main,
+        hints,
+        reference_manager,
+        identifiers,
+        error_message_attributes,
+        instruction_locations,
+    )
+}
+
 // TODO(spapini): Share with cairo-lang-runner.
 fn hint_to_hint_params(hint: &cairo_lang_casm::hints::Hint) -> Result<HintParams, ProgramError> {
     Ok(HintParams {
@@ -427,4 +554,57 @@ impl ClassInfo {
             })
         }
     }
+
+    /// Compiles the given Sierra class to Casm, and returns the info of the compiled class; the
+    /// ABI length is that of the ABI string of the declare transaction.
+    /// The compiled class must match the declared compiled class hash.
+    pub fn from_sierra(
+        sierra_class: SierraContractClass,
+        abi_length: usize,
+        compiled_class_hash: CompiledClassHash,
+        config: &SierraCompilationConfig,
+    ) -> ContractClassResult<Self> {
+        let sierra_program_length = sierra_class.sierra_program.len();
+
+        let casm_class = compile_sierra_class(sierra_class, config)?;
+        let contract_class = ContractClassV1::try_from(casm_class)?;
+        contract_class.verify_compiled_class_hash(compiled_class_hash)?;
+
+        Self::new(&ContractClass::V1(contract_class), sierra_program_length, abi_length)
+    }
+}
+
+/// The configuration of the in-process compilation of Sierra classes to Casm.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SierraCompilationConfig {
+    /// The libfuncs a Sierra class may use; by default, the audited ones.
+    pub allowed_libfuncs: ListSelector,
+    /// Whether to add the Python code of the hints to the compiled class.
+    pub add_pythonic_hints: bool,
+    /// The maximal length of the compiled bytecode.
+    pub max_bytecode_size: usize,
+}
+
+impl Default for SierraCompilationConfig {
+    fn default() -> Self {
+        Self {
+            allowed_libfuncs: ListSelector::default(),
+            add_pythonic_hints: false,
+            max_bytecode_size: usize::MAX,
+        }
+    }
+}
+
+/// Compiles the given Sierra class to Casm, after checking that it only uses allowed libfuncs.
+pub fn compile_sierra_class(
+    sierra_class: SierraContractClass,
+    config: &SierraCompilationConfig,
+) -> ContractClassResult<CasmContractClass> {
+    sierra_class.validate_version_compatible(config.allowed_libfuncs.clone())?;
+
+    Ok(CasmContractClass::from_contract_class(
+        sierra_class,
+        config.add_pythonic_hints,
+        config.max_bytecode_size,
+    )?)
 }
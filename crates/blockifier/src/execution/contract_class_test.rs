@@ -0,0 +1,94 @@
+use assert_matches::assert_matches;
+use cairo_lang_starknet_classes::allowed_libfuncs::{AllowedLibfuncsError, ListSelector};
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use pretty_assertions::assert_eq;
+use starknet_api::core::CompiledClassHash;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+
+use crate::execution::contract_class::{
+    ClassInfo, ContractClass, ContractClassV1, SierraCompilationConfig,
+};
+use crate::execution::errors::ContractClassError;
+use crate::execution::execution_utils::felt_to_stark_felt;
+use crate::test_utils::{
+    get_raw_contract_class, MINIMAL_CONTRACT_CASM_PATH, MINIMAL_CONTRACT_SIERRA_PATH,
+};
+
+fn sierra_class() -> SierraContractClass {
+    serde_json::from_str(&get_raw_contract_class(MINIMAL_CONTRACT_SIERRA_PATH)).unwrap()
+}
+
+fn casm_class() -> CasmContractClass {
+    serde_json::from_str(&get_raw_contract_class(MINIMAL_CONTRACT_CASM_PATH)).unwrap()
+}
+
+#[test]
+fn test_class_info_from_sierra() {
+    let casm_class = casm_class();
+    let compiled_class_hash =
+        CompiledClassHash(felt_to_stark_felt(&casm_class.compiled_class_hash()));
+    let sierra_class = sierra_class();
+    let sierra_program_length = sierra_class.sierra_program.len();
+    let abi_length = 10;
+
+    let class_info = ClassInfo::from_sierra(
+        sierra_class,
+        abi_length,
+        compiled_class_hash,
+        &SierraCompilationConfig::default(),
+    )
+    .unwrap();
+
+    // The in-process compilation matches that of the Sierra compiler.
+    assert_eq!(
+        class_info.contract_class(),
+        ContractClass::V1(ContractClassV1::try_from(casm_class).unwrap())
+    );
+    assert_eq!(class_info.sierra_program_length(), sierra_program_length);
+    assert_eq!(class_info.abi_length(), abi_length);
+}
+
+#[test]
+fn test_class_info_from_sierra_with_wrong_compiled_class_hash() {
+    let wrong_compiled_class_hash = CompiledClassHash(stark_felt!(1_u8));
+
+    let error = ClassInfo::from_sierra(
+        sierra_class(),
+        0,
+        wrong_compiled_class_hash,
+        &SierraCompilationConfig::default(),
+    )
+    .unwrap_err();
+    let expected_compiled_class_hash =
+        CompiledClassHash(felt_to_stark_felt(&casm_class().compiled_class_hash()));
+    assert_matches!(
+        error,
+        ContractClassError::CompiledClassHashMismatch { expected, actual }
+        if expected == wrong_compiled_class_hash && actual == expected_compiled_class_hash
+    );
+}
+
+#[test]
+fn test_sierra_compilation_config() {
+    let compiled_class_hash =
+        CompiledClassHash(felt_to_stark_felt(&casm_class().compiled_class_hash()));
+
+    let unknown_libfuncs_list = SierraCompilationConfig {
+        allowed_libfuncs: ListSelector::ListName("unknown".to_string()),
+        ..Default::default()
+    };
+    assert_matches!(
+        ClassInfo::from_sierra(sierra_class(), 0, compiled_class_hash, &unknown_libfuncs_list),
+        Err(ContractClassError::AllowedLibfuncsError(
+            AllowedLibfuncsError::UnexpectedAllowedLibfuncsList { .. }
+        ))
+    );
+
+    let tiny_bytecode_size = SierraCompilationConfig { max_bytecode_size: 1, ..Default::default() };
+    assert_matches!(
+        ClassInfo::from_sierra(sierra_class(), 0, compiled_class_hash, &tiny_bytecode_size),
+        Err(ContractClassError::SierraCompilationError(_))
+    );
+}
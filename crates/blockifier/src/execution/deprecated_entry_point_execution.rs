@@ -56,7 +56,12 @@ pub fn execute_entry_point_call(
     let previous_resources = syscall_handler.resources.clone();
 
     // Execute.
-    run_entry_point(&mut vm, &mut runner, &mut syscall_handler, entry_point_pc, args)?;
+    let run_result =
+        run_entry_point(&mut vm, &mut runner, &mut syscall_handler, entry_point_pc, args);
+    if let Some(vm_tracer) = &mut syscall_handler.context.vm_tracer {
+        vm_tracer.record_vm_trace(&mut vm, runner.get_program().data_len());
+    }
+    run_result?;
 
     Ok(finalize_execution(
         vm,
@@ -83,7 +88,8 @@ pub fn initialize_execution_context<'a>(
     let proof_mode = false;
     let mut runner = CairoRunner::new(&contract_class.program, "starknet", proof_mode)?;
 
-    let trace_enabled = false;
+    // The trace is only needed by the VM tracer.
+    let trace_enabled = context.vm_tracer.is_some();
     let mut vm = VirtualMachine::new(trace_enabled);
 
     runner.initialize_builtins(&mut vm)?;
@@ -199,6 +199,18 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         Ok(())
     }
 
+    /// Returns an error if the syscall is run in read-only mode.
+    pub fn verify_not_in_read_only_mode(&self, syscall_name: &str) -> DeprecatedSyscallResult<()> {
+        if self.execution_mode() == ExecutionMode::ReadOnly {
+            return Err(DeprecatedSyscallExecutionError::InvalidSyscallInExecutionMode {
+                syscall_name: syscall_name.to_string(),
+                execution_mode: self.execution_mode(),
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn verify_syscall_ptr(&self, actual_ptr: Relocatable) -> DeprecatedSyscallResult<()> {
         if actual_ptr != self.syscall_ptr {
             return Err(DeprecatedSyscallExecutionError::BadSyscallPointer {
@@ -223,6 +235,9 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
 
         let selector = DeprecatedSyscallSelector::try_from(self.read_next_syscall_selector(vm)?)?;
         self.increment_syscall_count(&selector);
+        if let Some(vm_tracer) = &mut self.context.vm_tracer {
+            vm_tracer.record_syscall(&selector);
+        }
 
         match selector {
             DeprecatedSyscallSelector::CallContract => self.execute_syscall(vm, call_contract),
@@ -297,8 +312,8 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         execute_callback: ExecuteCallback,
     ) -> HintExecutionResult
     where
-        Request: SyscallRequest,
-        Response: SyscallResponse,
+        Request: SyscallRequest + std::fmt::Debug,
+        Response: SyscallResponse + std::fmt::Debug,
         ExecuteCallback: FnOnce(
             Request,
             &mut VirtualMachine,
@@ -306,8 +321,14 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         ) -> DeprecatedSyscallResult<Response>,
     {
         let request = Request::read(vm, &mut self.syscall_ptr)?;
+        if let Some(vm_tracer) = &mut self.context.vm_tracer {
+            vm_tracer.record_syscall_request(&request);
+        }
 
         let response = execute_callback(request, vm, self)?;
+        if let Some(vm_tracer) = &mut self.context.vm_tracer {
+            vm_tracer.record_syscall_response(&response);
+        }
         response.write(vm, &mut self.syscall_ptr)?;
 
         Ok(())
@@ -415,6 +436,9 @@ impl HintProcessorLogic for DeprecatedSyscallHintProcessor<'_> {
         constants: &HashMap<String, Felt252>,
     ) -> HintExecutionResult {
         let hint = hint_data.downcast_ref::<HintProcessorData>().ok_or(HintError::WrongHintData)?;
+        if let Some(vm_tracer) = &mut self.context.vm_tracer {
+            vm_tracer.record_hint(vm.get_pc(), hint.code.clone());
+        }
         if hint_code::SYSCALL_HINTS.contains(hint.code.as_str()) {
             return self.execute_next_syscall(vm, &hint.ids_data, &hint.ap_tracking);
         }
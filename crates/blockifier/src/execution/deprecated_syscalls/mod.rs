@@ -308,6 +308,7 @@ pub fn deploy(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<DeployResponse> {
+    syscall_handler.verify_not_in_read_only_mode("deploy")?;
     let deployer_address = syscall_handler.storage_address;
     let deployer_address_for_calculation = match request.deploy_from_zero {
         true => ContractAddress::default(),
@@ -369,6 +370,7 @@ pub fn emit_event(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<EmitEventResponse> {
+    syscall_handler.verify_not_in_read_only_mode("emit_event")?;
     let execution_context = &mut syscall_handler.context;
     exceeds_event_size_limit(
         execution_context.versioned_constants(),
@@ -411,7 +413,7 @@ pub fn get_block_number(
             (block_number.0 / versioned_constants.validate_block_number_rounding)
                 * versioned_constants.validate_block_number_rounding,
         ),
-        ExecutionMode::Execute => block_number,
+        ExecutionMode::Execute | ExecutionMode::ReadOnly => block_number,
     };
     Ok(GetBlockNumberResponse { block_number })
 }
@@ -444,7 +446,7 @@ pub fn get_block_timestamp(
             (block_timestamp.0 / versioned_constants.validate_timestamp_rounding)
                 * versioned_constants.validate_timestamp_rounding,
         ),
-        ExecutionMode::Execute => block_timestamp,
+        ExecutionMode::Execute | ExecutionMode::ReadOnly => block_timestamp,
     };
     Ok(GetBlockTimestampResponse { block_timestamp })
 }
@@ -629,6 +631,7 @@ pub fn replace_class(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<ReplaceClassResponse> {
+    syscall_handler.verify_not_in_read_only_mode("replace_class")?;
     // Ensure the class is declared (by reading it).
     syscall_handler.state.get_compiled_contract_class(request.class_hash)?;
     syscall_handler.state.set_class_hash_at(syscall_handler.storage_address, request.class_hash)?;
@@ -663,6 +666,7 @@ pub fn send_message_to_l1(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<SendMessageToL1Response> {
+    syscall_handler.verify_not_in_read_only_mode("send_message_to_l1")?;
     let execution_context = &mut syscall_handler.context;
     let ordered_message_to_l1 = OrderedL2ToL1Message {
         order: execution_context.n_sent_messages_to_l1,
@@ -737,6 +741,7 @@ pub fn storage_write(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<StorageWriteResponse> {
+    syscall_handler.verify_not_in_read_only_mode("storage_write")?;
     // Read the value before the write operation in order to log it in the list of read·
     // values. This is needed to correctly build the `DictAccess` entry corresponding to·
     // `storage_write` syscall in the OS.
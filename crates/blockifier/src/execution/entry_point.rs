@@ -16,6 +16,7 @@ use crate::execution::call_info::CallInfo;
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::errors::{EntryPointExecutionError, PreExecutionError};
 use crate::execution::execution_utils::execute_entry_point_call;
+use crate::execution::vm_tracer::VmTracer;
 use crate::state::state_api::State;
 use crate::transaction::objects::{HasRelatedFeeType, TransactionExecutionResult, TransactionInfo};
 use crate::transaction::transaction_types::TransactionType;
@@ -95,29 +96,38 @@ impl CallEntryPoint {
         self.class_hash = Some(class_hash);
         let contract_class = state.get_compiled_contract_class(class_hash)?;
 
-        execute_entry_point_call(self, contract_class, state, resources, context).map_err(|error| {
-            let vm_trace = error.try_to_vm_trace();
-            match error {
-                // On VM error, pack the stack trace into the propagated error.
-                EntryPointExecutionError::CairoRunError(internal_error) => {
-                    context.error_stack.push((storage_address, vm_trace));
-                    // TODO(Dori, 1/5/2023): Call error_trace only in the top call; as it is
-                    //   right now, each intermediate VM error is wrapped in a
-                    //   VirtualMachineExecutionErrorWithTrace error with the stringified trace
-                    //   of all errors below it.
-                    //   When that's done, remove the 10000 character limitation.
-                    let error_trace = context.error_trace();
-                    EntryPointExecutionError::VirtualMachineExecutionErrorWithTrace {
-                        trace: error_trace[..min(10000, error_trace.len())].to_string(),
-                        source: internal_error,
+        if let Some(vm_tracer) = &mut context.vm_tracer {
+            vm_tracer.enter_call(&self);
+        }
+        let result = execute_entry_point_call(self, contract_class, state, resources, context)
+            .map_err(|error| {
+                let vm_trace = error.try_to_vm_trace();
+                match error {
+                    // On VM error, pack the stack trace into the propagated error.
+                    EntryPointExecutionError::CairoRunError(internal_error) => {
+                        context.error_stack.push((storage_address, vm_trace));
+                        // TODO(Dori, 1/5/2023): Call error_trace only in the top call; as it is
+                        //   right now, each intermediate VM error is wrapped in a
+                        //   VirtualMachineExecutionErrorWithTrace error with the stringified trace
+                        //   of all errors below it.
+                        //   When that's done, remove the 10000 character limitation.
+                        let error_trace = context.error_trace();
+                        EntryPointExecutionError::VirtualMachineExecutionErrorWithTrace {
+                            trace: error_trace[..min(10000, error_trace.len())].to_string(),
+                            source: internal_error,
+                        }
+                    }
+                    other_error => {
+                        context.error_stack.push((storage_address, format!("{}\n", &other_error)));
+                        other_error
                     }
                 }
-                other_error => {
-                    context.error_stack.push((storage_address, format!("{}\n", &other_error)));
-                    other_error
-                }
-            }
-        })
+            });
+        if let Some(vm_tracer) = &mut context.vm_tracer {
+            vm_tracer.exit_call(result.as_ref().err());
+        }
+
+        result
     }
 }
 
@@ -148,6 +158,9 @@ pub struct EntryPointExecutionContext {
 
     // The execution mode affects the behavior of the hint processor.
     pub execution_mode: ExecutionMode,
+    /// Records the VM traces of the executed calls, if set; by default, the tracer of the block
+    /// context.
+    pub vm_tracer: Option<VmTracer>,
 }
 
 impl EntryPointExecutionContext {
@@ -165,6 +178,7 @@ impl EntryPointExecutionContext {
             tx_context: tx_context.clone(),
             current_recursion_depth: Default::default(),
             execution_mode: mode,
+            vm_tracer: tx_context.block_context.vm_tracer.clone(),
         })
     }
 
@@ -199,7 +213,7 @@ impl EntryPointExecutionContext {
                 .validate_max_n_steps
                 .try_into()
                 .expect("Failed to convert validate_max_n_steps (u32) to usize."),
-            ExecutionMode::Execute => versioned_constants
+            ExecutionMode::Execute | ExecutionMode::ReadOnly => versioned_constants
                 .invoke_tx_max_n_steps
                 .try_into()
                 .expect("Failed to convert invoke_tx_max_n_steps (u32) to usize."),
@@ -79,14 +79,20 @@ pub fn execute_entry_point_call(
     // Execute.
     let bytecode_length = contract_class.bytecode_length();
     let program_segment_size = bytecode_length + program_extra_data_length;
-    run_entry_point(
+    let run_result = run_entry_point(
         &mut vm,
         &mut runner,
         &mut syscall_handler,
         entry_point,
         args,
         program_segment_size,
-    )?;
+    );
+    if let Err(error) = run_result {
+        if let Some(vm_tracer) = &mut syscall_handler.context.vm_tracer {
+            vm_tracer.record_vm_trace(&mut vm, program_segment_size);
+        }
+        return Err(error);
+    }
 
     // Collect the set PC values that were visited during the entry point execution.
     register_visited_pcs(
@@ -96,6 +102,10 @@ pub fn execute_entry_point_call(
         program_segment_size,
         bytecode_length,
     )?;
+    if let Some(vm_tracer) = &mut syscall_handler.context.vm_tracer {
+        // The trace was relocated above.
+        vm_tracer.record_trace(vm.get_relocated_trace()?);
+    }
 
     let call_info = finalize_execution(
         vm,
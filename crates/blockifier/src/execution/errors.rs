@@ -1,3 +1,5 @@
+use cairo_lang_starknet_classes::allowed_libfuncs::AllowedLibfuncsError;
+use cairo_lang_starknet_classes::casm_contract_class::StarknetSierraCompilationError;
 use cairo_vm::types::errors::math_errors::MathError;
 use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
 use cairo_vm::vm::errors::memory_errors::MemoryError;
@@ -5,7 +7,7 @@ use cairo_vm::vm::errors::runner_errors::RunnerError;
 use cairo_vm::vm::errors::trace_errors::TraceError;
 use cairo_vm::vm::errors::vm_errors::{VirtualMachineError, HINT_ERROR_STR};
 use num_bigint::{BigInt, TryFromBigIntError};
-use starknet_api::core::{ContractAddress, EntryPointSelector};
+use starknet_api::core::{CompiledClassHash, ContractAddress, EntryPointSelector};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
 use thiserror::Error;
@@ -143,6 +145,12 @@ pub enum EntryPointExecutionError {
 
 #[derive(Debug, Error)]
 pub enum ContractClassError {
+    #[error(transparent)]
+    AllowedLibfuncsError(#[from] AllowedLibfuncsError),
+    #[error("Bytecode segment lengths do not sum up to the bytecode length {bytecode_length}.")]
+    BytecodeSegmentLengthsMismatch { bytecode_length: usize },
+    #[error("Compiled class hash mismatch: expected {expected:?}, got {actual:?}.")]
+    CompiledClassHashMismatch { expected: CompiledClassHash, actual: CompiledClassHash },
     #[error(
         "Sierra program length must be > 0 for Cairo1, and == 0 for Cairo0. Got: \
          {sierra_program_length:?} for contract class version {contract_class_version:?}"
@@ -151,4 +159,8 @@ pub enum ContractClassError {
         contract_class_version: u8,
         sierra_program_length: usize,
     },
+    #[error(transparent)]
+    ProgramError(#[from] cairo_vm::types::errors::program_errors::ProgramError),
+    #[error(transparent)]
+    SierraCompilationError(#[from] StarknetSierraCompilationError),
 }
@@ -244,6 +244,18 @@ impl<'a> SyscallHintProcessor<'a> {
         self.execution_mode() == ExecutionMode::Validate
     }
 
+    /// Returns an error if the syscall is run in read-only mode.
+    pub fn verify_not_in_read_only_mode(&self, syscall_name: &str) -> SyscallResult<()> {
+        if self.execution_mode() == ExecutionMode::ReadOnly {
+            return Err(SyscallExecutionError::InvalidSyscallInExecutionMode {
+                syscall_name: syscall_name.to_string(),
+                execution_mode: self.execution_mode(),
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn verify_syscall_ptr(&self, actual_ptr: Relocatable) -> SyscallResult<()> {
         if actual_ptr != self.syscall_ptr {
             return Err(SyscallExecutionError::BadSyscallPointer {
@@ -271,6 +283,9 @@ impl<'a> SyscallHintProcessor<'a> {
         self.verify_syscall_ptr(initial_syscall_ptr)?;
 
         let selector = SyscallSelector::try_from(self.read_next_syscall_selector(vm)?)?;
+        if let Some(vm_tracer) = &mut self.context.vm_tracer {
+            vm_tracer.record_syscall(&selector);
+        }
 
         // Keccak resource usage depends on the input length, so we increment the syscall count
         // in the syscall execution callback.
@@ -429,6 +444,9 @@ impl<'a> SyscallHintProcessor<'a> {
 
         let SyscallRequestWrapper { gas_counter, request } =
             SyscallRequestWrapper::<Request>::read(vm, &mut self.syscall_ptr)?;
+        if let Some(vm_tracer) = &mut self.context.vm_tracer {
+            vm_tracer.record_syscall_request(&request);
+        }
 
         if gas_counter < required_gas {
             //  Out of gas failure.
@@ -436,6 +454,9 @@ impl<'a> SyscallHintProcessor<'a> {
                 StarkFelt::try_from(OUT_OF_GAS_ERROR).map_err(SyscallExecutionError::from)?;
             let response: SyscallResponseWrapper<Response> =
                 SyscallResponseWrapper::Failure { gas_counter, error_data: vec![out_of_gas_error] };
+            if let Some(vm_tracer) = &mut self.context.vm_tracer {
+                vm_tracer.record_syscall_response(&response);
+            }
             response.write(vm, &mut self.syscall_ptr)?;
 
             return Ok(());
@@ -454,6 +475,9 @@ impl<'a> SyscallHintProcessor<'a> {
             Err(error) => return Err(error.into()),
         };
 
+        if let Some(vm_tracer) = &mut self.context.vm_tracer {
+            vm_tracer.record_syscall_response(&response);
+        }
         response.write(vm, &mut self.syscall_ptr)?;
 
         Ok(())
@@ -672,6 +696,9 @@ impl HintProcessorLogic for SyscallHintProcessor<'_> {
         _constants: &HashMap<String, Felt252>,
     ) -> HintExecutionResult {
         let hint = hint_data.downcast_ref::<Hint>().ok_or(HintError::WrongHintData)?;
+        if let Some(vm_tracer) = &mut self.context.vm_tracer {
+            vm_tracer.record_hint(vm.get_pc(), hint.representing_string());
+        }
         match hint {
             Hint::Core(hint) => execute_core_hint_base(vm, exec_scopes, hint),
             Hint::Starknet(hint) => self.execute_next_syscall(vm, hint),
@@ -68,6 +68,7 @@ impl<T: SyscallRequest> SyscallRequest for SyscallRequestWrapper<T> {
     }
 }
 
+#[derive(Debug)]
 pub enum SyscallResponseWrapper<T: SyscallResponse> {
     Success { gas_counter: u64, response: T },
     Failure { gas_counter: u64, error_data: Vec<StarkFelt> },
@@ -232,6 +233,7 @@ pub fn deploy(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     remaining_gas: &mut u64,
 ) -> SyscallResult<DeployResponse> {
+    syscall_handler.verify_not_in_read_only_mode("deploy")?;
     let deployer_address = syscall_handler.storage_address();
     let deployer_address_for_calculation = match request.deploy_from_zero {
         true => ContractAddress::default(),
@@ -320,6 +322,7 @@ pub fn emit_event(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<EmitEventResponse> {
+    syscall_handler.verify_not_in_read_only_mode("emit_event")?;
     let execution_context = &mut syscall_handler.context;
     exceeds_event_size_limit(
         execution_context.versioned_constants(),
@@ -515,6 +518,7 @@ pub fn replace_class(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<ReplaceClassResponse> {
+    syscall_handler.verify_not_in_read_only_mode("replace_class")?;
     // Ensure the class is declared (by reading it), and of type V1.
     let class_hash = request.class_hash;
     let class = syscall_handler.state.get_compiled_contract_class(class_hash)?;
@@ -557,6 +561,7 @@ pub fn send_message_to_l1(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<SendMessageToL1Response> {
+    syscall_handler.verify_not_in_read_only_mode("send_message_to_l1")?;
     let execution_context = &mut syscall_handler.context;
     let ordered_message_to_l1 = OrderedL2ToL1Message {
         order: execution_context.n_sent_messages_to_l1,
@@ -638,6 +643,7 @@ pub fn storage_write(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<StorageWriteResponse> {
+    syscall_handler.verify_not_in_read_only_mode("storage_write")?;
     syscall_handler.set_contract_storage_at(request.address, request.value)
 }
 
@@ -333,7 +333,7 @@ fn test_get_execution_info(
             stark_felt!(CURRENT_BLOCK_TIMESTAMP_FOR_VALIDATE),
             StarkFelt::ZERO,
         ],
-        ExecutionMode::Execute => [
+        ExecutionMode::Execute | ExecutionMode::ReadOnly => [
             stark_felt!(CURRENT_BLOCK_NUMBER),    // Block number.
             stark_felt!(CURRENT_BLOCK_TIMESTAMP), // Block timestamp.
             StarkFelt::try_from(TEST_SEQUENCER_ADDRESS).unwrap(),
@@ -481,6 +481,7 @@ fn test_get_execution_info(
         ExecutionMode::Execute => {
             entry_point_call.execute_directly_given_tx_info(state, tx_info, false)
         }
+        ExecutionMode::ReadOnly => unreachable!("Transactions do not run in read-only mode."),
     };
 
     assert!(!result.unwrap().execution.failed);
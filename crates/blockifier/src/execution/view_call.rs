@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use starknet_api::core::{ContractAddress, EntryPointSelector};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::transaction::Calldata;
+
+use crate::context::{BlockContext, TransactionContext};
+use crate::execution::call_info::CallInfo;
+use crate::execution::common_hints::ExecutionMode;
+use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::{
+    DeprecatedTransactionInfo, TransactionExecutionResult, TransactionInfo,
+};
+
+#[cfg(test)]
+#[path = "view_call_test.rs"]
+pub mod test;
+
+/// Executes the given external entry point as a view call (e.g., for `starknet_call`), outside of
+/// any transaction; the call is bounded by the step and gas limits of an invoke transaction.
+/// The call runs in read-only mode, so syscalls that modify the state fail; other state changes
+/// (e.g., the caching of read classes) are discarded.
+pub fn call_contract<S: StateReader>(
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+    contract_address: ContractAddress,
+    entry_point_selector: EntryPointSelector,
+    calldata: Calldata,
+) -> TransactionExecutionResult<CallInfo> {
+    let tx_context = TransactionContext {
+        block_context: block_context.clone(),
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let limit_steps_by_resources = false;
+    let mut context = EntryPointExecutionContext::new(
+        Arc::new(tx_context),
+        ExecutionMode::ReadOnly,
+        limit_steps_by_resources,
+    )?;
+    let call = CallEntryPoint {
+        class_hash: None,
+        code_address: None,
+        entry_point_type: EntryPointType::External,
+        entry_point_selector,
+        calldata,
+        storage_address: contract_address,
+        caller_address: ContractAddress::default(),
+        call_type: CallType::Call,
+        initial_gas: block_context.versioned_constants.tx_initial_gas(),
+    };
+
+    let mut transactional_state = CachedState::create_transactional(state);
+    let call_result =
+        call.execute(&mut transactional_state, &mut ExecutionResources::default(), &mut context);
+    transactional_state.abort();
+
+    call_result.map_err(TransactionExecutionError::ExecutionError)
+}
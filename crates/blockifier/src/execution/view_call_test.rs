@@ -0,0 +1,110 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::BlockContext;
+use crate::execution::call_info::Retdata;
+use crate::execution::view_call::call_contract;
+use crate::retdata;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, CairoVersion};
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::test_utils::block_context;
+
+#[rstest]
+fn test_call_contract(
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let contract_address = test_contract.get_instance_address(0);
+    let mut state = test_state(&block_context.chain_info, 0, &[(test_contract, 1)]);
+    let depth = stark_felt!(3_u8);
+
+    let call_info = call_contract(
+        &mut state,
+        &block_context,
+        contract_address,
+        selector_from_name("recurse"),
+        calldata![depth],
+    )
+    .unwrap();
+    assert!(!call_info.execution.failed);
+    assert_eq!(call_info.execution.retdata, retdata![]);
+
+    assert_matches!(
+        call_contract(
+            &mut state,
+            &block_context,
+            contract_address!("0x1234"),
+            selector_from_name("recurse"),
+            calldata![depth],
+        ),
+        Err(TransactionExecutionError::ExecutionError(_))
+    );
+}
+
+/// Syscalls that modify the state fail, also when invoked by a nested call.
+#[rstest]
+#[case::storage_write("test_storage_read_write", vec![15, 7], "storage_write")]
+#[case::emit_event("test_emit_events", vec![1, 1, 5, 1, 6], "emit_event")]
+#[case::send_message_to_l1("send_message", vec![17], "send_message_to_l1")]
+#[case::nested_call_contract("test_call_contract", vec![], "storage_write")]
+#[case::nested_library_call("test_library_call", vec![], "storage_write")]
+#[case::replace_class("test_replace_class", vec![], "replace_class")]
+#[case::deploy("test_deploy", vec![], "deploy")]
+fn test_read_only_call(
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+    #[case] entry_point_name: &str,
+    #[case] calldata: Vec<u8>,
+    #[case] syscall_name: &str,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let contract_address = test_contract.get_instance_address(0);
+    let class_hash = test_contract.get_class_hash().0;
+    let mut state = test_state(&block_context.chain_info, 0, &[(test_contract, 1)]);
+
+    let storage_write_calldata = [stark_felt!(15_u8), stark_felt!(7_u8)];
+    let calldata = match entry_point_name {
+        "test_call_contract" => {
+            create_calldata(contract_address, "test_storage_read_write", &storage_write_calldata)
+        }
+        "test_library_call" => Calldata(
+            [
+                vec![
+                    class_hash,
+                    selector_from_name("test_storage_read_write").0,
+                    stark_felt!(2_u8),
+                ],
+                storage_write_calldata.to_vec(),
+            ]
+            .concat()
+            .into(),
+        ),
+        "test_replace_class" => calldata![class_hash],
+        "test_deploy" => {
+            calldata![class_hash, stark_felt!(1_u8), stark_felt!(0_u8), stark_felt!(0_u8)]
+        }
+        _ => Calldata(calldata.into_iter().map(StarkFelt::from).collect::<Vec<_>>().into()),
+    };
+
+    let error = call_contract(
+        &mut state,
+        &block_context,
+        contract_address,
+        selector_from_name(entry_point_name),
+        calldata,
+    )
+    .unwrap_err();
+    assert_matches!(error, TransactionExecutionError::ExecutionError(_));
+    let expected_message =
+        format!("Unauthorized syscall {syscall_name} in execution mode ReadOnly.");
+    assert!(format!("{error:?}").contains(&expected_message), "Unexpected error: {error:?}");
+}
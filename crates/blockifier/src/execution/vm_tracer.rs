@@ -0,0 +1,190 @@
+use std::fmt::Debug;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use cairo_vm::types::relocatable::Relocatable;
+use cairo_vm::vm::trace::trace_entry::TraceEntry as VmTraceEntry;
+use cairo_vm::vm::vm_core::VirtualMachine;
+use serde::Serialize;
+
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::errors::EntryPointExecutionError;
+
+#[cfg(test)]
+#[path = "vm_tracer_test.rs"]
+pub mod test;
+
+/// The registers before the execution of an instruction, relocated: the program segment is placed
+/// at address 1, and the execution segment right after it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct TraceEntry {
+    pub pc: usize,
+    pub ap: usize,
+    pub fp: usize,
+}
+
+impl From<&VmTraceEntry> for TraceEntry {
+    fn from(entry: &VmTraceEntry) -> Self {
+        Self { pc: entry.pc, ap: entry.ap, fp: entry.fp }
+    }
+}
+
+/// An executed hint, and the (relocated) PC it was executed at.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct HintTrace {
+    pub pc: usize,
+    pub code: String,
+}
+
+/// An executed syscall; the request and response are [None] if the syscall failed before they
+/// were read or written, respectively.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct SyscallTrace {
+    pub selector: String,
+    pub request: Option<String>,
+    pub response: Option<String>,
+}
+
+/// The VM trace of an entry point call.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct CallVmTrace {
+    pub call: CallEntryPoint,
+    pub trace: Vec<TraceEntry>,
+    pub hints: Vec<HintTrace>,
+    pub syscalls: Vec<SyscallTrace>,
+    pub inner_calls: Vec<CallVmTrace>,
+    /// The error the call failed with; [None] if it succeeded.
+    pub error: Option<String>,
+}
+
+impl CallVmTrace {
+    /// Returns the traces of the inner calls that succeeded; they correspond, in order, to the
+    /// inner calls of the [CallInfo] of this call.
+    pub fn successful_inner_calls(&self) -> impl Iterator<Item = &CallVmTrace> {
+        self.inner_calls.iter().filter(|inner_call| inner_call.error.is_none())
+    }
+
+    /// Returns the traces of this call and of the nodes of the given call tree, paired; the call
+    /// tree is expected to be the one of this call.
+    pub fn zip_with_call_info<'a>(
+        &'a self,
+        call_info: &'a CallInfo,
+    ) -> Vec<(&'a CallVmTrace, &'a CallInfo)> {
+        let mut pairs = vec![(self, call_info)];
+        for (inner_trace, inner_call_info) in
+            self.successful_inner_calls().zip(&call_info.inner_calls)
+        {
+            pairs.extend(inner_trace.zip_with_call_info(inner_call_info));
+        }
+
+        pairs
+    }
+
+    /// Writes the trace of this call (without its inner calls, which run in VMs of their own) in
+    /// the binary format of the Cairo VM: `ap`, `fp` and `pc` of each entry, as 8-byte
+    /// little-endian integers.
+    pub fn write_binary_trace(&self, writer: &mut impl Write) -> io::Result<()> {
+        for entry in &self.trace {
+            for register in [entry.ap, entry.fp, entry.pc] {
+                writer.write_all(&(register as u64).to_le_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Records the VM traces of the entry point calls executed with the contexts it is set on.
+/// Clones share their records; e.g., a tracer set on a block context records the calls of all the
+/// execution contexts of its transactions, which are expected to run one at a time (hence, a
+/// tracer cannot be used in parallel execution).
+#[derive(Clone, Debug, Default)]
+pub struct VmTracer(Arc<Mutex<VmTraceRecords>>);
+
+#[derive(Debug, Default)]
+struct VmTraceRecords {
+    // The traces of the calls in execution, outermost first.
+    call_stack: Vec<CallVmTrace>,
+    call_traces: Vec<CallVmTrace>,
+}
+
+impl VmTracer {
+    /// Returns the traces of the outermost calls executed so far.
+    pub fn call_traces(&self) -> Vec<CallVmTrace> {
+        self.records().call_traces.clone()
+    }
+
+    /// Returns the traces of the outermost calls executed so far, and clears them.
+    pub fn take_call_traces(&self) -> Vec<CallVmTrace> {
+        std::mem::take(&mut self.records().call_traces)
+    }
+
+    pub(crate) fn enter_call(&self, call: &CallEntryPoint) {
+        self.records().call_stack.push(CallVmTrace { call: call.clone(), ..Default::default() });
+    }
+
+    pub(crate) fn exit_call(&self, error: Option<&EntryPointExecutionError>) {
+        let mut records = self.records();
+        let mut call_trace = records.call_stack.pop().expect("Exited a call that was not entered.");
+        call_trace.error = error.map(ToString::to_string);
+        match records.call_stack.last_mut() {
+            Some(caller_trace) => caller_trace.inner_calls.push(call_trace),
+            None => records.call_traces.push(call_trace),
+        }
+    }
+
+    pub(crate) fn record_trace(&self, trace: &[VmTraceEntry]) {
+        self.records().current_call().trace = trace.iter().map(TraceEntry::from).collect();
+    }
+
+    /// Relocates the trace of the given VM, whose program segment is of the given size, and
+    /// records it. Used for failed runs, whose trace is not relocated otherwise; a trace that
+    /// fails to relocate is not recorded, so as not to mask the failure of the run.
+    pub(crate) fn record_vm_trace(&self, vm: &mut VirtualMachine, program_segment_size: usize) {
+        // Relocate the trace, putting the program segment at address 1 and the execution segment
+        // right after it.
+        if vm.relocate_trace(&[1, 1 + program_segment_size]).is_err() {
+            return;
+        }
+        if let Ok(trace) = vm.get_relocated_trace() {
+            self.record_trace(trace);
+        }
+    }
+
+    pub(crate) fn record_hint(&self, pc: Relocatable, code: String) {
+        // Hints are executed in the program segment, placed at address 1.
+        self.records().current_call().hints.push(HintTrace { pc: pc.offset + 1, code });
+    }
+
+    pub(crate) fn record_syscall(&self, selector: &impl Debug) {
+        self.records().current_call().syscalls.push(SyscallTrace {
+            selector: format!("{selector:?}"),
+            request: None,
+            response: None,
+        });
+    }
+
+    pub(crate) fn record_syscall_request(&self, request: &impl Debug) {
+        self.records().current_syscall().request = Some(format!("{request:?}"));
+    }
+
+    pub(crate) fn record_syscall_response(&self, response: &impl Debug) {
+        self.records().current_syscall().response = Some(format!("{response:?}"));
+    }
+
+    fn records(&self) -> MutexGuard<'_, VmTraceRecords> {
+        self.0.lock().expect("VM tracer is poisoned.")
+    }
+}
+
+impl VmTraceRecords {
+    fn current_call(&mut self) -> &mut CallVmTrace {
+        self.call_stack.last_mut().expect("Traced data should be recorded within a call.")
+    }
+
+    fn current_syscall(&mut self) -> &mut SyscallTrace {
+        // Inner calls of the syscall are traced separately, so it is the last of its caller.
+        self.current_call().syscalls.last_mut().expect("Syscall data should follow its selector.")
+    }
+}
@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::Fee;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+use crate::execution::vm_tracer::{CallVmTrace, TraceEntry, VmTracer};
+use crate::invoke_tx_args;
+use crate::state::state_api::State;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{
+    create_calldata, trivial_external_entry_point_with_address, CairoVersion, BALANCE, MAX_FEE,
+};
+use crate::transaction::constants::EXECUTE_ENTRY_POINT_NAME;
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+use crate::transaction::test_utils::{account_invoke_tx, block_context};
+use crate::transaction::transactions::ExecutableTransaction;
+
+fn execute_traced(
+    call: CallEntryPoint,
+    state: &mut dyn State,
+) -> (EntryPointExecutionResult<CallInfo>, Vec<CallVmTrace>) {
+    let tx_context = TransactionContext {
+        block_context: BlockContext::create_for_testing(),
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+    context.vm_tracer = Some(VmTracer::default());
+    let result = call.execute(state, &mut ExecutionResources::default(), &mut context);
+
+    (result, context.vm_tracer.unwrap().take_call_traces())
+}
+
+/// Returns a call of `test_call_contract` of the test contract, calling the given function of
+/// itself.
+fn call_contract_call(test_contract: FeatureContract, function_name: &str) -> CallEntryPoint {
+    let calldata = create_calldata(
+        test_contract.get_instance_address(0),
+        function_name,
+        &[stark_felt!(405_u16), stark_felt!(48_u8)],
+    );
+    CallEntryPoint {
+        entry_point_selector: selector_from_name("test_call_contract"),
+        calldata,
+        ..trivial_external_entry_point_with_address(test_contract.get_instance_address(0))
+    }
+}
+
+#[rstest]
+fn test_vm_trace(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let call = call_contract_call(test_contract, "test_storage_read_write");
+
+    let (result, call_traces) = execute_traced(call, &mut state);
+    let call_info = result.unwrap();
+    let [call_trace] = &call_traces[..] else {
+        panic!("Expected a single call trace, got {call_traces:?}.")
+    };
+    assert_eq!(call_trace.inner_calls.len(), 1);
+
+    let traced_calls = call_trace.zip_with_call_info(&call_info);
+    assert_eq!(traced_calls.len(), 2);
+    for (call_trace, call_info) in traced_calls {
+        assert_eq!(call_trace.call, call_info.call);
+        assert_eq!(call_trace.error, None);
+        assert!(!call_trace.trace.is_empty());
+        // The program segment is placed at address 1.
+        assert!(call_trace.trace.iter().all(|entry| entry.pc >= 1));
+        for hint in &call_trace.hints {
+            assert!(call_trace.trace.iter().any(|entry| entry.pc == hint.pc), "{hint:?}");
+        }
+        assert!(!call_trace.syscalls.is_empty());
+        for syscall in &call_trace.syscalls {
+            assert!(syscall.request.is_some() && syscall.response.is_some(), "{syscall:?}");
+        }
+    }
+    assert_eq!(call_trace.syscalls[0].selector, "CallContract");
+    assert_eq!(call_trace.inner_calls[0].syscalls[0].selector, "StorageWrite");
+
+    let serialized_trace = serde_json::to_value(call_trace).unwrap();
+    assert_eq!(serialized_trace["inner_calls"][0]["syscalls"][1]["selector"], "StorageRead");
+}
+
+#[rstest]
+#[case::cairo0(CairoVersion::Cairo0, "write_and_revert")]
+#[case::cairo1(CairoVersion::Cairo1, "assert_eq")]
+fn test_vm_trace_of_failed_call(
+    #[case] cairo_version: CairoVersion,
+    #[case] failing_function_name: &str,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let call = call_contract_call(test_contract, failing_function_name);
+
+    let (result, call_traces) = execute_traced(call, &mut state);
+    let error = result.unwrap_err();
+    let [call_trace] = &call_traces[..] else {
+        panic!("Expected a single call trace, got {call_traces:?}.")
+    };
+    assert_eq!(call_trace.error, Some(error.to_string()));
+    assert_eq!(call_trace.successful_inner_calls().count(), 0);
+
+    // The failed call is traced up to its failure, and so is the syscall that invoked it.
+    let [inner_call_trace] = &call_trace.inner_calls[..] else {
+        panic!("Expected a single inner call trace, got {:?}.", call_trace.inner_calls)
+    };
+    assert!(inner_call_trace.error.is_some());
+    assert!(!inner_call_trace.trace.is_empty());
+    let call_contract_syscall = call_trace.syscalls.last().unwrap();
+    assert_eq!(call_contract_syscall.selector, "CallContract");
+    assert!(call_contract_syscall.request.is_some());
+    assert_eq!(call_contract_syscall.response, None);
+}
+
+#[rstest]
+fn test_vm_trace_of_reverted_invoke(block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut state =
+        test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let vm_tracer = VmTracer::default();
+    let block_context = block_context.with_vm_tracer(vm_tracer.clone());
+    let calldata = create_calldata(
+        test_contract.get_instance_address(0),
+        "assert_eq",
+        &[stark_felt!(1_u8), stark_felt!(2_u8)],
+    );
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account.get_instance_address(0),
+        calldata,
+    });
+
+    let execution_info = tx.execute(&mut state, &block_context, true, true).unwrap();
+    assert!(execution_info.is_reverted());
+
+    // The validation, the reverted execution and the fee transfer are traced, in order.
+    let call_traces = vm_tracer.take_call_traces();
+    let [validate_trace, execute_trace, fee_transfer_trace] = &call_traces[..] else {
+        panic!("Expected three call traces, got {call_traces:?}.")
+    };
+    assert_eq!(validate_trace.call, execution_info.validate_call_info.unwrap().call);
+    assert_eq!(fee_transfer_trace.call, execution_info.fee_transfer_call_info.unwrap().call);
+    for call_trace in [validate_trace, fee_transfer_trace] {
+        assert_eq!(call_trace.error, None);
+        assert!(!call_trace.trace.is_empty());
+    }
+
+    assert_eq!(
+        execute_trace.call.entry_point_selector,
+        selector_from_name(EXECUTE_ENTRY_POINT_NAME)
+    );
+    assert!(execute_trace.error.is_some());
+    let [inner_call_trace] = &execute_trace.inner_calls[..] else {
+        panic!("Expected a single inner call trace, got {:?}.", execute_trace.inner_calls)
+    };
+    assert_eq!(inner_call_trace.call.entry_point_selector, selector_from_name("assert_eq"));
+    assert!(inner_call_trace.error.is_some());
+    assert!(!inner_call_trace.trace.is_empty());
+    assert!(vm_tracer.call_traces().is_empty());
+}
+
+#[test]
+fn test_binary_trace() {
+    let call_trace = CallVmTrace {
+        trace: vec![TraceEntry { pc: 1, ap: 2, fp: 3 }, TraceEntry { pc: 4, ap: 5, fp: 6 }],
+        ..Default::default()
+    };
+
+    let mut binary_trace = vec![];
+    call_trace.write_binary_trace(&mut binary_trace).unwrap();
+    let registers: Vec<u64> = binary_trace
+        .chunks_exact(8)
+        .map(|register| u64::from_le_bytes(register.try_into().unwrap()))
+        .collect();
+    assert_eq!(registers, vec![2, 3, 1, 5, 6, 4]);
+}
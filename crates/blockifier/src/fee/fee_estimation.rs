@@ -0,0 +1,111 @@
+use starknet_api::transaction::Fee;
+use thiserror::Error;
+
+use crate::context::BlockContext;
+use crate::fee::fee_utils::{calculate_tx_gas_vector, get_fee_by_gas_vector};
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::transaction::errors::{TransactionExecutionError, TransactionFeeError};
+use crate::transaction::objects::{
+    FeeType, GasVector, HasRelatedFeeType, TransactionExecutionInfo, TransactionFeeResult,
+    TransactionInfoCreator,
+};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[cfg(test)]
+#[path = "fee_estimation_test.rs"]
+pub mod test;
+
+/// The errors of fee estimation and of transaction simulation; simulation includes reverted
+/// transactions, rather than failing on them.
+#[derive(Debug, Error)]
+pub enum FeeEstimationError {
+    #[error("Transaction at index {tx_index} failed execution: {error}")]
+    TransactionExecutionFailed { tx_index: usize, error: TransactionExecutionError },
+    #[error(transparent)]
+    TransactionFeeError(#[from] TransactionFeeError),
+    #[error("Transaction at index {tx_index} was reverted: {revert_error}")]
+    TransactionReverted { tx_index: usize, revert_error: String },
+}
+
+pub type FeeEstimationResult<T> = Result<T, FeeEstimationError>;
+
+/// The estimated fee of a transaction, as returned by `starknet_estimateFee`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FeeEstimate {
+    /// The total gas consumption of the transaction, including data availability.
+    pub gas_vector: GasVector,
+    /// The gas consumption for data availability; included in `gas_vector`.
+    pub da_gas: GasVector,
+    pub eth_fee: Fee,
+    pub strk_fee: Fee,
+    /// The fee token the transaction pays in.
+    pub unit: FeeType,
+}
+
+impl FeeEstimate {
+    /// Computes the fee estimate of an executed transaction, paying in the given fee token.
+    /// The fee is derived from the consumed resources, rather than taken from the execution
+    /// info; the latter is zero for transactions that do not enforce a fee (e.g., ones sent
+    /// with a zero max fee for estimation).
+    pub fn new(
+        tx_execution_info: &TransactionExecutionInfo,
+        unit: FeeType,
+        block_context: &BlockContext,
+    ) -> TransactionFeeResult<Self> {
+        let gas_vector = calculate_tx_gas_vector(
+            &tx_execution_info.actual_resources,
+            &block_context.versioned_constants,
+        )?;
+        let block_info = &block_context.block_info;
+
+        Ok(Self {
+            gas_vector,
+            da_gas: tx_execution_info.da_gas,
+            eth_fee: get_fee_by_gas_vector(block_info, gas_vector, &FeeType::Eth),
+            strk_fee: get_fee_by_gas_vector(block_info, gas_vector, &FeeType::Strk),
+            unit,
+        })
+    }
+
+    /// Returns the fee in the fee token the transaction pays in.
+    pub fn overall_fee(&self) -> Fee {
+        match self.unit {
+            FeeType::Eth => self.eth_fee,
+            FeeType::Strk => self.strk_fee,
+        }
+    }
+}
+
+/// Estimates the fees of the given transactions, executed sequentially on top of the given state;
+/// each transaction observes the changes of its predecessors. No fee is charged, and the state
+/// is left unmodified.
+/// Fails if any of the transactions fails or is reverted, as the estimate of the following ones
+/// would be meaningless.
+pub fn estimate_fee<S: StateReader>(
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+    txs: Vec<Transaction>,
+    skip_validate: bool,
+) -> FeeEstimationResult<Vec<FeeEstimate>> {
+    let mut transactional_state = CachedState::create_transactional(state);
+    let charge_fee = false;
+    let validate = !skip_validate;
+
+    let mut fee_estimates = Vec::with_capacity(txs.len());
+    for (tx_index, tx) in txs.into_iter().enumerate() {
+        let unit = tx.create_tx_info().fee_type();
+        let tx_execution_info = tx
+            .execute_raw(&mut transactional_state, block_context, charge_fee, validate)
+            .map_err(|error| FeeEstimationError::TransactionExecutionFailed { tx_index, error })?;
+        if let Some(revert_error) = tx_execution_info.revert_error {
+            return Err(FeeEstimationError::TransactionReverted { tx_index, revert_error });
+        }
+
+        fee_estimates.push(FeeEstimate::new(&tx_execution_info, unit, block_context)?);
+    }
+    transactional_state.abort();
+
+    Ok(fee_estimates)
+}
@@ -0,0 +1,118 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::Nonce;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::{Fee, TransactionVersion};
+
+use crate::context::BlockContext;
+use crate::fee::fee_estimation::{estimate_fee, FeeEstimationError};
+use crate::fee::fee_utils::get_fee_by_gas_vector;
+use crate::invoke_tx_args;
+use crate::state::state_api::StateReader;
+use crate::test_utils::invoke::InvokeTxArgs;
+use crate::test_utils::{create_calldata, CairoVersion, NonceManager, MAX_FEE};
+use crate::transaction::objects::FeeType;
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, create_test_init_data, max_resource_bounds, TestInitData,
+};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[rstest]
+#[case::deprecated(TransactionVersion::ONE, FeeType::Eth)]
+#[case::current(TransactionVersion::THREE, FeeType::Strk)]
+fn test_estimate_fee(
+    block_context: BlockContext,
+    #[case] version: TransactionVersion,
+    #[case] expected_unit: FeeType,
+    #[values(false, true)] only_query: bool,
+) {
+    let TestInitData { mut state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let mut nonce_manager = NonceManager::default();
+    // The second transaction is only valid on top of the first one.
+    let tx_args: Vec<InvokeTxArgs> = (0..2_u8)
+        .map(|i| {
+            invoke_tx_args! {
+                sender_address: account_address,
+                calldata: create_calldata(
+                    contract_address, "test_storage_read_write", &[stark_felt!(i), stark_felt!(i)]
+                ),
+                version,
+                nonce: nonce_manager.next(account_address),
+            }
+        })
+        .collect();
+
+    // Fee-less transactions, as usually sent for estimation.
+    let txs = tx_args
+        .iter()
+        .map(|args| {
+            Transaction::from(account_invoke_tx(InvokeTxArgs { only_query, ..args.clone() }))
+        })
+        .collect();
+    let skip_validate = false;
+    let fee_estimates = estimate_fee(&mut state, &block_context, txs, skip_validate).unwrap();
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), Nonce::default());
+
+    // The estimates match the fees charged for the same transactions.
+    for (args, fee_estimate) in tx_args.into_iter().zip(fee_estimates) {
+        let charged_tx = account_invoke_tx(InvokeTxArgs {
+            max_fee: Fee(MAX_FEE),
+            resource_bounds: max_resource_bounds(),
+            ..args
+        });
+        let tx_execution_info = charged_tx.execute(&mut state, &block_context, true, true).unwrap();
+
+        assert_eq!(fee_estimate.unit, expected_unit);
+        assert_eq!(fee_estimate.overall_fee(), tx_execution_info.actual_fee);
+        assert_eq!(fee_estimate.da_gas, tx_execution_info.da_gas);
+        assert_eq!(
+            fee_estimate.strk_fee,
+            get_fee_by_gas_vector(
+                &block_context.block_info,
+                fee_estimate.gas_vector,
+                &FeeType::Strk
+            )
+        );
+    }
+}
+
+#[rstest]
+fn test_estimate_fee_failures(block_context: BlockContext) {
+    let TestInitData { mut state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let tx = |nonce: u8, function_name: &str| -> Transaction {
+        account_invoke_tx(invoke_tx_args! {
+            sender_address: account_address,
+            calldata: create_calldata(
+                contract_address, function_name, &[stark_felt!(1_u8), stark_felt!(1_u8)]
+            ),
+            nonce: Nonce(stark_felt!(nonce)),
+        })
+        .into()
+    };
+    let skip_validate = true;
+
+    assert_matches!(
+        estimate_fee(
+            &mut state,
+            &block_context,
+            vec![tx(0, "test_storage_read_write"), tx(1, "write_and_revert")],
+            skip_validate
+        ),
+        Err(FeeEstimationError::TransactionReverted { tx_index: 1, .. })
+    );
+    assert_matches!(
+        estimate_fee(
+            &mut state,
+            &block_context,
+            vec![tx(1, "test_storage_read_write")],
+            skip_validate
+        ),
+        Err(FeeEstimationError::TransactionExecutionFailed { tx_index: 0, .. })
+    );
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), Nonce::default());
+}
@@ -1,9 +1,12 @@
 use std::collections::HashSet;
 
 use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
 use starknet_api::transaction::Fee;
 
+use crate::abi::abi_utils::get_fee_token_var_address;
 use crate::abi::constants;
+use crate::abi::sierra_types::next_storage_key;
 use crate::blockifier::block::BlockInfo;
 use crate::context::{BlockContext, TransactionContext};
 use crate::state::state_api::StateReader;
@@ -36,6 +39,15 @@ pub fn extract_l1_blob_gas_usage(resources: &ResourcesMapping) -> (usize, Resour
     (l1_blob_gas_usage, ResourcesMapping(vm_resource_usage))
 }
 
+/// Returns the storage keys of the low and high parts of the fee token balance of the sequencer.
+pub fn get_sequencer_balance_keys(block_context: &BlockContext) -> (StorageKey, StorageKey) {
+    let sequencer_address = block_context.block_info.sequencer_address;
+    let sequencer_balance_key_low = get_fee_token_var_address(sequencer_address);
+    let sequencer_balance_key_high = next_storage_key(&sequencer_balance_key_low)
+        .expect("Cannot get sequencer balance high key.");
+    (sequencer_balance_key_low, sequencer_balance_key_high)
+}
+
 /// Calculates the L1 gas consumed when submitting the underlying Cairo program to SHARP.
 /// I.e., returns the heaviest Cairo resource weight (in terms of L1 gas), as the size of
 /// a proof is determined similarly - by the (normalized) largest segment.
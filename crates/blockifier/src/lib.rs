@@ -1,5 +1,7 @@
 pub mod abi;
 pub mod blockifier;
+pub mod commitment;
+pub mod concurrency;
 pub mod context;
 pub mod execution;
 pub mod fee;
@@ -1,3 +1,6 @@
 pub mod cached_state;
 pub mod errors;
+pub mod recording_state_reader;
 pub mod state_api;
+pub mod state_overrides;
+pub mod state_snapshot;
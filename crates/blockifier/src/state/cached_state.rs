@@ -1,5 +1,6 @@
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 use std::sync::{Arc, Mutex, MutexGuard};
 
 use cached::{Cached, SizedCache};
@@ -13,6 +14,7 @@ use crate::abi::abi_utils::get_fee_token_var_address;
 use crate::execution::contract_class::ContractClass;
 use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateReader, StateResult};
+use crate::state::state_snapshot::{StateSnapshot, ToStateSnapshot};
 use crate::utils::subtract_mappings;
 
 #[cfg(test)]
@@ -31,11 +33,15 @@ pub struct CachedState<S: StateReader> {
     // Invariant: read/write access is managed by CachedState.
     // Using interior mutability to update caches during `State`'s immutable getters.
     cache: RefCell<StateCache>,
+    // The cells read from the wrapped state, with the values observed.
+    read_set: RefCell<StateReadSet>,
     class_hash_to_class: RefCell<ContractClassMapping>,
     // Invariant: managed by CachedState.
-    global_class_hash_to_class: GlobalContractCache,
+    pub(crate) global_class_hash_to_class: GlobalContractCache,
     /// A map from class hash to the set of PC values that were visited in the class.
     pub visited_pcs: HashMap<ClassHash, HashSet<usize>>,
+    // The undo logs of the taken savepoints, oldest first.
+    savepoints: Vec<Savepoint>,
 }
 
 impl<S: StateReader> CachedState<S> {
@@ -43,9 +49,11 @@ impl<S: StateReader> CachedState<S> {
         Self {
             state,
             cache: RefCell::new(StateCache::default()),
+            read_set: RefCell::new(StateReadSet::default()),
             class_hash_to_class: RefCell::new(HashMap::default()),
             global_class_hash_to_class,
             visited_pcs: HashMap::default(),
+            savepoints: Vec::default(),
         }
     }
 
@@ -74,6 +82,19 @@ impl<S: StateReader> CachedState<S> {
         })
     }
 
+    /// Returns the cells read through this state from the wrapped state, and the cells written
+    /// through this state (with their values before and after the writes).
+    /// Note: cells read after being written through this state are not part of the read set.
+    pub fn get_state_accesses(&mut self) -> StateResult<StateAccesses> {
+        self.update_initial_values_of_write_only_access()?;
+        let cache = self.cache.borrow();
+
+        Ok(StateAccesses {
+            read_set: self.read_set.borrow().clone(),
+            write_set: cache.get_write_set(),
+        })
+    }
+
     /// Drains contract-class cache collected during execution and updates the global cache.
     pub fn move_classes_to_global_cache(&mut self) {
         let contract_class_updates: Vec<_> = self.class_hash_to_class.get_mut().drain().collect();
@@ -89,7 +110,61 @@ impl<S: StateReader> CachedState<S> {
         self.global_class_hash_to_class.lock()
     }
 
+    /// Takes a savepoint, to which the state can later be rolled back; savepoints may be nested.
+    pub fn checkpoint(&mut self) -> SavepointId {
+        self.savepoints.push(Savepoint::default());
+        SavepointId(self.savepoints.len() - 1)
+    }
+
+    /// Undoes all writes made since the given savepoint was taken, including the ones committed
+    /// from transactional states. The savepoint remains valid; later savepoints are released.
+    /// Note: the read caches are kept, as the values read from the wrapped state are unaffected.
+    pub fn rollback_to(&mut self, savepoint_id: SavepointId) {
+        let SavepointId(savepoint_index) = savepoint_id;
+        assert!(
+            savepoint_index < self.savepoints.len(),
+            "Cannot roll back to a released savepoint: {savepoint_id:?}."
+        );
+
+        let cache = self.cache.get_mut();
+        let class_hash_to_class = self.class_hash_to_class.get_mut();
+        // Undo the changes in reverse order, so that the oldest recorded values prevail.
+        for savepoint in self.savepoints.drain(savepoint_index..).rev() {
+            restore_values(&mut cache.nonce_writes, savepoint.nonce_writes);
+            restore_values(&mut cache.class_hash_writes, savepoint.class_hash_writes);
+            restore_values(&mut cache.storage_writes, savepoint.storage_writes);
+            restore_values(
+                &mut cache.compiled_class_hash_writes,
+                savepoint.compiled_class_hash_writes,
+            );
+            restore_values(class_hash_to_class, savepoint.class_hash_to_class);
+            restore_values(&mut self.visited_pcs, savepoint.visited_pcs);
+        }
+        self.savepoints.push(Savepoint::default());
+    }
+
+    /// Releases the given savepoint, and all later ones; their changes can still be undone by
+    /// rolling back to an earlier savepoint.
+    pub fn release_savepoint(&mut self, savepoint_id: SavepointId) {
+        let SavepointId(savepoint_index) = savepoint_id;
+        assert!(
+            savepoint_index < self.savepoints.len(),
+            "Cannot release a released savepoint: {savepoint_id:?}."
+        );
+
+        let released_savepoints: Vec<Savepoint> =
+            self.savepoints.drain(savepoint_index..).collect();
+        if let Some(previous_savepoint) = self.savepoints.last_mut() {
+            for savepoint in released_savepoints {
+                previous_savepoint.merge(savepoint);
+            }
+        }
+    }
+
     pub fn update_cache(&mut self, cache_updates: StateCache) {
+        if let Some(savepoint) = self.savepoints.last_mut() {
+            savepoint.record_cache_writes(self.cache.get_mut(), &cache_updates);
+        }
         let mut cache = self.cache.borrow_mut();
 
         cache.nonce_writes.extend(cache_updates.nonce_writes);
@@ -98,11 +173,40 @@ impl<S: StateReader> CachedState<S> {
         cache.compiled_class_hash_writes.extend(cache_updates.compiled_class_hash_writes);
     }
 
+    /// Sets the value of the given storage cell, as if it were read from the wrapped state; the
+    /// cell is then not read from the wrapped state.
+    pub(crate) fn set_storage_initial_value(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: StarkFelt,
+    ) {
+        self.cache.get_mut().set_storage_initial_value(contract_address, key, value);
+    }
+
+    /// Discards the writes to the given storage cell made through this state.
+    pub(crate) fn discard_storage_write(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) {
+        self.cache.get_mut().storage_writes.remove(&(contract_address, key));
+    }
+
     pub fn update_contract_class_caches(
         &mut self,
         local_contract_cache_updates: ContractClassMapping,
         global_contract_cache: GlobalContractCache,
     ) {
+        if let Some(savepoint) = self.savepoints.last_mut() {
+            for class_hash in local_contract_cache_updates.keys() {
+                record_value(
+                    &mut savepoint.class_hash_to_class,
+                    self.class_hash_to_class.get_mut(),
+                    class_hash,
+                );
+            }
+        }
         self.class_hash_to_class.get_mut().extend(local_contract_cache_updates);
         self.global_class_hash_to_class = global_contract_cache;
     }
@@ -186,6 +290,28 @@ impl<S: StateReader> From<S> for CachedState<S> {
     }
 }
 
+/// A snapshot of the wrapped state, with the cached writes applied on top of it.
+impl<S: StateReader + ToStateSnapshot> ToStateSnapshot for CachedState<S> {
+    fn to_snapshot(&self) -> StateSnapshot {
+        let mut snapshot = self.state.to_snapshot();
+        let cache = self.cache.borrow();
+        for (&(contract_address, key), &value) in &cache.storage_writes {
+            snapshot.storage.entry(contract_address).or_default().insert(key, value);
+        }
+        snapshot.address_to_nonce.extend(&cache.nonce_writes);
+        snapshot.address_to_class_hash.extend(&cache.class_hash_writes);
+        snapshot.class_hash_to_compiled_class_hash.extend(&cache.compiled_class_hash_writes);
+        snapshot.class_hash_to_class.extend(
+            self.class_hash_to_class
+                .borrow()
+                .iter()
+                .map(|(class_hash, contract_class)| (*class_hash, contract_class.clone())),
+        );
+
+        snapshot
+    }
+}
+
 impl<S: StateReader> StateReader for CachedState<S> {
     fn get_storage_at(
         &self,
@@ -197,6 +323,7 @@ impl<S: StateReader> StateReader for CachedState<S> {
         if cache.get_storage_at(contract_address, key).is_none() {
             let storage_value = self.state.get_storage_at(contract_address, key)?;
             cache.set_storage_initial_value(contract_address, key, storage_value);
+            self.read_set.borrow_mut().storage.insert((contract_address, key), storage_value);
         }
 
         let value = cache.get_storage_at(contract_address, key).unwrap_or_else(|| {
@@ -211,6 +338,7 @@ impl<S: StateReader> StateReader for CachedState<S> {
         if cache.get_nonce_at(contract_address).is_none() {
             let nonce = self.state.get_nonce_at(contract_address)?;
             cache.set_nonce_initial_value(contract_address, nonce);
+            self.read_set.borrow_mut().nonces.insert(contract_address, nonce);
         }
 
         let nonce = cache
@@ -226,6 +354,7 @@ impl<S: StateReader> StateReader for CachedState<S> {
         if cache.get_class_hash_at(contract_address).is_none() {
             let class_hash = self.state.get_class_hash_at(contract_address)?;
             cache.set_class_hash_initial_value(contract_address, class_hash);
+            self.read_set.borrow_mut().class_hashes.insert(contract_address, class_hash);
         }
 
         let class_hash = cache
@@ -268,6 +397,10 @@ impl<S: StateReader> StateReader for CachedState<S> {
         if cache.get_compiled_class_hash(class_hash).is_none() {
             let compiled_class_hash = self.state.get_compiled_class_hash(class_hash)?;
             cache.set_compiled_class_hash_initial_value(class_hash, compiled_class_hash);
+            self.read_set
+                .borrow_mut()
+                .compiled_class_hashes
+                .insert(class_hash, compiled_class_hash);
         }
 
         let compiled_class_hash = cache
@@ -284,7 +417,15 @@ impl<S: StateReader> State for CachedState<S> {
         key: StorageKey,
         value: StarkFelt,
     ) -> StateResult<()> {
-        self.cache.get_mut().set_storage_value(contract_address, key, value);
+        let cache = self.cache.get_mut();
+        if let Some(savepoint) = self.savepoints.last_mut() {
+            record_value(
+                &mut savepoint.storage_writes,
+                &cache.storage_writes,
+                &(contract_address, key),
+            );
+        }
+        cache.set_storage_value(contract_address, key, value);
 
         Ok(())
     }
@@ -297,7 +438,11 @@ impl<S: StateReader> State for CachedState<S> {
             usize::try_from(current_nonce.0)?.try_into().expect("Failed to convert usize to u64.");
         let next_nonce_val = 1_u64 + current_nonce_as_u64;
         let next_nonce = Nonce(StarkFelt::from(next_nonce_val));
-        self.cache.get_mut().set_nonce_value(contract_address, next_nonce);
+        let cache = self.cache.get_mut();
+        if let Some(savepoint) = self.savepoints.last_mut() {
+            record_value(&mut savepoint.nonce_writes, &cache.nonce_writes, &contract_address);
+        }
+        cache.set_nonce_value(contract_address, next_nonce);
 
         Ok(())
     }
@@ -311,7 +456,15 @@ impl<S: StateReader> State for CachedState<S> {
             return Err(StateError::OutOfRangeContractAddress);
         }
 
-        self.cache.get_mut().set_class_hash_write(contract_address, class_hash);
+        let cache = self.cache.get_mut();
+        if let Some(savepoint) = self.savepoints.last_mut() {
+            record_value(
+                &mut savepoint.class_hash_writes,
+                &cache.class_hash_writes,
+                &contract_address,
+            );
+        }
+        cache.set_class_hash_write(contract_address, class_hash);
         Ok(())
     }
 
@@ -320,7 +473,11 @@ impl<S: StateReader> State for CachedState<S> {
         class_hash: ClassHash,
         contract_class: ContractClass,
     ) -> StateResult<()> {
-        self.class_hash_to_class.get_mut().insert(class_hash, contract_class);
+        let class_hash_to_class = self.class_hash_to_class.get_mut();
+        if let Some(savepoint) = self.savepoints.last_mut() {
+            record_value(&mut savepoint.class_hash_to_class, class_hash_to_class, &class_hash);
+        }
+        class_hash_to_class.insert(class_hash, contract_class);
         Ok(())
     }
 
@@ -329,26 +486,113 @@ impl<S: StateReader> State for CachedState<S> {
         class_hash: ClassHash,
         compiled_class_hash: CompiledClassHash,
     ) -> StateResult<()> {
-        self.cache.get_mut().set_compiled_class_hash_write(class_hash, compiled_class_hash);
+        let cache = self.cache.get_mut();
+        if let Some(savepoint) = self.savepoints.last_mut() {
+            record_value(
+                &mut savepoint.compiled_class_hash_writes,
+                &cache.compiled_class_hash_writes,
+                &class_hash,
+            );
+        }
+        cache.set_compiled_class_hash_write(class_hash, compiled_class_hash);
         Ok(())
     }
 
     fn add_visited_pcs(&mut self, class_hash: ClassHash, pcs: &HashSet<usize>) {
+        if let Some(savepoint) = self.savepoints.last_mut() {
+            record_value(&mut savepoint.visited_pcs, &self.visited_pcs, &class_hash);
+        }
         self.visited_pcs.entry(class_hash).or_default().extend(pcs);
     }
 }
 
+/// Identifies a savepoint of a `CachedState`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SavepointId(usize);
+
+/// The undo log of a savepoint: the values of the entries modified since it was taken (and before
+/// the next savepoint was taken), as they were before their first modification. `None` stands for
+/// an absent entry.
+#[derive(Debug, Default)]
+struct Savepoint {
+    nonce_writes: HashMap<ContractAddress, Option<Nonce>>,
+    class_hash_writes: HashMap<ContractAddress, Option<ClassHash>>,
+    storage_writes: HashMap<StorageEntry, Option<StarkFelt>>,
+    compiled_class_hash_writes: HashMap<ClassHash, Option<CompiledClassHash>>,
+    class_hash_to_class: HashMap<ClassHash, Option<ContractClass>>,
+    visited_pcs: HashMap<ClassHash, Option<HashSet<usize>>>,
+}
+
+impl Savepoint {
+    fn record_cache_writes(&mut self, cache: &StateCache, cache_updates: &StateCache) {
+        for contract_address in cache_updates.nonce_writes.keys() {
+            record_value(&mut self.nonce_writes, &cache.nonce_writes, contract_address);
+        }
+        for contract_address in cache_updates.class_hash_writes.keys() {
+            record_value(&mut self.class_hash_writes, &cache.class_hash_writes, contract_address);
+        }
+        for storage_entry in cache_updates.storage_writes.keys() {
+            record_value(&mut self.storage_writes, &cache.storage_writes, storage_entry);
+        }
+        for class_hash in cache_updates.compiled_class_hash_writes.keys() {
+            record_value(
+                &mut self.compiled_class_hash_writes,
+                &cache.compiled_class_hash_writes,
+                class_hash,
+            );
+        }
+    }
+
+    /// Merges the undo log of a later savepoint into this one; values recorded here prevail.
+    fn merge(&mut self, later: Savepoint) {
+        fn merge_log<K: Eq + Hash, V>(log: &mut HashMap<K, V>, later_log: HashMap<K, V>) {
+            for (key, value) in later_log {
+                log.entry(key).or_insert(value);
+            }
+        }
+
+        merge_log(&mut self.nonce_writes, later.nonce_writes);
+        merge_log(&mut self.class_hash_writes, later.class_hash_writes);
+        merge_log(&mut self.storage_writes, later.storage_writes);
+        merge_log(&mut self.compiled_class_hash_writes, later.compiled_class_hash_writes);
+        merge_log(&mut self.class_hash_to_class, later.class_hash_to_class);
+        merge_log(&mut self.visited_pcs, later.visited_pcs);
+    }
+}
+
+/// Records the current value of the given entry in the undo log, unless already recorded.
+fn record_value<K: Clone + Eq + Hash, V: Clone>(
+    undo_log: &mut HashMap<K, Option<V>>,
+    values: &HashMap<K, V>,
+    key: &K,
+) {
+    if !undo_log.contains_key(key) {
+        undo_log.insert(key.clone(), values.get(key).cloned());
+    }
+}
+
+fn restore_values<K: Eq + Hash, V>(values: &mut HashMap<K, V>, undo_log: HashMap<K, Option<V>>) {
+    for (key, value) in undo_log {
+        match value {
+            Some(value) => values.insert(key, value),
+            None => values.remove(&key),
+        };
+    }
+}
+
 #[cfg(any(feature = "testing", test))]
 impl Default for CachedState<crate::test_utils::dict_state_reader::DictStateReader> {
     fn default() -> Self {
         Self {
             state: Default::default(),
             cache: Default::default(),
+            read_set: Default::default(),
             class_hash_to_class: Default::default(),
             global_class_hash_to_class: GlobalContractCache::new(
                 GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST,
             ),
             visited_pcs: Default::default(),
+            savepoints: Default::default(),
         }
     }
 }
@@ -422,7 +666,7 @@ impl StateCache {
         self.storage_initial_values.insert(contract_storage_key, value);
     }
 
-    fn set_storage_value(
+    pub(crate) fn set_storage_value(
         &mut self,
         contract_address: ContractAddress,
         key: StorageKey,
@@ -498,6 +742,112 @@ impl StateCache {
             &self.compiled_class_hash_initial_values,
         )
     }
+
+    /// Returns all writes, including the ones that did not change the written cell.
+    /// Assumes that the initial values of write-only accesses were already cached.
+    fn get_write_set(&self) -> StateWriteSet {
+        StateWriteSet {
+            storage: pair_with_initial_values(&self.storage_writes, &self.storage_initial_values),
+            nonces: pair_with_initial_values(&self.nonce_writes, &self.nonce_initial_values),
+            class_hashes: pair_with_initial_values(
+                &self.class_hash_writes,
+                &self.class_hash_initial_values,
+            ),
+            // Compiled class hashes are only written upon declaration; the initial value of an
+            // undeclared class is the default one.
+            compiled_class_hashes: self
+                .compiled_class_hash_writes
+                .iter()
+                .map(|(&class_hash, &after)| {
+                    let before = self
+                        .compiled_class_hash_initial_values
+                        .get(&class_hash)
+                        .copied()
+                        .unwrap_or_default();
+                    (class_hash, ValueChange { before, after })
+                })
+                .collect(),
+        }
+    }
+}
+
+fn pair_with_initial_values<K, V>(
+    writes: &HashMap<K, V>,
+    initial_values: &HashMap<K, V>,
+) -> HashMap<K, ValueChange<V>>
+where
+    K: Copy + Eq + Hash,
+    V: Copy,
+{
+    writes
+        .iter()
+        .map(|(key, &after)| {
+            let before =
+                *initial_values.get(key).expect("Initial value of a written cell must be cached.");
+            (*key, ValueChange { before, after })
+        })
+        .collect()
+}
+
+/// A read-only view of a `CachedState`, exposing the values cached in it over the ones of the
+/// wrapped state. Unlike the cached state, which updates its caches upon reads, the view may be
+/// shared between threads; reads through it are not cached.
+pub struct CachedStateView<'a, S: StateReader> {
+    state: &'a S,
+    cache: &'a StateCache,
+    class_hash_to_class: &'a ContractClassMapping,
+}
+
+impl<S: StateReader> CachedState<S> {
+    /// Returns a read-only view of the state; the state cannot be modified while it is in use.
+    pub fn view(&mut self) -> CachedStateView<'_, S> {
+        CachedStateView {
+            state: &self.state,
+            cache: self.cache.get_mut(),
+            class_hash_to_class: self.class_hash_to_class.get_mut(),
+        }
+    }
+}
+
+impl<'a, S: StateReader> StateReader for CachedStateView<'a, S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        match self.cache.get_storage_at(contract_address, key) {
+            Some(&value) => Ok(value),
+            None => self.state.get_storage_at(contract_address, key),
+        }
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        match self.cache.get_nonce_at(contract_address) {
+            Some(&nonce) => Ok(nonce),
+            None => self.state.get_nonce_at(contract_address),
+        }
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        match self.cache.get_class_hash_at(contract_address) {
+            Some(&class_hash) => Ok(class_hash),
+            None => self.state.get_class_hash_at(contract_address),
+        }
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        match self.class_hash_to_class.get(&class_hash) {
+            Some(contract_class) => Ok(contract_class.clone()),
+            None => self.state.get_compiled_contract_class(class_hash),
+        }
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        match self.cache.get_compiled_class_hash(class_hash) {
+            Some(&compiled_class_hash) => Ok(compiled_class_hash),
+            None => self.state.get_compiled_class_hash(class_hash),
+        }
+    }
 }
 
 /// Wraps a mutable reference to a `State` object, exposing its API.
@@ -641,7 +991,7 @@ pub struct StagedTransactionalState {
 }
 
 /// Holds uncommitted changes induced on Starknet contracts.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct CommitmentStateDiff {
     // Contract instance attributes (per address).
     pub address_to_class_hash: IndexMap<ContractAddress, ClassHash>,
@@ -714,6 +1064,39 @@ impl StateChangesKeys {
     }
 }
 
+/// The cells read by a transaction from the state it was executed on, with the values observed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateReadSet {
+    pub storage: HashMap<StorageEntry, StarkFelt>,
+    pub nonces: HashMap<ContractAddress, Nonce>,
+    pub class_hashes: HashMap<ContractAddress, ClassHash>,
+    pub compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+}
+
+/// The value of a cell before and after it was written.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ValueChange<T> {
+    pub before: T,
+    pub after: T,
+}
+
+/// The cells written by a transaction; unlike `StateChanges`, includes writes that did not change
+/// the cell's value.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateWriteSet {
+    pub storage: HashMap<StorageEntry, ValueChange<StarkFelt>>,
+    pub nonces: HashMap<ContractAddress, ValueChange<Nonce>>,
+    pub class_hashes: HashMap<ContractAddress, ValueChange<ClassHash>>,
+    pub compiled_class_hashes: HashMap<ClassHash, ValueChange<CompiledClassHash>>,
+}
+
+/// The read and write sets of a transaction.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateAccesses {
+    pub read_set: StateReadSet,
+    pub write_set: StateWriteSet,
+}
+
 /// Holds the state changes.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct StateChanges {
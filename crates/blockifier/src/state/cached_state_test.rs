@@ -555,3 +555,171 @@ fn test_state_changes_keys() {
         }
     )
 }
+
+#[test]
+fn test_state_accesses() {
+    let contract_address0 = contract_address!("0x100");
+    let contract_address1 = contract_address!("0x200");
+    let read_key = StorageKey(patricia_key!("0x10"));
+    let read_write_key = StorageKey(patricia_key!("0x20"));
+    let write_key = StorageKey(patricia_key!("0x30"));
+    let class_hash = class_hash!("0x300");
+    let compiled_class_hash = CompiledClassHash(stark_felt!("0x3"));
+
+    let mut state = CachedState::from(DictStateReader {
+        storage_view: HashMap::from([
+            ((contract_address0, read_key), stark_felt!("0x1")),
+            ((contract_address0, read_write_key), stark_felt!("0x2")),
+            ((contract_address0, write_key), stark_felt!("0x3")),
+        ]),
+        address_to_nonce: HashMap::from([(contract_address0, Nonce(stark_felt!("0x7")))]),
+        ..Default::default()
+    });
+    let mut transactional_state = CachedState::create_transactional(&mut state);
+
+    transactional_state.get_storage_at(contract_address0, read_key).unwrap();
+    transactional_state.get_storage_at(contract_address0, read_write_key).unwrap();
+    transactional_state
+        .set_storage_at(contract_address0, read_write_key, stark_felt!("0x5"))
+        .unwrap();
+    // A no-op write, followed by a read of the written value.
+    transactional_state.set_storage_at(contract_address0, write_key, stark_felt!("0x3")).unwrap();
+    transactional_state.get_storage_at(contract_address0, write_key).unwrap();
+    transactional_state.increment_nonce(contract_address0).unwrap();
+    transactional_state.set_class_hash_at(contract_address1, class_hash).unwrap();
+    transactional_state.set_compiled_class_hash(class_hash, compiled_class_hash).unwrap();
+
+    let expected_read_set = StateReadSet {
+        storage: HashMap::from([
+            ((contract_address0, read_key), stark_felt!("0x1")),
+            ((contract_address0, read_write_key), stark_felt!("0x2")),
+        ]),
+        nonces: HashMap::from([(contract_address0, Nonce(stark_felt!("0x7")))]),
+        ..Default::default()
+    };
+    let expected_write_set = StateWriteSet {
+        storage: HashMap::from([
+            (
+                (contract_address0, read_write_key),
+                ValueChange { before: stark_felt!("0x2"), after: stark_felt!("0x5") },
+            ),
+            (
+                (contract_address0, write_key),
+                ValueChange { before: stark_felt!("0x3"), after: stark_felt!("0x3") },
+            ),
+        ]),
+        nonces: HashMap::from([(
+            contract_address0,
+            ValueChange { before: Nonce(stark_felt!("0x7")), after: Nonce(stark_felt!("0x8")) },
+        )]),
+        class_hashes: HashMap::from([(
+            contract_address1,
+            ValueChange { before: ClassHash::default(), after: class_hash },
+        )]),
+        compiled_class_hashes: HashMap::from([(
+            class_hash,
+            ValueChange { before: CompiledClassHash::default(), after: compiled_class_hash },
+        )]),
+    };
+    assert_eq!(
+        transactional_state.get_state_accesses().unwrap(),
+        StateAccesses { read_set: expected_read_set, write_set: expected_write_set }
+    );
+}
+
+#[test]
+fn test_rollback_to_savepoint() {
+    let contract_address = contract_address!("0x100");
+    let key0 = StorageKey(patricia_key!("0x10"));
+    let key1 = StorageKey(patricia_key!("0x20"));
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let mut state: CachedState<DictStateReader> = CachedState::default();
+
+    state.set_storage_at(contract_address, key0, stark_felt!("0x1")).unwrap();
+    let first_savepoint = state.checkpoint();
+    state.set_storage_at(contract_address, key0, stark_felt!("0x2")).unwrap();
+    state.increment_nonce(contract_address).unwrap();
+
+    // Changes committed from a transactional state are undone as well.
+    let second_savepoint = state.checkpoint();
+    let mut transactional_state = CachedState::create_transactional(&mut state);
+    transactional_state.set_storage_at(contract_address, key1, stark_felt!("0x3")).unwrap();
+    transactional_state.set_class_hash_at(contract_address, class_hash).unwrap();
+    transactional_state.set_contract_class(class_hash, get_test_contract_class()).unwrap();
+    transactional_state.add_visited_pcs(class_hash, &HashSet::from([1, 2]));
+    transactional_state.commit();
+
+    state.rollback_to(second_savepoint);
+    assert_eq!(state.get_storage_at(contract_address, key0).unwrap(), stark_felt!("0x2"));
+    assert_eq!(state.get_storage_at(contract_address, key1).unwrap(), StarkFelt::default());
+    assert_eq!(state.get_nonce_at(contract_address).unwrap(), Nonce(stark_felt!("0x1")));
+    assert_eq!(state.get_class_hash_at(contract_address).unwrap(), ClassHash::default());
+    assert_matches!(
+        state.get_compiled_contract_class(class_hash),
+        Err(StateError::UndeclaredClassHash(undeclared_class_hash))
+        if undeclared_class_hash == class_hash
+    );
+    assert!(state.visited_pcs.is_empty());
+
+    // Savepoints remain valid after a rollback.
+    state.set_storage_at(contract_address, key1, stark_felt!("0x4")).unwrap();
+    state.rollback_to(second_savepoint);
+    assert_eq!(state.get_storage_at(contract_address, key1).unwrap(), StarkFelt::default());
+
+    state.rollback_to(first_savepoint);
+    assert_eq!(state.get_nonce_at(contract_address).unwrap(), Nonce::default());
+    let expected_storage_updates =
+        IndexMap::from([(contract_address, IndexMap::from([(key0, stark_felt!("0x1"))]))]);
+    assert_eq!(state.to_state_diff().storage_updates, expected_storage_updates);
+}
+
+#[test]
+fn test_release_savepoint() {
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let mut state: CachedState<DictStateReader> = CachedState::default();
+
+    let first_savepoint = state.checkpoint();
+    state.set_storage_at(contract_address, key, stark_felt!("0x1")).unwrap();
+    let second_savepoint = state.checkpoint();
+    state.set_storage_at(contract_address, key, stark_felt!("0x2")).unwrap();
+
+    // The changes made since a released savepoint are undone by rolling back to an earlier one.
+    state.release_savepoint(second_savepoint);
+    state.rollback_to(first_savepoint);
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), StarkFelt::default());
+}
+
+#[test]
+#[should_panic(expected = "Cannot roll back to a released savepoint")]
+fn test_rollback_to_released_savepoint() {
+    let mut state: CachedState<DictStateReader> = CachedState::default();
+    let first_savepoint = state.checkpoint();
+    let second_savepoint = state.checkpoint();
+
+    state.rollback_to(first_savepoint);
+    state.rollback_to(second_savepoint);
+}
+
+#[test]
+fn test_state_view() {
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let mut state = CachedState::from(DictStateReader {
+        storage_view: HashMap::from([((contract_address, key), stark_felt!("0x1"))]),
+        address_to_nonce: HashMap::from([(contract_address, Nonce(stark_felt!("0x2")))]),
+        ..Default::default()
+    });
+    state.set_storage_at(contract_address, key, stark_felt!("0x3")).unwrap();
+    state.set_contract_class(class_hash, get_test_contract_class()).unwrap();
+
+    // The view exposes the values cached in the state over the ones of the wrapped state.
+    let view = state.view();
+    assert_eq!(view.get_storage_at(contract_address, key).unwrap(), stark_felt!("0x3"));
+    assert_eq!(view.get_nonce_at(contract_address).unwrap(), Nonce(stark_felt!("0x2")));
+    assert_eq!(view.get_compiled_contract_class(class_hash).unwrap(), get_test_contract_class());
+
+    // Reads through the view are not cached in the state.
+    assert!(state.cache.get_mut().nonce_initial_values.is_empty());
+}
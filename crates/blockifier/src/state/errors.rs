@@ -4,6 +4,7 @@ use starknet_api::StarknetApiError;
 use thiserror::Error;
 
 use crate::abi::constants;
+use crate::state::state_snapshot::STATE_SNAPSHOT_VERSION;
 
 #[derive(Debug, Error)]
 pub enum StateError {
@@ -26,3 +27,24 @@ pub enum StateError {
     #[error("Failed to read from state: {0}.")]
     StateReadError(String),
 }
+
+#[derive(Debug, Error)]
+pub enum StateRecordingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    SerdeError(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum StateSnapshotError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    SerdeError(#[from] serde_json::Error),
+    #[error(
+        "Unsupported state snapshot version: {0:?}; expected version {}.",
+        STATE_SNAPSHOT_VERSION
+    )]
+    UnsupportedVersion(Option<u64>),
+}
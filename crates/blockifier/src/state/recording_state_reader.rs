@@ -0,0 +1,230 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use crate::execution::contract_class::ContractClass;
+use crate::state::errors::{StateError, StateRecordingError};
+use crate::state::state_api::{StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "recording_state_reader_test.rs"]
+mod test;
+
+pub type StateRecordingResult<T> = Result<T, StateRecordingError>;
+
+/// The outcome of a single read, as served by the recorded state reader.
+pub type RecordedRead<T> = Result<T, RecordedStateError>;
+
+/// A serializable form of the errors a state reader may return.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum RecordedStateError {
+    UndeclaredClassHash(ClassHash),
+    StateReadError(String),
+}
+
+impl From<&StateError> for RecordedStateError {
+    fn from(error: &StateError) -> Self {
+        match error {
+            StateError::UndeclaredClassHash(class_hash) => Self::UndeclaredClassHash(*class_hash),
+            StateError::StateReadError(message) => Self::StateReadError(message.clone()),
+            error => Self::StateReadError(error.to_string()),
+        }
+    }
+}
+
+impl From<RecordedStateError> for StateError {
+    fn from(error: RecordedStateError) -> Self {
+        match error {
+            RecordedStateError::UndeclaredClassHash(class_hash) => {
+                Self::UndeclaredClassHash(class_hash)
+            }
+            RecordedStateError::StateReadError(message) => Self::StateReadError(message),
+        }
+    }
+}
+
+/// The results of all reads served by a state reader, keyed by the read arguments.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateRecording {
+    #[serde(with = "map_entries")]
+    pub storage: BTreeMap<(ContractAddress, StorageKey), RecordedRead<StarkFelt>>,
+    #[serde(with = "map_entries")]
+    pub nonces: BTreeMap<ContractAddress, RecordedRead<Nonce>>,
+    #[serde(with = "map_entries")]
+    pub class_hashes: BTreeMap<ContractAddress, RecordedRead<ClassHash>>,
+    #[serde(with = "map_entries")]
+    pub compiled_contract_classes: BTreeMap<ClassHash, RecordedRead<ContractClass>>,
+    #[serde(with = "map_entries")]
+    pub compiled_class_hashes: BTreeMap<ClassHash, RecordedRead<CompiledClassHash>>,
+}
+
+impl StateRecording {
+    /// Writes the recording, as JSON, to the given file.
+    pub fn dump(&self, path: &Path) -> StateRecordingResult<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        Ok(serde_json::to_writer(writer, self)?)
+    }
+
+    /// Reads a recording previously written by `dump`.
+    pub fn load(path: &Path) -> StateRecordingResult<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+/// A state reader that records the result of every read it forwards to the wrapped reader.
+/// The recording suffices to serve the same reads again, without the wrapped reader; see
+/// `ReplayStateReader`.
+pub struct RecordingStateReader<S: StateReader> {
+    pub state: S,
+    // Using interior mutability to record during `StateReader`'s immutable getters.
+    recording: RefCell<StateRecording>,
+}
+
+impl<S: StateReader> RecordingStateReader<S> {
+    pub fn new(state: S) -> Self {
+        Self { state, recording: RefCell::new(StateRecording::default()) }
+    }
+
+    pub fn recording(&self) -> StateRecording {
+        self.recording.borrow().clone()
+    }
+
+    pub fn into_recording(self) -> StateRecording {
+        self.recording.into_inner()
+    }
+}
+
+impl<S: StateReader> StateReader for RecordingStateReader<S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        let result = self.state.get_storage_at(contract_address, key);
+        record(&mut self.recording.borrow_mut().storage, (contract_address, key), &result);
+        result
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        let result = self.state.get_nonce_at(contract_address);
+        record(&mut self.recording.borrow_mut().nonces, contract_address, &result);
+        result
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        let result = self.state.get_class_hash_at(contract_address);
+        record(&mut self.recording.borrow_mut().class_hashes, contract_address, &result);
+        result
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        let result = self.state.get_compiled_contract_class(class_hash);
+        record(&mut self.recording.borrow_mut().compiled_contract_classes, class_hash, &result);
+        result
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        let result = self.state.get_compiled_class_hash(class_hash);
+        record(&mut self.recording.borrow_mut().compiled_class_hashes, class_hash, &result);
+        result
+    }
+}
+
+/// A state reader that serves reads from a recording alone; reads that were not recorded fail.
+#[derive(Debug, Default)]
+pub struct ReplayStateReader {
+    pub recording: StateRecording,
+}
+
+impl ReplayStateReader {
+    pub fn new(recording: StateRecording) -> Self {
+        Self { recording }
+    }
+
+    pub fn load(path: &Path) -> StateRecordingResult<Self> {
+        Ok(Self::new(StateRecording::load(path)?))
+    }
+}
+
+impl StateReader for ReplayStateReader {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        replay(&self.recording.storage, (contract_address, key), "storage")
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        replay(&self.recording.nonces, contract_address, "nonce")
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        replay(&self.recording.class_hashes, contract_address, "class hash")
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        replay(&self.recording.compiled_contract_classes, class_hash, "compiled contract class")
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        replay(&self.recording.compiled_class_hashes, class_hash, "compiled class hash")
+    }
+}
+
+// Utilities.
+
+/// Records the result of a read; a repeated read keeps the first result, as the wrapped state is
+/// not expected to change during the recording.
+fn record<K: Ord, T: Clone>(
+    reads: &mut BTreeMap<K, RecordedRead<T>>,
+    key: K,
+    result: &StateResult<T>,
+) {
+    reads.entry(key).or_insert_with(|| result.as_ref().cloned().map_err(RecordedStateError::from));
+}
+
+fn replay<K: Ord + Debug, T: Clone>(
+    reads: &BTreeMap<K, RecordedRead<T>>,
+    key: K,
+    read_name: &str,
+) -> StateResult<T> {
+    match reads.get(&key) {
+        Some(result) => result.clone().map_err(StateError::from),
+        None => {
+            Err(StateError::StateReadError(format!("No {read_name} read was recorded for {key:?}")))
+        }
+    }
+}
+
+/// (De)serializes a map as a sequence of entries, as JSON only supports string keys.
+mod map_entries {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<K: Serialize, V: Serialize, S: Serializer>(
+        map: &BTreeMap<K, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map)
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
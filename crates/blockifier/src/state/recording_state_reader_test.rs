@@ -0,0 +1,96 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::Fee;
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::cached_state::CachedState;
+use crate::state::errors::StateError;
+use crate::state::recording_state_reader::{
+    RecordingStateReader, ReplayStateReader, StateRecording,
+};
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::test_utils::{account_invoke_tx, block_context};
+use crate::transaction::transactions::ExecutableTransaction;
+
+fn execute_invoke<S: StateReader>(
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+    cairo_version: CairoVersion,
+) -> TransactionExecutionInfo {
+    let account = FeatureContract::AccountWithoutValidations(cairo_version);
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let calldata = create_calldata(
+        test_contract.get_instance_address(0),
+        "test_storage_read_write",
+        &[stark_felt!(1_u8), stark_felt!(2_u8)],
+    );
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account.get_instance_address(0),
+        calldata,
+    });
+    tx.execute(state, block_context, true, true).unwrap()
+}
+
+#[rstest]
+fn test_replay_matches_recorded_execution(
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let account = FeatureContract::AccountWithoutValidations(cairo_version);
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let initial_state: DictStateReader =
+        test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]).state;
+
+    let mut recorded_state = CachedState::from(RecordingStateReader::new(initial_state));
+    let recorded_execution_info =
+        execute_invoke(&mut recorded_state, &block_context, cairo_version);
+
+    let path = std::env::temp_dir()
+        .join(format!("blockifier_state_recording_{cairo_version:?}_{}.json", std::process::id()));
+    recorded_state.state.recording().dump(&path).unwrap();
+    let replay_state_reader = ReplayStateReader::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    let replay_state_reader = replay_state_reader.unwrap();
+    assert_eq!(replay_state_reader.recording, recorded_state.state.recording());
+
+    let mut replayed_state = CachedState::from(replay_state_reader);
+    let replayed_execution_info =
+        execute_invoke(&mut replayed_state, &block_context, cairo_version);
+
+    assert_eq!(replayed_execution_info, recorded_execution_info);
+    assert_eq!(replayed_state.to_state_diff(), recorded_state.to_state_diff());
+}
+
+#[test]
+fn test_replay_errors() {
+    let undeclared_class_hash = class_hash!("0x10");
+    let recording_state_reader = RecordingStateReader::new(DictStateReader::default());
+    recording_state_reader.get_compiled_contract_class(undeclared_class_hash).unwrap_err();
+    let recording: StateRecording = serde_json::from_str(
+        &serde_json::to_string(&recording_state_reader.into_recording()).unwrap(),
+    )
+    .unwrap();
+
+    let replay_state_reader = ReplayStateReader::new(recording);
+    assert_matches!(
+        replay_state_reader.get_compiled_contract_class(undeclared_class_hash),
+        Err(StateError::UndeclaredClassHash(class_hash)) if class_hash == undeclared_class_hash
+    );
+    assert_matches!(
+        replay_state_reader
+            .get_storage_at(contract_address!("0x1"), StorageKey(patricia_key!("0x2"))),
+        Err(StateError::StateReadError(_))
+    );
+}
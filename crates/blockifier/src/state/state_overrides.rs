@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use crate::abi::abi_utils::get_fee_token_var_address;
+use crate::abi::sierra_types::next_storage_key;
+use crate::execution::contract_class::ContractClass;
+use crate::state::state_api::{StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "state_overrides_test.rs"]
+mod test;
+
+/// User-supplied state values, taking precedence over the values of the overridden state.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateOverrides {
+    pub storage: HashMap<(ContractAddress, StorageKey), StarkFelt>,
+    pub nonces: HashMap<ContractAddress, Nonce>,
+    pub class_hashes: HashMap<ContractAddress, ClassHash>,
+    pub contract_classes: HashMap<ClassHash, ContractClass>,
+    pub compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+}
+
+impl StateOverrides {
+    /// Overrides the balance of the given account in the given fee token.
+    pub fn set_fee_token_balance(
+        &mut self,
+        contract_address: ContractAddress,
+        fee_token_address: ContractAddress,
+        balance: u128,
+    ) -> StateResult<()> {
+        let low_key = get_fee_token_var_address(contract_address);
+        let high_key = next_storage_key(&low_key)?;
+        self.storage.insert((fee_token_address, low_key), StarkFelt::from(balance));
+        self.storage.insert((fee_token_address, high_key), StarkFelt::ZERO);
+
+        Ok(())
+    }
+}
+
+/// A state reader that serves the given overrides, and delegates any other read to the wrapped
+/// state; the wrapped state itself is left untouched.
+///
+/// Note: classes are cached by class hash (see `GlobalContractCache`); an overriding class should
+/// either have a class hash unknown to the cache, or be executed with a fresh cache.
+pub struct OverriddenStateReader<S: StateReader> {
+    pub state: S,
+    pub overrides: StateOverrides,
+}
+
+impl<S: StateReader> OverriddenStateReader<S> {
+    pub fn new(state: S, overrides: StateOverrides) -> Self {
+        Self { state, overrides }
+    }
+}
+
+impl<S: StateReader> StateReader for OverriddenStateReader<S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        match self.overrides.storage.get(&(contract_address, key)) {
+            Some(value) => Ok(*value),
+            None => self.state.get_storage_at(contract_address, key),
+        }
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        match self.overrides.nonces.get(&contract_address) {
+            Some(nonce) => Ok(*nonce),
+            None => self.state.get_nonce_at(contract_address),
+        }
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        match self.overrides.class_hashes.get(&contract_address) {
+            Some(class_hash) => Ok(*class_hash),
+            None => self.state.get_class_hash_at(contract_address),
+        }
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        match self.overrides.contract_classes.get(&class_hash) {
+            Some(contract_class) => Ok(contract_class.clone()),
+            None => self.state.get_compiled_contract_class(class_hash),
+        }
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        match self.overrides.compiled_class_hashes.get(&class_hash) {
+            Some(compiled_class_hash) => Ok(*compiled_class_hash),
+            None => self.state.get_compiled_class_hash(class_hash),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::{ClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::Fee;
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::abi::abi_utils::get_fee_token_var_address;
+use crate::context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::cached_state::{
+    CachedState, GlobalContractCache, GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST,
+};
+use crate::state::errors::StateError;
+use crate::state::state_api::StateReader;
+use crate::state::state_overrides::{OverriddenStateReader, StateOverrides};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{
+    create_calldata, get_test_contract_class, CairoVersion, BALANCE, MAX_FEE, TEST_CLASS_HASH,
+};
+use crate::transaction::test_utils::{account_invoke_tx, block_context};
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[test]
+fn test_overrides_take_precedence() {
+    let contract_address = contract_address!("0x1");
+    let (key, other_key) = (StorageKey(patricia_key!("0x10")), StorageKey(patricia_key!("0x11")));
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let fee_token_address = contract_address!("0x2");
+    let initial_state = DictStateReader {
+        storage_view: HashMap::from([
+            ((contract_address, key), stark_felt!("0x5")),
+            ((contract_address, other_key), stark_felt!("0x6")),
+        ]),
+        address_to_nonce: HashMap::from([(contract_address, Nonce(stark_felt!("0x2")))]),
+        ..Default::default()
+    };
+
+    let mut overrides = StateOverrides {
+        storage: HashMap::from([((contract_address, key), stark_felt!("0x7"))]),
+        class_hashes: HashMap::from([(contract_address, class_hash)]),
+        contract_classes: HashMap::from([(class_hash, get_test_contract_class())]),
+        ..Default::default()
+    };
+    overrides.set_fee_token_balance(contract_address, fee_token_address, 8).unwrap();
+    let mut state = OverriddenStateReader::new(initial_state.clone(), overrides);
+
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), stark_felt!("0x7"));
+    assert_eq!(state.get_storage_at(contract_address, other_key).unwrap(), stark_felt!("0x6"));
+    assert_eq!(state.get_nonce_at(contract_address).unwrap(), Nonce(stark_felt!("0x2")));
+    assert_eq!(state.get_class_hash_at(contract_address).unwrap(), class_hash);
+    assert_eq!(state.get_compiled_contract_class(class_hash).unwrap(), get_test_contract_class());
+    assert_matches!(
+        state.get_compiled_contract_class(class_hash!("0x3")),
+        Err(StateError::UndeclaredClassHash(_))
+    );
+    assert_eq!(
+        state.get_fee_token_balance(contract_address, fee_token_address).unwrap(),
+        (stark_felt!(8_u8), StarkFelt::ZERO)
+    );
+    assert_eq!(state.state, initial_state);
+}
+
+#[rstest]
+fn test_execution_against_overridden_state(block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let account_address = account.get_instance_address(0);
+    // The account is unfunded, and the injected contract undeclared, in the backing state.
+    let initial_state = test_state(&block_context.chain_info, 0, &[(account, 1)]).state;
+    let injected_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let injected_contract_address = contract_address!("0x1234");
+
+    let mut overrides = StateOverrides {
+        nonces: HashMap::from([(account_address, Nonce(stark_felt!(5_u8)))]),
+        class_hashes: HashMap::from([(
+            injected_contract_address,
+            injected_contract.get_class_hash(),
+        )]),
+        contract_classes: HashMap::from([(
+            injected_contract.get_class_hash(),
+            injected_contract.get_class(),
+        )]),
+        ..Default::default()
+    };
+    let eth_fee_token_address = block_context.chain_info.fee_token_addresses.eth_fee_token_address;
+    overrides.set_fee_token_balance(account_address, eth_fee_token_address, BALANCE).unwrap();
+
+    let mut state = CachedState::new(
+        OverriddenStateReader::new(initial_state.clone(), overrides),
+        GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
+    );
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account_address,
+        calldata: create_calldata(
+            injected_contract_address,
+            "test_storage_read_write",
+            &[stark_felt!(1_u8), stark_felt!(2_u8)],
+        ),
+        nonce: Nonce(stark_felt!(5_u8)),
+    });
+    let execution_info = tx.execute(&mut state, &block_context, true, true).unwrap();
+
+    assert!(!execution_info.is_reverted());
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), Nonce(stark_felt!(6_u8)));
+    // The backing state is left untouched.
+    assert_eq!(state.state.state, initial_state);
+    assert_eq!(
+        initial_state
+            .get_storage_at(eth_fee_token_address, get_fee_token_var_address(account_address))
+            .unwrap(),
+        StarkFelt::ZERO
+    );
+}
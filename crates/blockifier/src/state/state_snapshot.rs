@@ -0,0 +1,132 @@
+//! A versioned on-disk format for whole states.
+//!
+//! A snapshot is a JSON object of the following form, where all addresses, keys, hashes and values
+//! are hex strings:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "storage": { "<contract address>": { "<storage key>": "<value>" } },
+//!   "address_to_nonce": { "<contract address>": "<nonce>" },
+//!   "address_to_class_hash": { "<contract address>": "<class hash>" },
+//!   "class_hash_to_compiled_class_hash": { "<class hash>": "<compiled class hash>" },
+//!   "class_hash_to_class": { "<class hash>": { "V0": { ... } } }
+//! }
+//! ```
+//!
+//! Classes are in the serialization format of `ContractClass`; that is, either
+//! `{"V0": {"program", "entry_points_by_type"}}` or
+//! `{"V1": {"program", "entry_points_by_type", "hints", "bytecode_segment_lengths"}}`, where the
+//! programs are in the Cairo VM program serialization format.
+//! Cells missing from a snapshot hold their default value, as in any state.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use crate::execution::contract_class::ContractClass;
+use crate::state::errors::{StateError, StateSnapshotError};
+use crate::state::state_api::{StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "state_snapshot_test.rs"]
+mod test;
+
+/// The version of the snapshot format written by this crate; bumped on any format change.
+pub const STATE_SNAPSHOT_VERSION: u64 = 1;
+
+pub type StateSnapshotResult<T> = Result<T, StateSnapshotError>;
+
+/// The content of a whole state; serves as a state reader as well.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSnapshot {
+    pub storage: BTreeMap<ContractAddress, BTreeMap<StorageKey, StarkFelt>>,
+    pub address_to_nonce: BTreeMap<ContractAddress, Nonce>,
+    pub address_to_class_hash: BTreeMap<ContractAddress, ClassHash>,
+    pub class_hash_to_compiled_class_hash: BTreeMap<ClassHash, CompiledClassHash>,
+    pub class_hash_to_class: BTreeMap<ClassHash, ContractClass>,
+}
+
+/// The serialized form of a snapshot, tagged with the format version.
+#[derive(Serialize)]
+struct VersionedStateSnapshot<'a> {
+    version: u64,
+    #[serde(flatten)]
+    snapshot: &'a StateSnapshot,
+}
+
+impl StateSnapshot {
+    /// Writes the snapshot to the given file, in the current format version.
+    pub fn save(&self, path: &Path) -> StateSnapshotResult<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        let snapshot = VersionedStateSnapshot { version: STATE_SNAPSHOT_VERSION, snapshot: self };
+        Ok(serde_json::to_writer_pretty(writer, &snapshot)?)
+    }
+
+    /// Reads a snapshot from the given file; fails on snapshots of other format versions.
+    pub fn load(path: &Path) -> StateSnapshotResult<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let raw_snapshot: serde_json::Value = serde_json::from_reader(reader)?;
+        match raw_snapshot.get("version").and_then(serde_json::Value::as_u64) {
+            Some(STATE_SNAPSHOT_VERSION) => Ok(serde_json::from_value(raw_snapshot)?),
+            version => Err(StateSnapshotError::UnsupportedVersion(version)),
+        }
+    }
+}
+
+impl StateReader for StateSnapshot {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        let value = self
+            .storage
+            .get(&contract_address)
+            .and_then(|contract_storage| contract_storage.get(&key))
+            .copied()
+            .unwrap_or_default();
+        Ok(value)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        Ok(self.address_to_nonce.get(&contract_address).copied().unwrap_or_default())
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        Ok(self.address_to_class_hash.get(&contract_address).copied().unwrap_or_default())
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.class_hash_to_class
+            .get(&class_hash)
+            .cloned()
+            .ok_or(StateError::UndeclaredClassHash(class_hash))
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        Ok(self.class_hash_to_compiled_class_hash.get(&class_hash).copied().unwrap_or_default())
+    }
+}
+
+/// A state whose whole content is known, and can hence be snapshotted.
+pub trait ToStateSnapshot {
+    fn to_snapshot(&self) -> StateSnapshot;
+
+    /// Writes a snapshot of the state to the given file.
+    fn save(&self, path: &Path) -> StateSnapshotResult<()> {
+        self.to_snapshot().save(path)
+    }
+}
+
+impl ToStateSnapshot for StateSnapshot {
+    fn to_snapshot(&self) -> StateSnapshot {
+        self.clone()
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use serde_json::json;
+use starknet_api::core::{ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::Fee;
+use starknet_api::{contract_address, patricia_key, stark_felt};
+
+use crate::context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::cached_state::CachedState;
+use crate::state::errors::StateSnapshotError;
+use crate::state::state_snapshot::{StateSnapshot, ToStateSnapshot, STATE_SNAPSHOT_VERSION};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::test_utils::{account_invoke_tx, block_context};
+use crate::transaction::transactions::ExecutableTransaction;
+
+fn snapshot_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("blockifier_{name}_{}.json", std::process::id()))
+}
+
+fn save_and_load(snapshot: &StateSnapshot, name: &str) -> StateSnapshot {
+    let path = snapshot_path(name);
+    snapshot.save(&path).unwrap();
+    let loaded_snapshot = StateSnapshot::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    loaded_snapshot.unwrap()
+}
+
+fn invoke_tx(nonce: u8) -> AccountTransaction {
+    let calldata = create_calldata(
+        FeatureContract::TestContract(CairoVersion::Cairo0).get_instance_address(0),
+        "test_storage_read_write",
+        &[stark_felt!(1_u8), stark_felt!(nonce)],
+    );
+    account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address:
+            FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1).get_instance_address(0),
+        calldata,
+        nonce: Nonce(stark_felt!(nonce)),
+    })
+}
+
+#[rstest]
+fn test_snapshot_round_trip(block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let mut state =
+        test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    invoke_tx(0).execute(&mut state, &block_context, true, true).unwrap();
+
+    // The snapshot of a cached state includes its writes.
+    let snapshot = state.to_snapshot();
+    assert_eq!(
+        snapshot.address_to_nonce[&account.get_instance_address(0)],
+        Nonce(stark_felt!(1_u8))
+    );
+    let loaded_snapshot = save_and_load(&snapshot, "test_snapshot_round_trip");
+    assert_eq!(loaded_snapshot, snapshot);
+
+    // Both class versions survive the round trip; execution continues identically.
+    let mut loaded_state = CachedState::from(DictStateReader::from(loaded_snapshot));
+    let expected_execution_info =
+        invoke_tx(1).execute(&mut state, &block_context, true, true).unwrap();
+    let execution_info =
+        invoke_tx(1).execute(&mut loaded_state, &block_context, true, true).unwrap();
+    assert_eq!(execution_info, expected_execution_info);
+    assert_eq!(loaded_state.to_snapshot(), state.to_snapshot());
+}
+
+#[test]
+fn test_snapshot_format() {
+    let contract_address = contract_address!("0x1");
+    let state = DictStateReader {
+        storage_view: HashMap::from([(
+            (contract_address, StorageKey(patricia_key!("0x10"))),
+            stark_felt!("0x5"),
+        )]),
+        address_to_nonce: HashMap::from([(contract_address, Nonce(stark_felt!("0x2")))]),
+        ..Default::default()
+    };
+
+    let path = snapshot_path("test_snapshot_format");
+    state.save(&path).unwrap();
+    let raw_snapshot: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let address = "0x1";
+    assert_eq!(
+        raw_snapshot,
+        json!({
+            "version": STATE_SNAPSHOT_VERSION,
+            "storage": { address: { "0x10": "0x5" } },
+            "address_to_nonce": { address: "0x2" },
+            "address_to_class_hash": {},
+            "class_hash_to_compiled_class_hash": {},
+            "class_hash_to_class": {},
+        })
+    );
+    assert_eq!(DictStateReader::from(state.to_snapshot()), state);
+}
+
+#[rstest]
+#[case::unsupported_version(json!({ "version": STATE_SNAPSHOT_VERSION + 1 }), Some(STATE_SNAPSHOT_VERSION + 1))]
+#[case::missing_version(json!({}), None)]
+fn test_unsupported_snapshot_version(
+    #[case] raw_snapshot: serde_json::Value,
+    #[case] expected_version: Option<u64>,
+) {
+    let path = snapshot_path(&format!("test_unsupported_snapshot_version_{expected_version:?}"));
+    std::fs::write(&path, raw_snapshot.to_string()).unwrap();
+    let result = StateSnapshot::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_matches!(
+        result,
+        Err(StateSnapshotError::UnsupportedVersion(version)) if version == expected_version
+    );
+}
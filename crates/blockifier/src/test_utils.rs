@@ -79,6 +79,10 @@ pub const TEST_EMPTY_CONTRACT_CAIRO0_PATH: &str =
     "./feature_contracts/cairo0/compiled/empty_contract_compiled.json";
 pub const TEST_EMPTY_CONTRACT_CAIRO1_PATH: &str =
     "./feature_contracts/cairo1/compiled/empty_contract.casm.json";
+pub const MINIMAL_CONTRACT_SIERRA_PATH: &str =
+    "./feature_contracts/cairo1/compiled/minimal_contract.sierra.json";
+pub const MINIMAL_CONTRACT_CASM_PATH: &str =
+    "./feature_contracts/cairo1/compiled/minimal_contract.casm.json";
 pub const TEST_FAULTY_ACCOUNT_CONTRACT_CAIRO0_PATH: &str =
     "./feature_contracts/cairo0/compiled/account_faulty_compiled.json";
 pub const ERC20_CONTRACT_PATH: &str =
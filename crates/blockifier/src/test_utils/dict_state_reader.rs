@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
@@ -8,9 +8,10 @@ use crate::execution::contract_class::ContractClass;
 use crate::state::cached_state::StorageEntry;
 use crate::state::errors::StateError;
 use crate::state::state_api::{StateReader, StateResult};
+use crate::state::state_snapshot::{StateSnapshot, ToStateSnapshot};
 
 /// A simple implementation of `StateReader` using `HashMap`s as storage.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct DictStateReader {
     pub storage_view: HashMap<StorageEntry, StarkFelt>,
     pub address_to_nonce: HashMap<ContractAddress, Nonce>,
@@ -58,3 +59,49 @@ impl StateReader for DictStateReader {
         Ok(compiled_class_hash)
     }
 }
+
+impl ToStateSnapshot for DictStateReader {
+    fn to_snapshot(&self) -> StateSnapshot {
+        let mut storage: BTreeMap<_, BTreeMap<_, _>> = BTreeMap::new();
+        for (&(contract_address, key), &value) in &self.storage_view {
+            storage.entry(contract_address).or_default().insert(key, value);
+        }
+
+        StateSnapshot {
+            storage,
+            address_to_nonce: self.address_to_nonce.clone().into_iter().collect(),
+            address_to_class_hash: self.address_to_class_hash.clone().into_iter().collect(),
+            class_hash_to_compiled_class_hash: self
+                .class_hash_to_compiled_class_hash
+                .clone()
+                .into_iter()
+                .collect(),
+            class_hash_to_class: self.class_hash_to_class.clone().into_iter().collect(),
+        }
+    }
+}
+
+impl From<StateSnapshot> for DictStateReader {
+    fn from(snapshot: StateSnapshot) -> Self {
+        let storage_view = snapshot
+            .storage
+            .into_iter()
+            .flat_map(|(contract_address, contract_storage)| {
+                contract_storage
+                    .into_iter()
+                    .map(move |(key, value)| ((contract_address, key), value))
+            })
+            .collect();
+
+        Self {
+            storage_view,
+            address_to_nonce: snapshot.address_to_nonce.into_iter().collect(),
+            address_to_class_hash: snapshot.address_to_class_hash.into_iter().collect(),
+            class_hash_to_class: snapshot.class_hash_to_class.into_iter().collect(),
+            class_hash_to_compiled_class_hash: snapshot
+                .class_hash_to_compiled_class_hash
+                .into_iter()
+                .collect(),
+        }
+    }
+}
@@ -123,6 +123,8 @@ impl BlockContext {
             block_info: BlockInfo::create_for_testing(),
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_testing(),
+            vm_tracer: None,
+            concurrency_mode: false,
         }
     }
 
@@ -131,6 +133,8 @@ impl BlockContext {
             block_info: BlockInfo::create_for_testing(),
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_account_testing(),
+            vm_tracer: None,
+            concurrency_mode: false,
         }
     }
 
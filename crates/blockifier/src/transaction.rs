@@ -2,9 +2,13 @@ pub mod account_transaction;
 pub mod constants;
 pub mod errors;
 pub mod objects;
+pub mod receipt;
+pub mod simulation;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
+pub mod trace;
 pub mod transaction_execution;
+pub mod transaction_hash;
 pub mod transaction_types;
 pub mod transaction_utils;
 pub mod transactions;
@@ -15,7 +15,9 @@ use crate::execution::contract_class::ContractClass;
 use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
 use crate::fee::actual_cost::{ActualCost, ActualCostBuilder};
 use crate::fee::fee_checks::{FeeCheckReportFields, PostExecutionReport};
-use crate::fee::fee_utils::{get_fee_by_gas_vector, verify_can_pay_committed_bounds};
+use crate::fee::fee_utils::{
+    get_fee_by_gas_vector, get_sequencer_balance_keys, verify_can_pay_committed_bounds,
+};
 use crate::fee::gas_usage::{compute_discounted_gas_from_gas_vector, estimate_minimal_gas_vector};
 use crate::retdata;
 use crate::state::cached_state::{CachedState, TransactionalState};
@@ -48,7 +50,7 @@ mod flavors_test;
 mod post_execution_test;
 
 /// Represents a paid Starknet transaction.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum AccountTransaction {
     Declare(DeclareTransaction),
     DeployAccount(DeployAccountTransaction),
@@ -129,7 +131,10 @@ impl AccountTransaction {
         signature.0.len()
     }
 
-    fn verify_tx_version(&self, version: TransactionVersion) -> TransactionExecutionResult<()> {
+    pub(crate) fn verify_tx_version(
+        &self,
+        version: TransactionVersion,
+    ) -> TransactionExecutionResult<()> {
         let allowed_versions: Vec<TransactionVersion> = match self {
             // Support `Declare` of version 0 in order to allow bootstrapping of a new system.
             Self::Declare(_) => {
@@ -270,9 +275,9 @@ impl AccountTransaction {
         }
     }
 
-    fn handle_fee(
+    fn handle_fee<S: StateReader>(
         &self,
-        state: &mut dyn State,
+        state: &mut TransactionalState<'_, S>,
         tx_context: Arc<TransactionContext>,
         actual_fee: Fee,
         charge_fee: bool,
@@ -283,11 +288,44 @@ impl AccountTransaction {
         }
 
         // Charge fee.
-        let fee_transfer_call_info = Self::execute_fee_transfer(state, tx_context, actual_fee)?;
+        let fee_transfer_call_info =
+            if tx_context.block_context.concurrency_mode && !tx_context.is_sequencer_the_sender() {
+                Self::concurrency_execute_fee_transfer(state, tx_context, actual_fee)?
+            } else {
+                Self::execute_fee_transfer(state, tx_context, actual_fee)?
+            };
 
         Ok(Some(fee_transfer_call_info))
     }
 
+    /// Executes the fee transfer without accessing the balance of the sequencer, to which all
+    /// transactions of the block transfer their fees: the fee is transferred to a zero balance,
+    /// and the update of the balance is discarded. The fee is added to the actual balance upon
+    /// commit; see `ExecutionOutput::add_fee_to_sequencer_balance`.
+    fn concurrency_execute_fee_transfer<S: StateReader>(
+        state: &mut TransactionalState<'_, S>,
+        tx_context: Arc<TransactionContext>,
+        actual_fee: Fee,
+    ) -> TransactionExecutionResult<CallInfo> {
+        let TransactionContext { block_context, tx_info } = tx_context.as_ref();
+        let fee_token_address = block_context.chain_info.fee_token_address(&tx_info.fee_type());
+        let (sequencer_balance_key_low, sequencer_balance_key_high) =
+            get_sequencer_balance_keys(block_context);
+
+        let mut transfer_state = CachedState::create_transactional(state);
+        for key in [sequencer_balance_key_low, sequencer_balance_key_high] {
+            transfer_state.set_storage_initial_value(fee_token_address, key, StarkFelt::ZERO);
+        }
+        let fee_transfer_call_info =
+            Self::execute_fee_transfer(&mut transfer_state, tx_context, actual_fee)?;
+        for key in [sequencer_balance_key_low, sequencer_balance_key_high] {
+            transfer_state.discard_storage_write(fee_token_address, key);
+        }
+        transfer_state.commit();
+
+        Ok(fee_transfer_call_info)
+    }
+
     fn execute_fee_transfer(
         state: &mut dyn State,
         tx_context: Arc<TransactionContext>,
@@ -1,11 +1,11 @@
-use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
-use starknet_api::transaction::{Fee, TransactionVersion};
+use starknet_api::transaction::{Fee, TransactionHash, TransactionVersion};
 use starknet_api::StarknetApiError;
 use thiserror::Error;
 
 use crate::execution::call_info::Retdata;
-use crate::execution::errors::EntryPointExecutionError;
+use crate::execution::errors::{ContractClassError, EntryPointExecutionError};
 use crate::fee::fee_checks::FeeCheckError;
 use crate::state::errors::StateError;
 
@@ -51,6 +51,17 @@ pub enum TransactionFeeError {
 
 #[derive(Debug, Error)]
 pub enum TransactionExecutionError {
+    #[error(
+        "The compiled class hash of class {class_hash:?} is {actual:?}, but the transaction \
+         declares {expected:?}."
+    )]
+    CompiledClassHashMismatch {
+        class_hash: ClassHash,
+        expected: CompiledClassHash,
+        actual: CompiledClassHash,
+    },
+    #[error(transparent)]
+    ContractClassError(#[from] ContractClassError),
     #[error(
         "Declare transaction version {declare_version:?} must have a contract class of Cairo \
          version {cairo_version:?}."
@@ -76,12 +87,16 @@ pub enum TransactionExecutionError {
          {allowed_versions:?}."
     )]
     InvalidVersion { version: TransactionVersion, allowed_versions: Vec<TransactionVersion> },
+    #[error("{tx_type} transaction is missing its {missing_data}.")]
+    MissingTransactionData { tx_type: String, missing_data: String },
     #[error(transparent)]
     StarknetApiError(#[from] StarknetApiError),
     #[error(transparent)]
     StateError(#[from] StateError),
     #[error(transparent)]
     TransactionFeeError(#[from] TransactionFeeError),
+    #[error("Transaction hash mismatch: got {tx_hash:?}, computed {computed_tx_hash:?}.")]
+    TransactionHashMismatch { tx_hash: TransactionHash, computed_tx_hash: TransactionHash },
     #[error(transparent)]
     TransactionPreValidationError(#[from] TransactionPreValidationError),
     #[error("Unexpected holes in the {object} order. No object with the order: {order}.")]
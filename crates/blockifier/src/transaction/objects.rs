@@ -1,8 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
-use cairo_felt::Felt252;
 use itertools::concat;
-use num_traits::Pow;
 use serde::Serialize;
 use starknet_api::core::{ClassHash, ContractAddress, Nonce};
 use starknet_api::data_availability::DataAvailabilityMode;
@@ -14,13 +12,12 @@ use strum_macros::EnumIter;
 
 use crate::context::BlockContext;
 use crate::execution::call_info::CallInfo;
-use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
 use crate::fee::fee_utils::calculate_tx_fee;
 use crate::state::cached_state::StorageEntry;
-use crate::transaction::constants;
 use crate::transaction::errors::{
     TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
 };
+use crate::transaction::transaction_utils::signed_version;
 
 #[cfg(test)]
 #[path = "objects_test.rs"]
@@ -69,14 +66,7 @@ impl TransactionInfo {
     }
 
     pub fn signed_version(&self) -> TransactionVersion {
-        let version = self.version();
-        if !self.only_query() {
-            return version;
-        }
-
-        let query_version_base = Pow::pow(Felt252::from(2_u8), constants::QUERY_VERSION_BASE_BIT);
-        let query_version = query_version_base + stark_felt_to_felt(version.0);
-        TransactionVersion(felt_to_stark_felt(&query_version))
+        signed_version(self.version(), self.only_query())
     }
 
     pub fn enforce_fee(&self) -> TransactionFeeResult<bool> {
@@ -275,7 +265,7 @@ pub trait HasRelatedFeeType {
     }
 }
 
-#[derive(Clone, Copy, Hash, EnumIter, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, EnumIter, Eq, PartialEq)]
 pub enum FeeType {
     Strk,
     Eth,
@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+
+use cairo_vm::vm::runners::builtin_runner::{
+    BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+    POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME, SEGMENT_ARENA_BUILTIN_NAME,
+    SIGNATURE_BUILTIN_NAME,
+};
+use serde::Serialize;
+use starknet_api::core::{ContractAddress, EthAddress};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::transaction::{EventContent, Fee, L2ToL1Payload};
+
+use crate::abi::constants;
+use crate::execution::call_info::CallInfo;
+use crate::transaction::objects::{FeeType, GasVector, TransactionExecutionInfo};
+
+#[cfg(test)]
+#[path = "receipt_test.rs"]
+pub mod test;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExecutionStatus {
+    Succeeded,
+    Reverted,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FinalityStatus {
+    AcceptedOnL2,
+    AcceptedOnL1,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PriceUnit {
+    Wei,
+    Fri,
+}
+
+impl From<FeeType> for PriceUnit {
+    fn from(fee_type: FeeType) -> Self {
+        match fee_type {
+            FeeType::Eth => Self::Wei,
+            FeeType::Strk => Self::Fri,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct FeePayment {
+    pub amount: Fee,
+    pub unit: PriceUnit,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Event {
+    pub from_address: ContractAddress,
+    #[serde(flatten)]
+    pub content: EventContent,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct MessageToL1 {
+    pub from_address: ContractAddress,
+    pub to_address: EthAddress,
+    pub payload: L2ToL1Payload,
+}
+
+/// The resources the transaction is charged for.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ReceiptExecutionResources {
+    /// The number of Cairo steps, including memory holes.
+    pub steps: usize,
+    /// The number of applications of each used builtin, keyed as in the RPC spec; see
+    /// [rpc_builtin_key].
+    #[serde(flatten)]
+    pub builtin_applications: BTreeMap<String, usize>,
+    pub data_availability: GasVector,
+}
+
+/// A transaction receipt, in the shape of the Starknet RPC one (sans the transaction and block
+/// identifiers, which are not known to the executor).
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TransactionReceipt {
+    pub actual_fee: FeePayment,
+    pub execution_status: ExecutionStatus,
+    pub finality_status: FinalityStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// The emitted events, in emission order: those of the validation, then of the execution,
+    /// then of the fee transfer.
+    pub events: Vec<Event>,
+    /// The sent messages, in the same order as the events.
+    pub messages_sent: Vec<MessageToL1>,
+    pub execution_resources: ReceiptExecutionResources,
+    /// The address of the deployed contract; [None] for transactions that deploy no contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<ContractAddress>,
+}
+
+/// Builds the receipt of an executed transaction, paying in the given fee token; the fee token is
+/// not recorded in the execution info.
+/// The finality status of a freshly executed transaction is `AcceptedOnL2`.
+impl From<(&TransactionExecutionInfo, FeeType)> for TransactionReceipt {
+    fn from((tx_execution_info, fee_type): (&TransactionExecutionInfo, FeeType)) -> Self {
+        let mut events = Vec::new();
+        let mut messages_sent = Vec::new();
+        for call_info in tx_execution_info.non_optional_call_infos() {
+            events.extend(ordered_events(call_info));
+            messages_sent.extend(ordered_messages(call_info));
+        }
+
+        let contract_address = tx_execution_info
+            .execute_call_info
+            .as_ref()
+            .filter(|call_info| call_info.call.entry_point_type == EntryPointType::Constructor)
+            .map(|call_info| call_info.call.storage_address);
+
+        Self {
+            actual_fee: FeePayment { amount: tx_execution_info.actual_fee, unit: fee_type.into() },
+            execution_status: if tx_execution_info.is_reverted() {
+                ExecutionStatus::Reverted
+            } else {
+                ExecutionStatus::Succeeded
+            },
+            finality_status: FinalityStatus::AcceptedOnL2,
+            revert_reason: tx_execution_info.revert_error.clone(),
+            events,
+            messages_sent,
+            execution_resources: execution_resources(tx_execution_info),
+            contract_address,
+        }
+    }
+}
+
+/// Returns the events emitted in the given call tree, sorted by their emission order.
+pub(crate) fn ordered_events(call_info: &CallInfo) -> Vec<Event> {
+    let mut ordered_events: Vec<(usize, Event)> = call_info
+        .into_iter()
+        .flat_map(|call_info| {
+            call_info.execution.events.iter().map(|ordered_event| {
+                let event = Event {
+                    from_address: call_info.call.storage_address,
+                    content: ordered_event.event.clone(),
+                };
+                (ordered_event.order, event)
+            })
+        })
+        .collect();
+    ordered_events.sort_by_key(|(order, _)| *order);
+
+    ordered_events.into_iter().map(|(_, event)| event).collect()
+}
+
+/// Returns the messages sent in the given call tree, sorted by their sending order.
+fn ordered_messages(call_info: &CallInfo) -> Vec<MessageToL1> {
+    let mut ordered_messages: Vec<(usize, MessageToL1)> = call_info
+        .into_iter()
+        .flat_map(|call_info| {
+            call_info.execution.l2_to_l1_messages.iter().map(|ordered_message| {
+                let message = MessageToL1 {
+                    from_address: call_info.call.storage_address,
+                    to_address: ordered_message.message.to_address,
+                    payload: ordered_message.message.payload.clone(),
+                };
+                (ordered_message.order, message)
+            })
+        })
+        .collect();
+    ordered_messages.sort_by_key(|(order, _)| *order);
+
+    ordered_messages.into_iter().map(|(_, message)| message).collect()
+}
+
+/// Returns the key of the usage of the given builtin in the execution resources of the RPC spec;
+/// e.g., `pedersen_builtin_applications`. Builtins the spec does not count (e.g., the output
+/// builtin) have no key.
+pub(crate) fn rpc_builtin_key(builtin_name: &str) -> Option<&'static str> {
+    match builtin_name {
+        BITWISE_BUILTIN_NAME => Some("bitwise_builtin_applications"),
+        EC_OP_BUILTIN_NAME => Some("ec_op_builtin_applications"),
+        HASH_BUILTIN_NAME => Some("pedersen_builtin_applications"),
+        KECCAK_BUILTIN_NAME => Some("keccak_builtin_applications"),
+        POSEIDON_BUILTIN_NAME => Some("poseidon_builtin_applications"),
+        RANGE_CHECK_BUILTIN_NAME => Some("range_check_builtin_applications"),
+        // Unlike the other builtins, the spec names the segment arena one as is.
+        SEGMENT_ARENA_BUILTIN_NAME => Some("segment_arena_builtin"),
+        SIGNATURE_BUILTIN_NAME => Some("ecdsa_builtin_applications"),
+        _ => None,
+    }
+}
+
+pub(crate) fn execution_resources(
+    tx_execution_info: &TransactionExecutionInfo,
+) -> ReceiptExecutionResources {
+    let mut steps = 0;
+    let mut builtin_applications = BTreeMap::new();
+    for (resource, &usage) in &tx_execution_info.actual_resources.0 {
+        match resource.as_str() {
+            constants::N_STEPS_RESOURCE => steps = usage,
+            // Not execution resources; the data availability gas is reported separately.
+            constants::L1_GAS_USAGE | constants::BLOB_GAS_USAGE => {}
+            builtin_name => {
+                if let Some(key) = rpc_builtin_key(builtin_name) {
+                    builtin_applications.insert(key.to_string(), usage);
+                }
+            }
+        }
+    }
+
+    ReceiptExecutionResources {
+        steps,
+        builtin_applications,
+        data_availability: tx_execution_info.da_gas,
+    }
+}
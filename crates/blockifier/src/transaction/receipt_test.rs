@@ -0,0 +1,207 @@
+use std::collections::{HashMap, HashSet};
+
+use cairo_vm::vm::runners::builtin_runner::SEGMENT_ARENA_BUILTIN_NAME;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use serde_json::json;
+use starknet_api::core::{ContractAddress, EthAddress, PatriciaKey};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{
+    EventContent, EventData, EventKey, Fee, L2ToL1Payload, TransactionVersion,
+};
+use starknet_api::{contract_address, patricia_key, stark_felt};
+
+use crate::context::BlockContext;
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message,
+};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::invoke_tx_args;
+use crate::test_utils::{create_calldata, CairoVersion, MAX_FEE};
+use crate::transaction::objects::{FeeType, GasVector, ResourcesMapping, TransactionExecutionInfo};
+use crate::transaction::receipt::{
+    Event, ExecutionStatus, FeePayment, FinalityStatus, PriceUnit, TransactionReceipt,
+};
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, create_test_init_data, max_resource_bounds, TestInitData,
+};
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[rstest]
+#[case::deprecated(TransactionVersion::ONE, FeeType::Eth, PriceUnit::Wei)]
+#[case::current(TransactionVersion::THREE, FeeType::Strk, PriceUnit::Fri)]
+fn test_invoke_receipt(
+    block_context: BlockContext,
+    #[case] version: TransactionVersion,
+    #[case] fee_type: FeeType,
+    #[case] expected_unit: PriceUnit,
+) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let (key, data) = (stark_felt!(5_u8), stark_felt!(6_u8));
+    let n_events = 2_u8;
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        resource_bounds: max_resource_bounds(),
+        sender_address: account_address,
+        calldata: create_calldata(
+            contract_address,
+            "test_emit_events",
+            &[stark_felt!(n_events), stark_felt!(1_u8), key, stark_felt!(1_u8), data],
+        ),
+        version,
+        nonce: nonce_manager.next(account_address),
+    });
+    let tx_execution_info = tx.execute(&mut state, &block_context, true, true).unwrap();
+
+    let receipt = TransactionReceipt::from((&tx_execution_info, fee_type));
+    assert_eq!(
+        receipt.actual_fee,
+        FeePayment { amount: tx_execution_info.actual_fee, unit: expected_unit }
+    );
+    assert_eq!(receipt.execution_status, ExecutionStatus::Succeeded);
+    assert_eq!(receipt.finality_status, FinalityStatus::AcceptedOnL2);
+    assert_eq!(receipt.revert_reason, None);
+    assert_eq!(receipt.contract_address, None);
+    assert_eq!(receipt.execution_resources.steps, tx_execution_info.actual_resources.n_steps());
+    assert_eq!(receipt.execution_resources.data_availability, tx_execution_info.da_gas);
+
+    // The emitted events are followed by the `Transfer` event of the fee transfer.
+    let expected_event = Event {
+        from_address: contract_address,
+        content: EventContent { keys: vec![EventKey(key)], data: EventData(vec![data]) },
+    };
+    let (fee_transfer_event, emitted_events) = receipt.events.split_last().unwrap();
+    assert_eq!(emitted_events, vec![expected_event; n_events.into()]);
+    assert_eq!(
+        fee_transfer_event.from_address,
+        block_context.chain_info.fee_token_address(&fee_type)
+    );
+}
+
+#[rstest]
+fn test_receipt_order_and_serialization() {
+    let contract_address = contract_address!("0x100");
+    let inner_address = contract_address!("0x200");
+    let event = |order: usize, key: u8| OrderedEvent {
+        order,
+        event: EventContent { keys: vec![EventKey(stark_felt!(key))], ..Default::default() },
+    };
+    let message = |order: usize, to_address: u8| OrderedL2ToL1Message {
+        order,
+        message: MessageToL1 {
+            to_address: EthAddress::try_from(stark_felt!(to_address)).unwrap(),
+            payload: L2ToL1Payload(vec![stark_felt!(to_address)]),
+        },
+    };
+    let call_info = |storage_address: ContractAddress, execution: CallExecution| CallInfo {
+        call: CallEntryPoint {
+            entry_point_type: EntryPointType::Constructor,
+            storage_address,
+            ..Default::default()
+        },
+        execution,
+        ..Default::default()
+    };
+
+    // The inner call emits in between the events of its caller.
+    let inner_call = call_info(
+        inner_address,
+        CallExecution {
+            events: vec![event(1, 1)],
+            l2_to_l1_messages: vec![message(0, 1)],
+            ..Default::default()
+        },
+    );
+    let execute_call_info = CallInfo {
+        inner_calls: vec![inner_call],
+        ..call_info(
+            contract_address,
+            CallExecution {
+                events: vec![event(0, 0), event(2, 2)],
+                l2_to_l1_messages: vec![message(1, 2)],
+                ..Default::default()
+            },
+        )
+    };
+    let tx_execution_info = TransactionExecutionInfo {
+        execute_call_info: Some(execute_call_info),
+        actual_fee: Fee(7),
+        da_gas: GasVector { l1_gas: 0, l1_data_gas: 3 },
+        actual_resources: ResourcesMapping(HashMap::from([
+            ("n_steps".to_string(), 11),
+            ("l1_gas_usage".to_string(), 5),
+            ("l1_blob_gas_usage".to_string(), 3),
+            ("range_check_builtin".to_string(), 2),
+        ])),
+        revert_error: Some("Reverted.".to_string()),
+        ..Default::default()
+    };
+
+    let receipt = TransactionReceipt::from((&tx_execution_info, FeeType::Strk));
+    let event_sources: Vec<(ContractAddress, Vec<EventKey>)> = receipt
+        .events
+        .iter()
+        .map(|event| (event.from_address, event.content.keys.clone()))
+        .collect();
+    assert_eq!(
+        event_sources,
+        vec![
+            (contract_address, vec![EventKey(stark_felt!(0_u8))]),
+            (inner_address, vec![EventKey(stark_felt!(1_u8))]),
+            (contract_address, vec![EventKey(stark_felt!(2_u8))]),
+        ]
+    );
+    let message_sources: Vec<ContractAddress> =
+        receipt.messages_sent.iter().map(|message| message.from_address).collect();
+    assert_eq!(message_sources, vec![inner_address, contract_address]);
+    assert_eq!(receipt.contract_address, Some(contract_address));
+
+    let serialized_receipt = serde_json::to_value(&receipt).unwrap();
+    assert_eq!(serialized_receipt["actual_fee"], json!({"amount": "0x7", "unit": "FRI"}));
+    assert_eq!(serialized_receipt["execution_status"], json!("REVERTED"));
+    assert_eq!(serialized_receipt["finality_status"], json!("ACCEPTED_ON_L2"));
+    assert_eq!(serialized_receipt["revert_reason"], json!("Reverted."));
+    assert_eq!(
+        serialized_receipt["execution_resources"],
+        json!({
+            "steps": 11,
+            "range_check_builtin_applications": 2,
+            "data_availability": {"l1_gas": 0, "l1_data_gas": 3},
+        })
+    );
+    assert_eq!(serialized_receipt["events"][1]["keys"], json!([stark_felt!(1_u8)]));
+}
+
+#[rstest]
+fn test_segment_arena_receipt(block_context: BlockContext) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account_address,
+        calldata: create_calldata(contract_address, "segment_arena_builtin", &[]),
+        nonce: nonce_manager.next(account_address),
+    });
+    let tx_execution_info = tx.execute(&mut state, &block_context, true, true).unwrap();
+    let execute_call_resources = &tx_execution_info.execute_call_info.as_ref().unwrap().resources;
+    assert!(
+        execute_call_resources.builtin_instance_counter.contains_key(SEGMENT_ARENA_BUILTIN_NAME)
+    );
+
+    // The segment arena builtin is charged for in steps; only builtins of the RPC spec are keyed.
+    let receipt = TransactionReceipt::from((&tx_execution_info, FeeType::Eth));
+    let serialized_resources = serde_json::to_value(&receipt.execution_resources).unwrap();
+    let keys: HashSet<&str> =
+        serialized_resources.as_object().unwrap().keys().map(String::as_str).collect();
+    assert_eq!(
+        keys,
+        HashSet::from([
+            "steps",
+            "pedersen_builtin_applications",
+            "range_check_builtin_applications",
+            "data_availability",
+        ])
+    );
+}
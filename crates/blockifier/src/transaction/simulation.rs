@@ -0,0 +1,79 @@
+use crate::context::BlockContext;
+use crate::execution::call_info::CallInfo;
+use crate::fee::fee_estimation::{FeeEstimate, FeeEstimationError, FeeEstimationResult};
+use crate::state::cached_state::{CachedState, CommitmentStateDiff};
+use crate::state::state_api::StateReader;
+use crate::transaction::objects::{HasRelatedFeeType, TransactionInfoCreator};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[cfg(test)]
+#[path = "simulation_test.rs"]
+mod test;
+
+/// The flags of `starknet_simulateTransactions`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SimulationFlag {
+    /// Skips the `__validate__` entry point of account transactions.
+    SkipValidate,
+    /// Skips the fee checks and the fee transfer; the fee is estimated nevertheless.
+    SkipFeeCharge,
+}
+
+/// The outputs of a simulated transaction.
+#[derive(Debug)]
+pub struct TransactionSimulation {
+    /// The call tree of the `__validate__` entry point; [None] for `L1Handler` transactions, and
+    /// when validation is skipped.
+    pub validate_call_info: Option<CallInfo>,
+    /// The call tree of the executed entry point; [None] for `Declare` transactions, and for
+    /// reverted ones.
+    pub execute_call_info: Option<CallInfo>,
+    /// The call tree of the fee transfer; [None] for `L1Handler` transactions, and when fee
+    /// charge is skipped.
+    pub fee_transfer_call_info: Option<CallInfo>,
+    /// The revert reason; [None] if the transaction was not reverted.
+    pub revert_error: Option<String>,
+    /// The changes made to the state by the transaction, including the fee transfer.
+    pub state_diff: CommitmentStateDiff,
+    pub fee_estimate: FeeEstimate,
+}
+
+/// Simulates the given transactions, executed sequentially on top of the given state; each
+/// transaction observes the changes of its predecessors. The state is left unmodified.
+/// Reverted transactions are part of the simulation; transactions that fail execution fail it.
+pub fn simulate_transactions<S: StateReader>(
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+    txs: Vec<Transaction>,
+    flags: &[SimulationFlag],
+) -> FeeEstimationResult<Vec<TransactionSimulation>> {
+    let validate = !flags.contains(&SimulationFlag::SkipValidate);
+    let charge_fee = !flags.contains(&SimulationFlag::SkipFeeCharge);
+    // Accumulates the changes of the simulated transactions; discarded at the end.
+    let mut simulation_state = CachedState::create_transactional(state);
+
+    let mut simulations = Vec::with_capacity(txs.len());
+    for (tx_index, tx) in txs.into_iter().enumerate() {
+        let fee_type = tx.create_tx_info().fee_type();
+        let mut tx_state = CachedState::create_transactional(&mut simulation_state);
+        let tx_execution_info = tx
+            .execute_raw(&mut tx_state, block_context, charge_fee, validate)
+            .map_err(|error| FeeEstimationError::TransactionExecutionFailed { tx_index, error })?;
+        let state_diff = tx_state.to_state_diff();
+        tx_state.commit();
+
+        let fee_estimate = FeeEstimate::new(&tx_execution_info, fee_type, block_context)?;
+        simulations.push(TransactionSimulation {
+            validate_call_info: tx_execution_info.validate_call_info,
+            execute_call_info: tx_execution_info.execute_call_info,
+            fee_transfer_call_info: tx_execution_info.fee_transfer_call_info,
+            revert_error: tx_execution_info.revert_error,
+            state_diff,
+            fee_estimate,
+        });
+    }
+    simulation_state.abort();
+
+    Ok(simulations)
+}
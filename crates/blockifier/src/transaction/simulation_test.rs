@@ -0,0 +1,71 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::Nonce;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::Fee;
+
+use crate::context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::state_api::StateReader;
+use crate::test_utils::{create_calldata, CairoVersion, MAX_FEE};
+use crate::transaction::objects::FeeType;
+use crate::transaction::simulation::{simulate_transactions, SimulationFlag};
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, create_test_init_data, TestInitData,
+};
+use crate::transaction::transaction_execution::Transaction;
+
+#[rstest]
+#[case::no_flags(vec![])]
+#[case::skip_validate(vec![SimulationFlag::SkipValidate])]
+#[case::skip_fee_charge(vec![SimulationFlag::SkipFeeCharge])]
+#[case::skip_both(vec![SimulationFlag::SkipValidate, SimulationFlag::SkipFeeCharge])]
+fn test_simulate_transactions(block_context: BlockContext, #[case] flags: Vec<SimulationFlag>) {
+    let TestInitData { mut state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let tx = |nonce: u8, function_name: &str| -> Transaction {
+        account_invoke_tx(invoke_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            sender_address: account_address,
+            calldata: create_calldata(
+                contract_address, function_name, &[stark_felt!(1_u8), stark_felt!(7_u8)]
+            ),
+            nonce: Nonce(stark_felt!(nonce)),
+        })
+        .into()
+    };
+    let validate = !flags.contains(&SimulationFlag::SkipValidate);
+    let charge_fee = !flags.contains(&SimulationFlag::SkipFeeCharge);
+
+    // The second transaction is reverted; it is simulated nonetheless, on top of the first one.
+    let txs = vec![tx(0, "test_storage_read_write"), tx(1, "write_and_revert")];
+    let simulations = simulate_transactions(&mut state, &block_context, txs, &flags).unwrap();
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), Nonce::default());
+
+    let [successful, reverted] = simulations.as_slice() else {
+        panic!("Expected two simulations, got {}.", simulations.len());
+    };
+    let fee_token_address = block_context.chain_info.fee_token_address(&FeeType::Eth);
+    for (i, simulation) in simulations.iter().enumerate() {
+        assert_eq!(simulation.validate_call_info.is_some(), validate);
+        assert_eq!(simulation.fee_transfer_call_info.is_some(), charge_fee);
+        assert_eq!(
+            simulation.state_diff.address_to_nonce[&account_address],
+            Nonce(stark_felt!(i as u8 + 1))
+        );
+        assert_eq!(
+            simulation.state_diff.storage_updates.contains_key(&fee_token_address),
+            charge_fee
+        );
+        assert!(simulation.fee_estimate.overall_fee() > Fee(0));
+    }
+
+    assert!(successful.execute_call_info.is_some());
+    assert_eq!(successful.revert_error, None);
+    assert_eq!(successful.state_diff.storage_updates[&contract_address].len(), 1);
+
+    assert!(reverted.execute_call_info.is_none());
+    assert!(reverted.revert_error.is_some());
+    assert!(!reverted.state_diff.storage_updates.contains_key(&contract_address));
+}
@@ -1,12 +1,12 @@
 use std::collections::HashMap;
 
 use rstest::fixture;
-use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::core::{ClassHash, ContractAddress, Nonce, PatriciaKey};
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{
     Calldata, ContractAddressSalt, Fee, InvokeTransactionV0, InvokeTransactionV1,
-    InvokeTransactionV3, Resource, ResourceBounds, ResourceBoundsMapping, TransactionHash,
+    InvokeTransactionV3, Resource, ResourceBounds, ResourceBoundsMapping, Tip, TransactionHash,
     TransactionSignature, TransactionVersion,
 };
 use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
@@ -306,6 +306,45 @@ pub fn account_invoke_tx(invoke_args: InvokeTxArgs) -> AccountTransaction {
     AccountTransaction::Invoke(invoke_tx(invoke_args))
 }
 
+/// Returns an invoke transaction of the given instance of the Cairo 0 account without validations,
+/// calling the test contract, with the given nonce and max fee.
+pub fn test_contract_invoke_tx(
+    account_instance: u8,
+    nonce: u8,
+    max_fee: u128,
+) -> AccountTransaction {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract_address =
+        FeatureContract::TestContract(CairoVersion::Cairo0).get_instance_address(0);
+    account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(max_fee),
+        sender_address: account.get_instance_address(account_instance),
+        calldata: create_calldata(test_contract_address, "return_result", &[stark_felt!(2_u8)]),
+        nonce: Nonce(stark_felt!(nonce)),
+    })
+}
+
+/// Like [test_contract_invoke_tx], for a version 3 transaction with the given tip and max L1 gas
+/// price.
+pub fn test_contract_invoke_tx_v3(
+    account_instance: u8,
+    nonce: u8,
+    tip: u64,
+    max_l1_gas_price: u128,
+) -> AccountTransaction {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract_address =
+        FeatureContract::TestContract(CairoVersion::Cairo0).get_instance_address(0);
+    account_invoke_tx(invoke_tx_args! {
+        version: TransactionVersion::THREE,
+        resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, max_l1_gas_price),
+        tip: Tip(tip),
+        sender_address: account.get_instance_address(account_instance),
+        calldata: create_calldata(test_contract_address, "return_result", &[stark_felt!(2_u8)]),
+        nonce: Nonce(stark_felt!(nonce)),
+    })
+}
+
 pub fn run_invoke_tx(
     state: &mut CachedState<DictStateReader>,
     block_context: &BlockContext,
@@ -0,0 +1,371 @@
+use std::collections::BTreeMap;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use serde::Serialize;
+use starknet_api::core::{
+    ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, EthAddress, Nonce,
+};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Calldata, EventContent, L2ToL1Payload};
+
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point;
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::state::state_api::{StateReader, StateResult};
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+use crate::transaction::receipt::{
+    execution_resources, rpc_builtin_key, ReceiptExecutionResources,
+};
+use crate::transaction::transaction_types::TransactionType;
+
+#[cfg(test)]
+#[path = "trace_test.rs"]
+pub mod test;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CallType {
+    Call,
+    LibraryCall,
+}
+
+/// Library calls are executed as delegate calls.
+impl From<entry_point::CallType> for CallType {
+    fn from(call_type: entry_point::CallType) -> Self {
+        match call_type {
+            entry_point::CallType::Call => Self::Call,
+            entry_point::CallType::Delegate => Self::LibraryCall,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct OrderedEvent {
+    pub order: usize,
+    #[serde(flatten)]
+    pub content: EventContent,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct OrderedMessage {
+    pub order: usize,
+    pub from_address: ContractAddress,
+    pub to_address: EthAddress,
+    pub payload: L2ToL1Payload,
+}
+
+/// The VM resources of a call, including those of its inner calls.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ComputationResources {
+    pub steps: usize,
+    pub memory_holes: usize,
+    /// The number of applications of each used builtin, keyed as in the RPC spec.
+    #[serde(flatten)]
+    pub builtin_applications: BTreeMap<String, usize>,
+}
+
+impl From<&ExecutionResources> for ComputationResources {
+    fn from(resources: &ExecutionResources) -> Self {
+        Self {
+            steps: resources.n_steps,
+            memory_holes: resources.n_memory_holes,
+            builtin_applications: resources
+                .builtin_instance_counter
+                .iter()
+                .filter_map(|(builtin_name, &usage)| {
+                    Some((rpc_builtin_key(builtin_name)?.to_string(), usage))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A node of the call tree of a transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct FunctionInvocation {
+    pub contract_address: ContractAddress,
+    pub entry_point_selector: EntryPointSelector,
+    pub calldata: Calldata,
+    pub caller_address: ContractAddress,
+    pub class_hash: ClassHash,
+    pub entry_point_type: EntryPointType,
+    pub call_type: CallType,
+    pub result: Vec<StarkFelt>,
+    pub calls: Vec<FunctionInvocation>,
+    /// The events emitted by the call itself, i.e., not by its inner calls.
+    pub events: Vec<OrderedEvent>,
+    /// The messages sent by the call itself, i.e., not by its inner calls.
+    pub messages: Vec<OrderedMessage>,
+    pub execution_resources: ComputationResources,
+}
+
+impl From<&CallInfo> for FunctionInvocation {
+    fn from(call_info: &CallInfo) -> Self {
+        let call = &call_info.call;
+        let execution = &call_info.execution;
+
+        Self {
+            contract_address: call.storage_address,
+            entry_point_selector: call.entry_point_selector,
+            calldata: call.calldata.clone(),
+            caller_address: call.caller_address,
+            class_hash: call.class_hash.expect("Class hash must be set after execution."),
+            entry_point_type: call.entry_point_type,
+            call_type: call.call_type.into(),
+            result: execution.retdata.0.clone(),
+            calls: call_info.inner_calls.iter().map(Self::from).collect(),
+            events: execution
+                .events
+                .iter()
+                .map(|ordered_event| OrderedEvent {
+                    order: ordered_event.order,
+                    content: ordered_event.event.clone(),
+                })
+                .collect(),
+            messages: execution
+                .l2_to_l1_messages
+                .iter()
+                .map(|ordered_message| OrderedMessage {
+                    order: ordered_message.order,
+                    from_address: call.storage_address,
+                    to_address: ordered_message.message.to_address,
+                    payload: ordered_message.message.payload.clone(),
+                })
+                .collect(),
+            execution_resources: (&call_info.resources).into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RevertedInvocation {
+    pub revert_reason: String,
+}
+
+/// The execution of an `Invoke` transaction; its call tree is not reported if it was reverted.
+// Traces are built to be serialized, so the variant sizes are of no concern.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ExecuteInvocation {
+    FunctionInvocation(FunctionInvocation),
+    Reverted(RevertedInvocation),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct StorageEntry {
+    pub key: StorageKey,
+    pub value: StarkFelt,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ContractStorageDiff {
+    pub address: ContractAddress,
+    pub storage_entries: Vec<StorageEntry>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct DeclaredClass {
+    pub class_hash: ClassHash,
+    pub compiled_class_hash: CompiledClassHash,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct DeployedContract {
+    pub address: ContractAddress,
+    pub class_hash: ClassHash,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ReplacedClass {
+    pub contract_address: ContractAddress,
+    pub class_hash: ClassHash,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct NonceUpdate {
+    pub contract_address: ContractAddress,
+    pub nonce: Nonce,
+}
+
+/// The changes a transaction made to the state, in the shape of the Starknet RPC state diff.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct StateDiff {
+    pub storage_diffs: Vec<ContractStorageDiff>,
+    pub deprecated_declared_classes: Vec<ClassHash>,
+    pub declared_classes: Vec<DeclaredClass>,
+    pub deployed_contracts: Vec<DeployedContract>,
+    pub replaced_classes: Vec<ReplacedClass>,
+    pub nonces: Vec<NonceUpdate>,
+}
+
+impl StateDiff {
+    /// Converts the given state diff, given the state it was applied to; contracts that had no
+    /// class in that state were deployed, the others had their class replaced.
+    /// Note: declared Cairo 0 classes are not part of the given state diff, so
+    /// `deprecated_declared_classes` is left empty.
+    pub fn new(
+        state_diff: &CommitmentStateDiff,
+        previous_state: &impl StateReader,
+    ) -> StateResult<Self> {
+        let mut deployed_contracts = Vec::new();
+        let mut replaced_classes = Vec::new();
+        for (&address, &class_hash) in &state_diff.address_to_class_hash {
+            if previous_state.get_class_hash_at(address)? == ClassHash::default() {
+                deployed_contracts.push(DeployedContract { address, class_hash });
+            } else {
+                replaced_classes.push(ReplacedClass { contract_address: address, class_hash });
+            }
+        }
+
+        Ok(Self {
+            storage_diffs: state_diff
+                .storage_updates
+                .iter()
+                .map(|(&address, storage_updates)| ContractStorageDiff {
+                    address,
+                    storage_entries: storage_updates
+                        .iter()
+                        .map(|(&key, &value)| StorageEntry { key, value })
+                        .collect(),
+                })
+                .collect(),
+            deprecated_declared_classes: Vec::new(),
+            declared_classes: state_diff
+                .class_hash_to_compiled_class_hash
+                .iter()
+                .map(|(&class_hash, &compiled_class_hash)| DeclaredClass {
+                    class_hash,
+                    compiled_class_hash,
+                })
+                .collect(),
+            deployed_contracts,
+            replaced_classes,
+            nonces: state_diff
+                .address_to_nonce
+                .iter()
+                .map(|(&contract_address, &nonce)| NonceUpdate { contract_address, nonce })
+                .collect(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct InvokeTransactionTrace {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub execute_invocation: ExecuteInvocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<StateDiff>,
+    pub execution_resources: ReceiptExecutionResources,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct DeclareTransactionTrace {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<StateDiff>,
+    pub execution_resources: ReceiptExecutionResources,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct DeployAccountTransactionTrace {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub constructor_invocation: FunctionInvocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<StateDiff>,
+    pub execution_resources: ReceiptExecutionResources,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct L1HandlerTransactionTrace {
+    pub function_invocation: FunctionInvocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<StateDiff>,
+    pub execution_resources: ReceiptExecutionResources,
+}
+
+/// A transaction trace, in the shape of the one of `starknet_traceTransaction`.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum TransactionTrace {
+    #[serde(rename = "INVOKE")]
+    Invoke(InvokeTransactionTrace),
+    #[serde(rename = "DECLARE")]
+    Declare(DeclareTransactionTrace),
+    #[serde(rename = "DEPLOY_ACCOUNT")]
+    DeployAccount(DeployAccountTransactionTrace),
+    #[serde(rename = "L1_HANDLER")]
+    L1Handler(L1HandlerTransactionTrace),
+}
+
+impl TransactionTrace {
+    /// Builds the trace of an executed transaction of the given type; the type is not recorded in
+    /// the execution info. The state diff is that of the transaction, if known.
+    pub fn new(
+        tx_execution_info: &TransactionExecutionInfo,
+        tx_type: TransactionType,
+        state_diff: Option<StateDiff>,
+    ) -> TransactionExecutionResult<Self> {
+        let validate_invocation =
+            tx_execution_info.validate_call_info.as_ref().map(FunctionInvocation::from);
+        let fee_transfer_invocation =
+            tx_execution_info.fee_transfer_call_info.as_ref().map(FunctionInvocation::from);
+        let execution_resources = execution_resources(tx_execution_info);
+        let execute_invocation = |missing_data: &str| {
+            tx_execution_info.execute_call_info.as_ref().map(FunctionInvocation::from).ok_or_else(
+                || TransactionExecutionError::MissingTransactionData {
+                    tx_type: format!("{tx_type:?}"),
+                    missing_data: missing_data.to_string(),
+                },
+            )
+        };
+
+        Ok(match tx_type {
+            TransactionType::InvokeFunction => Self::Invoke(InvokeTransactionTrace {
+                validate_invocation,
+                execute_invocation: match &tx_execution_info.revert_error {
+                    Some(revert_reason) => ExecuteInvocation::Reverted(RevertedInvocation {
+                        revert_reason: revert_reason.clone(),
+                    }),
+                    None => ExecuteInvocation::FunctionInvocation(execute_invocation(
+                        "execute invocation",
+                    )?),
+                },
+                fee_transfer_invocation,
+                state_diff,
+                execution_resources,
+            }),
+            TransactionType::Declare => Self::Declare(DeclareTransactionTrace {
+                validate_invocation,
+                fee_transfer_invocation,
+                state_diff,
+                execution_resources,
+            }),
+            TransactionType::DeployAccount => Self::DeployAccount(DeployAccountTransactionTrace {
+                validate_invocation,
+                constructor_invocation: execute_invocation("constructor invocation")?,
+                fee_transfer_invocation,
+                state_diff,
+                execution_resources,
+            }),
+            TransactionType::L1Handler => Self::L1Handler(L1HandlerTransactionTrace {
+                function_invocation: execute_invocation("function invocation")?,
+                state_diff,
+                execution_resources,
+            }),
+        })
+    }
+}
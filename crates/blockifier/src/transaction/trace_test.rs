@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use cairo_vm::vm::runners::builtin_runner::SEGMENT_ARENA_BUILTIN_NAME;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use serde_json::json;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress, PatriciaKey};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{Calldata, EventContent, EventKey, Fee, L2ToL1Payload};
+use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::context::BlockContext;
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
+};
+use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::state::cached_state::CachedState;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::deploy_account::deploy_account_tx;
+use crate::test_utils::{create_calldata, CairoVersion, NonceManager, MAX_FEE};
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, create_test_init_data, TestInitData,
+};
+use crate::transaction::trace::{
+    ExecuteInvocation, FunctionInvocation, StateDiff, TransactionTrace,
+};
+use crate::transaction::transaction_types::TransactionType;
+use crate::transaction::transactions::ExecutableTransaction;
+use crate::{deploy_account_tx_args, invoke_tx_args, retdata};
+
+#[rstest]
+#[case::succeeded("test_storage_read_write", false)]
+#[case::reverted("write_and_revert", true)]
+fn test_invoke_trace(
+    block_context: BlockContext,
+    #[case] function_name: &str,
+    #[case] is_reverted: bool,
+) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account_address,
+        calldata: create_calldata(
+            contract_address, function_name, &[stark_felt!(1_u8), stark_felt!(7_u8)]
+        ),
+        nonce: nonce_manager.next(account_address),
+    });
+    let mut tx_state = CachedState::create_transactional(&mut state);
+    let tx_execution_info = tx.execute_raw(&mut tx_state, &block_context, true, true).unwrap();
+    let state_diff = StateDiff::new(&tx_state.to_state_diff(), &tx_state.state).unwrap();
+
+    let trace = TransactionTrace::new(
+        &tx_execution_info,
+        TransactionType::InvokeFunction,
+        Some(state_diff.clone()),
+    )
+    .unwrap();
+    let TransactionTrace::Invoke(invoke_trace) = &trace else {
+        panic!("Expected an invoke trace, got {trace:?}.")
+    };
+    assert_eq!(
+        invoke_trace.validate_invocation.as_ref().unwrap().contract_address,
+        account_address
+    );
+    assert!(invoke_trace.fee_transfer_invocation.is_some());
+    assert_eq!(state_diff.nonces[0].contract_address, account_address);
+    assert_eq!(state_diff.deployed_contracts, vec![]);
+
+    let serialized_trace = serde_json::to_value(&trace).unwrap();
+    assert_eq!(serialized_trace["type"], json!("INVOKE"));
+    if is_reverted {
+        assert_eq!(
+            serialized_trace["execute_invocation"],
+            json!({"revert_reason": tx_execution_info.revert_error.unwrap()})
+        );
+    } else {
+        let ExecuteInvocation::FunctionInvocation(execute_invocation) =
+            &invoke_trace.execute_invocation
+        else {
+            panic!("Expected a function invocation.")
+        };
+        assert_eq!(
+            *execute_invocation,
+            FunctionInvocation::from(tx_execution_info.execute_call_info.as_ref().unwrap())
+        );
+        // The account calls the contract.
+        assert_eq!(execute_invocation.calls[0].contract_address, contract_address);
+        assert_eq!(serialized_trace["execute_invocation"]["entry_point_type"], json!("EXTERNAL"));
+        assert_eq!(serialized_trace["execute_invocation"]["call_type"], json!("CALL"));
+    }
+}
+
+#[rstest]
+fn test_deploy_account_trace(block_context: BlockContext) {
+    let TestInitData { mut state, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let class_hash =
+        FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0).get_class_hash();
+    let deploy_account = deploy_account_tx(
+        deploy_account_tx_args! { max_fee: Fee(MAX_FEE), class_hash },
+        &mut NonceManager::default(),
+    );
+    let deployed_account_address = deploy_account.contract_address;
+    let tx = AccountTransaction::DeployAccount(deploy_account);
+    let mut tx_state = CachedState::create_transactional(&mut state);
+    let tx_execution_info = tx.execute_raw(&mut tx_state, &block_context, false, true).unwrap();
+    let state_diff = StateDiff::new(&tx_state.to_state_diff(), &tx_state.state).unwrap();
+
+    let trace =
+        TransactionTrace::new(&tx_execution_info, TransactionType::DeployAccount, Some(state_diff))
+            .unwrap();
+    let serialized_trace = serde_json::to_value(&trace).unwrap();
+    assert_eq!(serialized_trace["type"], json!("DEPLOY_ACCOUNT"));
+    assert_eq!(
+        serialized_trace["constructor_invocation"]["entry_point_type"],
+        json!("CONSTRUCTOR")
+    );
+    assert_eq!(
+        serialized_trace["constructor_invocation"]["contract_address"],
+        json!(deployed_account_address)
+    );
+    // The fee is not charged.
+    assert_eq!(serialized_trace.get("fee_transfer_invocation"), None);
+    assert_eq!(
+        serialized_trace["state_diff"]["deployed_contracts"],
+        json!([{"address": deployed_account_address, "class_hash": class_hash}])
+    );
+    assert_eq!(serialized_trace["state_diff"]["replaced_classes"], json!([]));
+}
+
+#[rstest]
+fn test_segment_arena_trace(block_context: BlockContext) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account_address,
+        calldata: create_calldata(contract_address, "segment_arena_builtin", &[]),
+        nonce: nonce_manager.next(account_address),
+    });
+    let tx_execution_info = tx.execute(&mut state, &block_context, true, true).unwrap();
+    let inner_call = &tx_execution_info.execute_call_info.as_ref().unwrap().inner_calls[0];
+    let segment_arena_usage =
+        inner_call.resources.builtin_instance_counter[SEGMENT_ARENA_BUILTIN_NAME];
+
+    let trace =
+        TransactionTrace::new(&tx_execution_info, TransactionType::InvokeFunction, None).unwrap();
+    let serialized_trace = serde_json::to_value(&trace).unwrap();
+    // Unlike those of the other builtins, the key of the segment arena builtin has no
+    // `_applications` suffix.
+    let serialized_resources =
+        &serialized_trace["execute_invocation"]["calls"][0]["execution_resources"];
+    assert_eq!(serialized_resources["segment_arena_builtin"], json!(segment_arena_usage));
+    assert_eq!(serialized_resources.get("segment_arena_builtin_applications"), None);
+}
+
+#[test]
+fn test_function_invocation_serialization() {
+    let inner_call = CallInfo {
+        call: CallEntryPoint {
+            class_hash: Some(class_hash!("0x20")),
+            entry_point_selector: EntryPointSelector(stark_felt!("0x21")),
+            call_type: CallType::Delegate,
+            storage_address: contract_address!("0x100"),
+            caller_address: contract_address!("0x100"),
+            ..Default::default()
+        },
+        execution: CallExecution {
+            events: vec![OrderedEvent {
+                order: 0,
+                event: EventContent {
+                    keys: vec![EventKey(stark_felt!(5_u8))],
+                    ..Default::default()
+                },
+            }],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let call_info = CallInfo {
+        call: CallEntryPoint {
+            class_hash: Some(class_hash!("0x10")),
+            entry_point_type: EntryPointType::L1Handler,
+            entry_point_selector: EntryPointSelector(stark_felt!("0x11")),
+            calldata: calldata![stark_felt!(1_u8)],
+            storage_address: contract_address!("0x100"),
+            ..Default::default()
+        },
+        execution: CallExecution {
+            retdata: retdata![stark_felt!(2_u8)],
+            l2_to_l1_messages: vec![OrderedL2ToL1Message {
+                order: 0,
+                message: MessageToL1 {
+                    to_address: EthAddress::try_from(stark_felt!(3_u8)).unwrap(),
+                    payload: L2ToL1Payload(vec![stark_felt!(4_u8)]),
+                },
+            }],
+            ..Default::default()
+        },
+        resources: ExecutionResources {
+            n_steps: 10,
+            n_memory_holes: 1,
+            builtin_instance_counter: HashMap::from([("range_check_builtin".to_string(), 2)]),
+        },
+        inner_calls: vec![inner_call],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        serde_json::to_value(FunctionInvocation::from(&call_info)).unwrap(),
+        json!({
+            "contract_address": "0x100",
+            "entry_point_selector": "0x11",
+            "calldata": ["0x1"],
+            "caller_address": "0x0",
+            "class_hash": "0x10",
+            "entry_point_type": "L1_HANDLER",
+            "call_type": "CALL",
+            "result": ["0x2"],
+            "calls": [{
+                "contract_address": "0x100",
+                "entry_point_selector": "0x21",
+                "calldata": [],
+                "caller_address": "0x100",
+                "class_hash": "0x20",
+                "entry_point_type": "EXTERNAL",
+                "call_type": "LIBRARY_CALL",
+                "result": [],
+                "calls": [],
+                "events": [{"order": 0, "keys": ["0x5"], "data": []}],
+                "messages": [],
+                "execution_resources": {"steps": 0, "memory_holes": 0},
+            }],
+            "events": [],
+            "messages": [
+                {"order": 0, "from_address": "0x100", "to_address": "0x3", "payload": ["0x4"]},
+            ],
+            "execution_resources": {
+                "steps": 10,
+                "memory_holes": 1,
+                "range_check_builtin_applications": 2,
+            },
+        })
+    );
+}
+
+#[test]
+fn test_trace_with_missing_call_info() {
+    let error = TransactionTrace::new(
+        &TransactionExecutionInfo::default(),
+        TransactionType::L1Handler,
+        None,
+    )
+    .unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::MissingTransactionData { tx_type, missing_data }
+        if tx_type == "L1Handler" && missing_data == "function invocation"
+    );
+}
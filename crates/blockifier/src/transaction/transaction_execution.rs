@@ -1,29 +1,32 @@
 use std::sync::Arc;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
-use starknet_api::core::{calculate_contract_address, ContractAddress};
+use starknet_api::core::{calculate_contract_address, ChainId, ContractAddress};
 use starknet_api::transaction::{Fee, Transaction as StarknetApiTransaction, TransactionHash};
 
 use crate::context::BlockContext;
 use crate::execution::contract_class::ClassInfo;
 use crate::execution::entry_point::EntryPointExecutionContext;
-use crate::fee::actual_cost::ActualCost;
+use crate::fee::actual_cost::{ActualCost, ActualCostBuilder};
 use crate::state::cached_state::TransactionalState;
 use crate::state::state_api::StateReader;
 use crate::transaction::account_transaction::AccountTransaction;
-use crate::transaction::errors::TransactionFeeError;
+use crate::transaction::errors::{TransactionExecutionError, TransactionFeeError};
 use crate::transaction::objects::{
     TransactionExecutionInfo, TransactionExecutionResult, TransactionInfo, TransactionInfoCreator,
 };
+use crate::transaction::transaction_hash::verify_transaction_hash;
+use crate::transaction::transaction_types::TransactionType;
 use crate::transaction::transactions::{
-    DeclareTransaction, DeployAccountTransaction, Executable, ExecutableTransaction,
-    InvokeTransaction, L1HandlerTransaction,
+    DeclareTransaction, DeployAccountTransaction, DeployTransaction, Executable,
+    ExecutableTransaction, InvokeTransaction, L1HandlerTransaction,
 };
 
 // TODO: Move into transaction.rs, makes more sense to be defined there.
-#[derive(Debug, derive_more::From)]
+#[derive(Clone, Debug, derive_more::From)]
 pub enum Transaction {
     AccountTransaction(AccountTransaction),
+    DeployTransaction(DeployTransaction),
     L1HandlerTransaction(L1HandlerTransaction),
 }
 
@@ -41,13 +44,21 @@ impl Transaction {
                 Ok(Self::L1HandlerTransaction(L1HandlerTransaction {
                     tx: l1_handler,
                     tx_hash,
-                    paid_fee_on_l1: paid_fee_on_l1
-                        .expect("L1Handler should be created with the fee paid on L1"),
+                    paid_fee_on_l1: paid_fee_on_l1.ok_or_else(|| {
+                        TransactionExecutionError::MissingTransactionData {
+                            tx_type: "L1Handler".to_string(),
+                            missing_data: "fee paid on L1".to_string(),
+                        }
+                    })?,
                 }))
             }
             StarknetApiTransaction::Declare(declare) => {
-                let non_optional_class_info =
-                    class_info.expect("Declare should be created with a ClassInfo.");
+                let non_optional_class_info = class_info.ok_or_else(|| {
+                    TransactionExecutionError::MissingTransactionData {
+                        tx_type: "Declare".to_string(),
+                        missing_data: "class info".to_string(),
+                    }
+                })?;
                 let declare_tx = match only_query {
                     true => {
                         DeclareTransaction::new_for_query(declare, tx_hash, non_optional_class_info)
@@ -56,6 +67,23 @@ impl Transaction {
                 };
                 Ok(Self::AccountTransaction(AccountTransaction::Declare(declare_tx?)))
             }
+            StarknetApiTransaction::Deploy(deploy) => {
+                let contract_address = match deployed_contract_address {
+                    Some(address) => address,
+                    None => calculate_contract_address(
+                        deploy.contract_address_salt,
+                        deploy.class_hash,
+                        &deploy.constructor_calldata,
+                        ContractAddress::default(),
+                    )?,
+                };
+                Ok(Self::DeployTransaction(DeployTransaction {
+                    tx: deploy,
+                    tx_hash,
+                    contract_address,
+                    class_info,
+                }))
+            }
             StarknetApiTransaction::DeployAccount(deploy_account) => {
                 let contract_address = match deployed_contract_address {
                     Some(address) => address,
@@ -85,20 +113,93 @@ impl Transaction {
                 };
                 Ok(Self::AccountTransaction(AccountTransaction::Invoke(invoke_tx)))
             }
-            _ => unimplemented!(),
         }
     }
+
+    /// Same as [Transaction::from_api], but first verifies that the given hash is that of the
+    /// given transaction on the given chain.
+    pub fn from_api_verified(
+        tx: StarknetApiTransaction,
+        tx_hash: TransactionHash,
+        chain_id: &ChainId,
+        class_info: Option<ClassInfo>,
+        paid_fee_on_l1: Option<Fee>,
+        deployed_contract_address: Option<ContractAddress>,
+        only_query: bool,
+    ) -> TransactionExecutionResult<Self> {
+        verify_transaction_hash(&tx, tx_hash, chain_id, only_query)?;
+        Self::from_api(
+            tx,
+            tx_hash,
+            class_info,
+            paid_fee_on_l1,
+            deployed_contract_address,
+            only_query,
+        )
+    }
 }
 
 impl TransactionInfoCreator for Transaction {
     fn create_tx_info(&self) -> TransactionInfo {
         match self {
             Self::AccountTransaction(account_tx) => account_tx.create_tx_info(),
+            Self::DeployTransaction(deploy_tx) => deploy_tx.create_tx_info(),
             Self::L1HandlerTransaction(l1_handler_tx) => l1_handler_tx.create_tx_info(),
         }
     }
 }
 
+impl<S: StateReader> ExecutableTransaction<S> for DeployTransaction {
+    fn execute_raw(
+        self,
+        state: &mut TransactionalState<'_, S>,
+        block_context: &BlockContext,
+        _charge_fee: bool,
+        _validate: bool,
+    ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        let tx_context = Arc::new(block_context.to_tx_context(&self));
+
+        let mut execution_resources = ExecutionResources::default();
+        let mut context = EntryPointExecutionContext::new_invoke(tx_context.clone(), true)?;
+        let mut remaining_gas = block_context.versioned_constants.tx_initial_gas();
+        let execute_call_info =
+            self.run_execute(state, &mut execution_resources, &mut context, &mut remaining_gas)?;
+
+        // The OS runs a deploy transaction as a deploy account one, sans validation; there is no
+        // account, so no nonce is updated. Deploy transactions have no OS resources of their own
+        // in the versioned constants; those of deploy account transactions cover the same
+        // contract address computation and deployment, as well as the validation and the nonce
+        // update, and thus bound the OS resources of a deploy transaction from above.
+        let calldata_length = self.tx.constructor_calldata.0.len();
+        let signature_length = 0;
+        let mut actual_cost_builder = ActualCostBuilder::new(
+            tx_context,
+            TransactionType::DeployAccount,
+            calldata_length,
+            signature_length,
+        )
+        .without_sender_address()
+        .with_execute_call_info(&execute_call_info);
+        // A deploy transaction that carries the class of the deployed contract publishes its code,
+        // and is charged for it like a declare transaction.
+        if let Some(class_info) = self.class_info {
+            actual_cost_builder = actual_cost_builder.with_class_info(class_info);
+        }
+        let ActualCost { da_gas, actual_resources, .. } =
+            actual_cost_builder.try_add_state_changes(state)?.build(&execution_resources)?;
+
+        Ok(TransactionExecutionInfo {
+            validate_call_info: None,
+            execute_call_info,
+            fee_transfer_call_info: None,
+            actual_fee: Fee::default(),
+            da_gas,
+            actual_resources,
+            revert_error: None,
+        })
+    }
+}
+
 impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
     fn execute_raw(
         self,
@@ -153,6 +254,9 @@ impl<S: StateReader> ExecutableTransaction<S> for Transaction {
             Self::AccountTransaction(account_tx) => {
                 account_tx.execute_raw(state, block_context, charge_fee, validate)
             }
+            Self::DeployTransaction(tx) => {
+                tx.execute_raw(state, block_context, charge_fee, validate)
+            }
             Self::L1HandlerTransaction(tx) => {
                 tx.execute_raw(state, block_context, charge_fee, validate)
             }
@@ -0,0 +1,345 @@
+use starknet_api::core::{calculate_contract_address, ChainId, ContractAddress};
+use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::hash::{pedersen_hash_array, poseidon_hash_array, StarkFelt};
+use starknet_api::transaction::{
+    DeclareTransaction, DeployAccountTransaction, DeployTransaction, Fee, InvokeTransaction,
+    L1HandlerTransaction, PaymasterData, Resource, ResourceBoundsMapping, Tip,
+    Transaction as StarknetApiTransaction, TransactionHash,
+};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::abi::constants as abi_constants;
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::TransactionExecutionResult;
+use crate::transaction::transaction_utils::signed_version;
+use crate::utils::short_string;
+
+#[cfg(test)]
+#[path = "transaction_hash_test.rs"]
+pub mod test;
+
+// The ASCII encodings of the transaction type prefixes, and of the resource names.
+pub const DECLARE_PREFIX: StarkFelt = short_string(b"declare");
+pub const DEPLOY_PREFIX: StarkFelt = short_string(b"deploy");
+pub const DEPLOY_ACCOUNT_PREFIX: StarkFelt = short_string(b"deploy_account");
+pub const INVOKE_PREFIX: StarkFelt = short_string(b"invoke");
+pub const L1_HANDLER_PREFIX: StarkFelt = short_string(b"l1_handler");
+const L1_GAS_NAME: &[u8; 6] = b"L1_GAS";
+const L2_GAS_NAME: &[u8; 6] = b"L2_GAS";
+
+/// Computes the hash of the given transaction on the given chain; `only_query` marks the hash of a
+/// query (e.g., fee estimation) transaction, whose signed version is offset by 2^128.
+/// `Deploy` and `L1Handler` transactions have no query version.
+pub fn calculate_transaction_hash(
+    tx: &StarknetApiTransaction,
+    chain_id: &ChainId,
+    only_query: bool,
+) -> TransactionExecutionResult<TransactionHash> {
+    match tx {
+        StarknetApiTransaction::Declare(tx) => calculate_declare_tx_hash(tx, chain_id, only_query),
+        StarknetApiTransaction::Deploy(tx) => calculate_deploy_tx_hash(tx, chain_id),
+        StarknetApiTransaction::DeployAccount(tx) => {
+            calculate_deploy_account_tx_hash(tx, chain_id, only_query)
+        }
+        StarknetApiTransaction::Invoke(tx) => calculate_invoke_tx_hash(tx, chain_id, only_query),
+        StarknetApiTransaction::L1Handler(tx) => calculate_l1_handler_tx_hash(tx, chain_id),
+    }
+}
+
+/// Verifies that the given hash is that of the given transaction on the given chain.
+pub fn verify_transaction_hash(
+    tx: &StarknetApiTransaction,
+    tx_hash: TransactionHash,
+    chain_id: &ChainId,
+    only_query: bool,
+) -> TransactionExecutionResult<()> {
+    check_transaction_hash(tx_hash, calculate_transaction_hash(tx, chain_id, only_query)?)
+}
+
+/// Fails if the given hash differs from the one computed for its transaction.
+pub(crate) fn check_transaction_hash(
+    tx_hash: TransactionHash,
+    computed_tx_hash: TransactionHash,
+) -> TransactionExecutionResult<()> {
+    if computed_tx_hash != tx_hash {
+        return Err(TransactionExecutionError::TransactionHashMismatch {
+            tx_hash,
+            computed_tx_hash,
+        });
+    }
+
+    Ok(())
+}
+
+pub fn calculate_declare_tx_hash(
+    tx: &DeclareTransaction,
+    chain_id: &ChainId,
+    only_query: bool,
+) -> TransactionExecutionResult<TransactionHash> {
+    let version = signed_version(tx.version(), only_query).0;
+    let chain_id = chain_id_to_felt(chain_id)?;
+    let hash = match tx {
+        DeclareTransaction::V0(tx) => pedersen_hash_array(&[
+            DECLARE_PREFIX,
+            version,
+            *tx.sender_address.0.key(),
+            StarkFelt::ZERO,
+            pedersen_hash_array(&[]),
+            fee_to_felt(tx.max_fee),
+            chain_id,
+            tx.class_hash.0,
+        ]),
+        DeclareTransaction::V1(tx) => pedersen_hash_array(&[
+            DECLARE_PREFIX,
+            version,
+            *tx.sender_address.0.key(),
+            StarkFelt::ZERO,
+            pedersen_hash_array(&[tx.class_hash.0]),
+            fee_to_felt(tx.max_fee),
+            chain_id,
+            tx.nonce.0,
+        ]),
+        DeclareTransaction::V2(tx) => pedersen_hash_array(&[
+            DECLARE_PREFIX,
+            version,
+            *tx.sender_address.0.key(),
+            StarkFelt::ZERO,
+            pedersen_hash_array(&[tx.class_hash.0]),
+            fee_to_felt(tx.max_fee),
+            chain_id,
+            tx.nonce.0,
+            tx.compiled_class_hash.0,
+        ]),
+        DeclareTransaction::V3(tx) => {
+            let mut hash_chain = common_fields_v3(
+                DECLARE_PREFIX,
+                version,
+                tx.sender_address,
+                &tx.resource_bounds,
+                tx.tip,
+                &tx.paymaster_data,
+                chain_id,
+                tx.nonce.0,
+                tx.nonce_data_availability_mode,
+                tx.fee_data_availability_mode,
+            );
+            hash_chain.extend([
+                poseidon_hash_array(&tx.account_deployment_data.0).0,
+                tx.class_hash.0,
+                tx.compiled_class_hash.0,
+            ]);
+            poseidon_hash_array(&hash_chain).0
+        }
+    };
+
+    Ok(TransactionHash(hash))
+}
+
+pub fn calculate_deploy_tx_hash(
+    tx: &DeployTransaction,
+    chain_id: &ChainId,
+) -> TransactionExecutionResult<TransactionHash> {
+    let contract_address = calculate_contract_address(
+        tx.contract_address_salt,
+        tx.class_hash,
+        &tx.constructor_calldata,
+        ContractAddress::default(),
+    )?;
+
+    Ok(TransactionHash(pedersen_hash_array(&[
+        DEPLOY_PREFIX,
+        tx.version.0,
+        *contract_address.0.key(),
+        selector_from_name(abi_constants::CONSTRUCTOR_ENTRY_POINT_NAME).0,
+        pedersen_hash_array(&tx.constructor_calldata.0),
+        StarkFelt::ZERO,
+        chain_id_to_felt(chain_id)?,
+    ])))
+}
+
+pub fn calculate_deploy_account_tx_hash(
+    tx: &DeployAccountTransaction,
+    chain_id: &ChainId,
+    only_query: bool,
+) -> TransactionExecutionResult<TransactionHash> {
+    let version = signed_version(tx.version(), only_query).0;
+    let chain_id = chain_id_to_felt(chain_id)?;
+    let contract_address = calculate_contract_address(
+        tx.contract_address_salt(),
+        tx.class_hash(),
+        &tx.constructor_calldata(),
+        ContractAddress::default(),
+    )?;
+    let hash = match tx {
+        DeployAccountTransaction::V1(tx) => {
+            let calldata_hash = pedersen_hash_array(
+                &[
+                    vec![tx.class_hash.0, tx.contract_address_salt.0],
+                    tx.constructor_calldata.0.to_vec(),
+                ]
+                .concat(),
+            );
+            pedersen_hash_array(&[
+                DEPLOY_ACCOUNT_PREFIX,
+                version,
+                *contract_address.0.key(),
+                StarkFelt::ZERO,
+                calldata_hash,
+                fee_to_felt(tx.max_fee),
+                chain_id,
+                tx.nonce.0,
+            ])
+        }
+        DeployAccountTransaction::V3(tx) => {
+            let mut hash_chain = common_fields_v3(
+                DEPLOY_ACCOUNT_PREFIX,
+                version,
+                contract_address,
+                &tx.resource_bounds,
+                tx.tip,
+                &tx.paymaster_data,
+                chain_id,
+                tx.nonce.0,
+                tx.nonce_data_availability_mode,
+                tx.fee_data_availability_mode,
+            );
+            hash_chain.extend([
+                poseidon_hash_array(&tx.constructor_calldata.0).0,
+                tx.class_hash.0,
+                tx.contract_address_salt.0,
+            ]);
+            poseidon_hash_array(&hash_chain).0
+        }
+    };
+
+    Ok(TransactionHash(hash))
+}
+
+pub fn calculate_invoke_tx_hash(
+    tx: &InvokeTransaction,
+    chain_id: &ChainId,
+    only_query: bool,
+) -> TransactionExecutionResult<TransactionHash> {
+    let version = signed_version(tx.version(), only_query).0;
+    let chain_id = chain_id_to_felt(chain_id)?;
+    let hash = match tx {
+        InvokeTransaction::V0(tx) => pedersen_hash_array(&[
+            INVOKE_PREFIX,
+            version,
+            *tx.contract_address.0.key(),
+            tx.entry_point_selector.0,
+            pedersen_hash_array(&tx.calldata.0),
+            fee_to_felt(tx.max_fee),
+            chain_id,
+        ]),
+        InvokeTransaction::V1(tx) => pedersen_hash_array(&[
+            INVOKE_PREFIX,
+            version,
+            *tx.sender_address.0.key(),
+            StarkFelt::ZERO,
+            pedersen_hash_array(&tx.calldata.0),
+            fee_to_felt(tx.max_fee),
+            chain_id,
+            tx.nonce.0,
+        ]),
+        InvokeTransaction::V3(tx) => {
+            let mut hash_chain = common_fields_v3(
+                INVOKE_PREFIX,
+                version,
+                tx.sender_address,
+                &tx.resource_bounds,
+                tx.tip,
+                &tx.paymaster_data,
+                chain_id,
+                tx.nonce.0,
+                tx.nonce_data_availability_mode,
+                tx.fee_data_availability_mode,
+            );
+            hash_chain.extend([
+                poseidon_hash_array(&tx.account_deployment_data.0).0,
+                poseidon_hash_array(&tx.calldata.0).0,
+            ]);
+            poseidon_hash_array(&hash_chain).0
+        }
+    };
+
+    Ok(TransactionHash(hash))
+}
+
+pub fn calculate_l1_handler_tx_hash(
+    tx: &L1HandlerTransaction,
+    chain_id: &ChainId,
+) -> TransactionExecutionResult<TransactionHash> {
+    Ok(TransactionHash(pedersen_hash_array(&[
+        L1_HANDLER_PREFIX,
+        tx.version.0,
+        *tx.contract_address.0.key(),
+        tx.entry_point_selector.0,
+        pedersen_hash_array(&tx.calldata.0),
+        // Max fee.
+        StarkFelt::ZERO,
+        chain_id_to_felt(chain_id)?,
+        tx.nonce.0,
+    ])))
+}
+
+/// Returns the fields shared by the hashes of all V3 transactions, in order.
+#[allow(clippy::too_many_arguments)]
+fn common_fields_v3(
+    tx_prefix: StarkFelt,
+    version: StarkFelt,
+    sender_address: ContractAddress,
+    resource_bounds: &ResourceBoundsMapping,
+    tip: Tip,
+    paymaster_data: &PaymasterData,
+    chain_id: StarkFelt,
+    nonce: StarkFelt,
+    nonce_data_availability_mode: DataAvailabilityMode,
+    fee_data_availability_mode: DataAvailabilityMode,
+) -> Vec<StarkFelt> {
+    let fee_fields_hash = poseidon_hash_array(&[
+        StarkFelt::from(tip.0),
+        resource_bounds_to_felt(resource_bounds, Resource::L1Gas, L1_GAS_NAME),
+        resource_bounds_to_felt(resource_bounds, Resource::L2Gas, L2_GAS_NAME),
+    ]);
+    // The nonce data availability mode occupies the 32 bits above the fee one.
+    let data_availability_modes =
+        ((nonce_data_availability_mode as u64) << 32) + fee_data_availability_mode as u64;
+
+    vec![
+        tx_prefix,
+        version,
+        *sender_address.0.key(),
+        fee_fields_hash.0,
+        poseidon_hash_array(&paymaster_data.0).0,
+        chain_id,
+        nonce,
+        StarkFelt::from(data_availability_modes),
+    ]
+}
+
+/// Packs the bounds of the given resource (zero if missing) with its name: the name occupies the
+/// bits above 192, the max amount the 64 bits below them, and the max price per unit the lowest
+/// 128 bits.
+fn resource_bounds_to_felt(
+    resource_bounds: &ResourceBoundsMapping,
+    resource: Resource,
+    resource_name: &[u8; 6],
+) -> StarkFelt {
+    let bounds = resource_bounds.0.get(&resource).copied().unwrap_or_default();
+    let mut bytes = [0_u8; 32];
+    bytes[2..8].copy_from_slice(resource_name);
+    bytes[8..16].copy_from_slice(&bounds.max_amount.to_be_bytes());
+    bytes[16..].copy_from_slice(&bounds.max_price_per_unit.to_be_bytes());
+
+    StarkFelt::new_unchecked(bytes)
+}
+
+fn fee_to_felt(fee: Fee) -> StarkFelt {
+    StarkFelt::from(fee.0)
+}
+
+/// Returns the chain ID as a Cairo short string.
+fn chain_id_to_felt(chain_id: &ChainId) -> TransactionExecutionResult<StarkFelt> {
+    Ok(StarkFelt::try_from(chain_id.as_hex().as_str())?)
+}
@@ -0,0 +1,234 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::{
+    calculate_contract_address, ChainId, ClassHash, ContractAddress, EntryPointSelector, Nonce,
+    PatriciaKey,
+};
+use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::hash::{pedersen_hash_array, poseidon_hash_array, StarkFelt, StarkHash};
+use starknet_api::transaction::{
+    AccountDeploymentData, Calldata, ContractAddressSalt, DeployAccountTransaction,
+    DeployAccountTransactionV1, Fee, InvokeTransaction, InvokeTransactionV1, InvokeTransactionV3,
+    L1HandlerTransaction, PaymasterData, Resource, ResourceBounds, ResourceBoundsMapping, Tip,
+    Transaction as StarknetApiTransaction, TransactionHash, TransactionSignature,
+    TransactionVersion,
+};
+use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::test_utils::CHAIN_ID_NAME;
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transaction_hash::{
+    calculate_deploy_account_tx_hash, calculate_invoke_tx_hash, calculate_l1_handler_tx_hash,
+    calculate_transaction_hash, verify_transaction_hash, DEPLOY_ACCOUNT_PREFIX, INVOKE_PREFIX,
+    L1_HANDLER_PREFIX,
+};
+use crate::transaction::transactions;
+
+// TODO: Add transactions recorded from mainnet and sepolia, with their known hashes, to check
+// the hashes computed here against the chain: Declare V0-V3, Deploy and Invoke V0 ones, as well as
+// a query version one.
+
+// The ASCII encoding of "SN_GOERLI".
+const CHAIN_ID_FELT: &str = "0x534e5f474f45524c49";
+// 2^128 + 1.
+const QUERY_VERSION_ONE: &str = "0x100000000000000000000000000000001";
+
+fn chain_id() -> ChainId {
+    ChainId(CHAIN_ID_NAME.to_string())
+}
+
+fn invoke_v1() -> InvokeTransactionV1 {
+    InvokeTransactionV1 {
+        max_fee: Fee(7),
+        signature: TransactionSignature(vec![stark_felt!(8_u8)]),
+        nonce: Nonce(stark_felt!(9_u8)),
+        sender_address: contract_address!("0x100"),
+        calldata: calldata![stark_felt!(1_u8), stark_felt!(2_u8)],
+    }
+}
+
+#[test]
+fn test_prefixes() {
+    assert_eq!(INVOKE_PREFIX, stark_felt!("0x696e766f6b65"));
+    assert_eq!(DEPLOY_ACCOUNT_PREFIX, stark_felt!("0x6465706c6f795f6163636f756e74"));
+    assert_eq!(L1_HANDLER_PREFIX, stark_felt!("0x6c315f68616e646c6572"));
+}
+
+#[rstest]
+#[case::execute(false, "0x1")]
+#[case::query(true, QUERY_VERSION_ONE)]
+fn test_invoke_v1_tx_hash(#[case] only_query: bool, #[case] signed_version: &str) {
+    let tx = InvokeTransaction::V1(invoke_v1());
+
+    let expected_hash = pedersen_hash_array(&[
+        INVOKE_PREFIX,
+        stark_felt!(signed_version),
+        stark_felt!("0x100"),
+        StarkFelt::ZERO,
+        pedersen_hash_array(&[stark_felt!(1_u8), stark_felt!(2_u8)]),
+        stark_felt!(7_u8),
+        stark_felt!(CHAIN_ID_FELT),
+        stark_felt!(9_u8),
+    ]);
+    assert_eq!(
+        calculate_invoke_tx_hash(&tx, &chain_id(), only_query).unwrap(),
+        TransactionHash(expected_hash)
+    );
+}
+
+#[test]
+fn test_invoke_v3_tx_hash() {
+    let tx = InvokeTransaction::V3(InvokeTransactionV3 {
+        resource_bounds: ResourceBoundsMapping::try_from(vec![
+            (Resource::L1Gas, ResourceBounds { max_amount: 2, max_price_per_unit: 3 }),
+            (Resource::L2Gas, ResourceBounds { max_amount: 0, max_price_per_unit: 0 }),
+        ])
+        .unwrap(),
+        tip: Tip(4),
+        signature: TransactionSignature::default(),
+        nonce: Nonce(stark_felt!(9_u8)),
+        sender_address: contract_address!("0x100"),
+        calldata: calldata![stark_felt!(1_u8)],
+        nonce_data_availability_mode: DataAvailabilityMode::L2,
+        fee_data_availability_mode: DataAvailabilityMode::L1,
+        paymaster_data: PaymasterData(vec![stark_felt!(5_u8)]),
+        account_deployment_data: AccountDeploymentData(vec![stark_felt!(6_u8)]),
+    });
+
+    let fee_fields_hash = poseidon_hash_array(&[
+        stark_felt!(4_u8),
+        // "L1_GAS", the max amount and the max price per unit.
+        stark_felt!("0x4c315f474153000000000000000200000000000000000000000000000003"),
+        // "L2_GAS", with zero bounds.
+        stark_felt!("0x4c325f474153000000000000000000000000000000000000000000000000"),
+    ]);
+    let expected_hash = poseidon_hash_array(&[
+        INVOKE_PREFIX,
+        stark_felt!(3_u8),
+        stark_felt!("0x100"),
+        fee_fields_hash.0,
+        poseidon_hash_array(&[stark_felt!(5_u8)]).0,
+        stark_felt!(CHAIN_ID_FELT),
+        stark_felt!(9_u8),
+        stark_felt!("0x100000000"),
+        poseidon_hash_array(&[stark_felt!(6_u8)]).0,
+        poseidon_hash_array(&[stark_felt!(1_u8)]).0,
+    ]);
+    assert_eq!(
+        calculate_invoke_tx_hash(&tx, &chain_id(), false).unwrap(),
+        TransactionHash(expected_hash.0)
+    );
+}
+
+#[test]
+fn test_deploy_account_v1_tx_hash() {
+    let (class_hash, salt) = (class_hash!("0x200"), ContractAddressSalt(stark_felt!(3_u8)));
+    let constructor_calldata = calldata![stark_felt!(1_u8)];
+    let tx = DeployAccountTransaction::V1(DeployAccountTransactionV1 {
+        max_fee: Fee(7),
+        nonce: Nonce(StarkFelt::ZERO),
+        class_hash,
+        contract_address_salt: salt,
+        constructor_calldata: constructor_calldata.clone(),
+        ..Default::default()
+    });
+    let contract_address = calculate_contract_address(
+        salt,
+        class_hash,
+        &constructor_calldata,
+        ContractAddress::default(),
+    )
+    .unwrap();
+
+    let expected_hash = pedersen_hash_array(&[
+        DEPLOY_ACCOUNT_PREFIX,
+        StarkFelt::ONE,
+        *contract_address.0.key(),
+        StarkFelt::ZERO,
+        pedersen_hash_array(&[stark_felt!("0x200"), stark_felt!(3_u8), stark_felt!(1_u8)]),
+        stark_felt!(7_u8),
+        stark_felt!(CHAIN_ID_FELT),
+        StarkFelt::ZERO,
+    ]);
+    assert_eq!(
+        calculate_deploy_account_tx_hash(&tx, &chain_id(), false).unwrap(),
+        TransactionHash(expected_hash)
+    );
+}
+
+#[test]
+fn test_l1_handler_tx_hash() {
+    let tx = L1HandlerTransaction {
+        version: TransactionVersion::ZERO,
+        nonce: Nonce(stark_felt!(9_u8)),
+        contract_address: contract_address!("0x100"),
+        entry_point_selector: EntryPointSelector(stark_felt!("0x300")),
+        calldata: calldata![stark_felt!(1_u8)],
+    };
+
+    let expected_hash = pedersen_hash_array(&[
+        L1_HANDLER_PREFIX,
+        StarkFelt::ZERO,
+        stark_felt!("0x100"),
+        stark_felt!("0x300"),
+        pedersen_hash_array(&[stark_felt!(1_u8)]),
+        StarkFelt::ZERO,
+        stark_felt!(CHAIN_ID_FELT),
+        stark_felt!(9_u8),
+    ]);
+    assert_eq!(
+        calculate_l1_handler_tx_hash(&tx, &chain_id()).unwrap(),
+        TransactionHash(expected_hash)
+    );
+}
+
+#[test]
+fn test_verify_transaction_hash() {
+    let tx = StarknetApiTransaction::Invoke(InvokeTransaction::V1(invoke_v1()));
+    let tx_hash = calculate_transaction_hash(&tx, &chain_id(), false).unwrap();
+    verify_transaction_hash(&tx, tx_hash, &chain_id(), false).unwrap();
+
+    // The hash depends on the chain and on whether the transaction is a query.
+    let other_chain_id = ChainId("SN_MAIN".to_string());
+    assert_ne!(calculate_transaction_hash(&tx, &other_chain_id, false).unwrap(), tx_hash);
+    assert_ne!(calculate_transaction_hash(&tx, &chain_id(), true).unwrap(), tx_hash);
+
+    let wrong_tx_hash = TransactionHash(stark_felt!("0x1234"));
+    assert_matches!(
+        verify_transaction_hash(&tx, wrong_tx_hash, &chain_id(), false).unwrap_err(),
+        TransactionExecutionError::TransactionHashMismatch { tx_hash, computed_tx_hash }
+        if tx_hash == wrong_tx_hash && computed_tx_hash == calculate_transaction_hash(
+            &tx, &chain_id(), false
+        ).unwrap()
+    );
+
+    // Building an executable transaction verifies its hash.
+    let executable_tx =
+        Transaction::from_api_verified(tx.clone(), tx_hash, &chain_id(), None, None, None, false)
+            .unwrap();
+    assert_matches!(executable_tx, Transaction::AccountTransaction(_));
+    assert_matches!(
+        Transaction::from_api_verified(tx, wrong_tx_hash, &chain_id(), None, None, None, false),
+        Err(TransactionExecutionError::TransactionHashMismatch { .. })
+    );
+}
+
+#[rstest]
+#[case::execute(false)]
+#[case::query(true)]
+fn test_verifying_constructor(#[case] only_query: bool) {
+    let tx = InvokeTransaction::V1(invoke_v1());
+    let tx_hash = calculate_invoke_tx_hash(&tx, &chain_id(), only_query).unwrap();
+    let invoke_tx =
+        transactions::InvokeTransaction::new_verified(tx.clone(), tx_hash, &chain_id(), only_query)
+            .unwrap();
+    assert_eq!(invoke_tx.only_query, only_query);
+
+    // The hash of the execution version does not match that of the query version, and vice versa.
+    assert_matches!(
+        transactions::InvokeTransaction::new_verified(tx, tx_hash, &chain_id(), !only_query),
+        Err(TransactionExecutionError::TransactionHashMismatch { .. })
+    );
+}
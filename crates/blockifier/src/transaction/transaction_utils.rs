@@ -1,14 +1,18 @@
 use std::collections::HashMap;
 
+use cairo_felt::Felt252;
 use cairo_vm::vm::runners::builtin_runner::SEGMENT_ARENA_BUILTIN_NAME;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use num_traits::Pow;
 use starknet_api::transaction::TransactionVersion;
 
 use crate::abi::constants;
 use crate::execution::call_info::CallInfo;
 use crate::execution::contract_class::ContractClass;
+use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
 use crate::fee::gas_usage::get_onchain_data_segment_length;
 use crate::state::cached_state::StateChangesCount;
+use crate::transaction::constants as tx_constants;
 use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::objects::{GasVector, ResourcesMapping, TransactionExecutionResult};
 use crate::transaction::transaction_types::TransactionType;
@@ -89,3 +93,15 @@ pub fn verify_contract_class_version(
         }
     }
 }
+
+/// Returns the version a transaction of the given version is signed with; query transactions (e.g.,
+/// for fee estimation) are signed with their version offset by 2^128, so they cannot be executed.
+pub fn signed_version(version: TransactionVersion, only_query: bool) -> TransactionVersion {
+    if !only_query {
+        return version;
+    }
+
+    let query_version_base = Pow::pow(Felt252::from(2_u8), tx_constants::QUERY_VERSION_BASE_BIT);
+    let query_version = query_version_base + stark_felt_to_felt(version.0);
+    TransactionVersion(felt_to_stark_felt(&query_version))
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
-use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::transaction::{
     AccountDeploymentData, Calldata, ContractAddressSalt, DeclareTransactionV2,
@@ -15,6 +15,7 @@ use crate::execution::contract_class::{ClassInfo, ContractClass};
 use crate::execution::entry_point::{
     CallEntryPoint, CallType, ConstructorContext, EntryPointExecutionContext,
 };
+use crate::execution::errors::ContractClassError;
 use crate::execution::execution_utils::execute_deployment;
 use crate::state::cached_state::{CachedState, TransactionalState};
 use crate::state::errors::StateError;
@@ -25,6 +26,10 @@ use crate::transaction::objects::{
     CommonAccountFields, CurrentTransactionInfo, DeprecatedTransactionInfo, HasRelatedFeeType,
     TransactionExecutionInfo, TransactionExecutionResult, TransactionInfo, TransactionInfoCreator,
 };
+use crate::transaction::transaction_hash::{
+    calculate_declare_tx_hash, calculate_deploy_account_tx_hash, calculate_invoke_tx_hash,
+    check_transaction_hash,
+};
 use crate::transaction::transaction_utils::{update_remaining_gas, verify_contract_class_version};
 
 #[cfg(test)]
@@ -103,13 +108,15 @@ pub trait ValidatableTransaction {
     ) -> TransactionExecutionResult<Option<CallInfo>>;
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct DeclareTransaction {
     pub tx: starknet_api::transaction::DeclareTransaction,
     pub tx_hash: TransactionHash,
     // Indicates the presence of the only_query bit in the version.
     only_query: bool,
     pub class_info: ClassInfo,
+    // Indicates whether the declared compiled class hash is checked against the class.
+    verify_compiled_class_hash: bool,
 }
 
 impl DeclareTransaction {
@@ -121,7 +128,13 @@ impl DeclareTransaction {
     ) -> TransactionExecutionResult<Self> {
         let declare_version = declare_tx.version();
         verify_contract_class_version(&class_info.contract_class(), declare_version)?;
-        Ok(Self { tx: declare_tx, tx_hash, class_info, only_query })
+        Ok(Self {
+            tx: declare_tx,
+            tx_hash,
+            class_info,
+            only_query,
+            verify_compiled_class_hash: false,
+        })
     }
 
     pub fn new(
@@ -140,6 +153,27 @@ impl DeclareTransaction {
         Self::create(declare_tx, tx_hash, class_info, true)
     }
 
+    /// Same as [DeclareTransaction::new] (or [DeclareTransaction::new_for_query]), but first
+    /// verifies that the given hash is that of the given transaction on the given chain.
+    pub fn new_verified(
+        declare_tx: starknet_api::transaction::DeclareTransaction,
+        tx_hash: TransactionHash,
+        class_info: ClassInfo,
+        chain_id: &ChainId,
+        only_query: bool,
+    ) -> TransactionExecutionResult<Self> {
+        let computed_tx_hash = calculate_declare_tx_hash(&declare_tx, chain_id, only_query)?;
+        check_transaction_hash(tx_hash, computed_tx_hash)?;
+        Self::create(declare_tx, tx_hash, class_info, only_query)
+    }
+
+    /// Makes the execution of the transaction fail if its compiled class hash is not that of its
+    /// class; relevant only to Cairo 1 classes, i.e., from version 2 on.
+    pub fn with_compiled_class_hash_verification(mut self) -> Self {
+        self.verify_compiled_class_hash = true;
+        self
+    }
+
     implement_inner_tx_getter_calls!((class_hash, ClassHash), (signature, TransactionSignature));
 
     pub fn tx(&self) -> &starknet_api::transaction::DeclareTransaction {
@@ -157,6 +191,27 @@ impl DeclareTransaction {
     pub fn only_query(&self) -> bool {
         self.only_query
     }
+
+    fn verify_compiled_class_hash(
+        &self,
+        declared_compiled_class_hash: CompiledClassHash,
+    ) -> TransactionExecutionResult<()> {
+        let ContractClass::V1(contract_class) = self.contract_class() else {
+            // Guaranteed by the class version check on creation.
+            unreachable!("Declare transactions of version 2 and above declare Cairo 1 classes.");
+        };
+
+        match contract_class.verify_compiled_class_hash(declared_compiled_class_hash) {
+            Err(ContractClassError::CompiledClassHashMismatch { expected, actual }) => {
+                Err(TransactionExecutionError::CompiledClassHashMismatch {
+                    class_hash: self.class_hash(),
+                    expected,
+                    actual,
+                })
+            }
+            result => Ok(result?),
+        }
+    }
 }
 
 impl<S: State> Executable<S> for DeclareTransaction {
@@ -186,6 +241,9 @@ impl<S: State> Executable<S> for DeclareTransaction {
             }) => {
                 match state.get_compiled_contract_class(class_hash) {
                     Err(StateError::UndeclaredClassHash(_)) => {
+                        if self.verify_compiled_class_hash {
+                            self.verify_compiled_class_hash(*compiled_class_hash)?;
+                        }
                         // Class is undeclared; declare it.
                         state.set_contract_class(class_hash, self.contract_class())?;
                         state.set_compiled_class_hash(class_hash, *compiled_class_hash)?;
@@ -243,6 +301,69 @@ impl TransactionInfoCreator for DeclareTransaction {
         }
     }
 }
+
+/// A legacy transaction deploying a contract without an account; it is not charged a fee.
+#[derive(Clone, Debug)]
+pub struct DeployTransaction {
+    pub tx: starknet_api::transaction::DeployTransaction,
+    pub tx_hash: TransactionHash,
+    pub contract_address: ContractAddress,
+    /// The class of the deployed contract, if the transaction declares it as well.
+    pub class_info: Option<ClassInfo>,
+}
+
+impl<S: State> Executable<S> for DeployTransaction {
+    fn run_execute(
+        &self,
+        state: &mut S,
+        resources: &mut ExecutionResources,
+        context: &mut EntryPointExecutionContext,
+        remaining_gas: &mut u64,
+    ) -> TransactionExecutionResult<Option<CallInfo>> {
+        let class_hash = self.tx.class_hash;
+        // No class commitment, so no need to check if the class is already declared.
+        if let Some(class_info) = &self.class_info {
+            state.set_contract_class(class_hash, class_info.contract_class())?;
+        }
+
+        let ctor_context = ConstructorContext {
+            class_hash,
+            code_address: None,
+            storage_address: self.contract_address,
+            caller_address: ContractAddress::default(),
+        };
+        let deployment_result = execute_deployment(
+            state,
+            resources,
+            context,
+            ctor_context,
+            self.tx.constructor_calldata.clone(),
+            *remaining_gas,
+        );
+        let call_info = deployment_result
+            .map_err(TransactionExecutionError::ContractConstructorExecutionFailed)?;
+        update_remaining_gas(remaining_gas, &call_info);
+
+        Ok(Some(call_info))
+    }
+}
+
+impl TransactionInfoCreator for DeployTransaction {
+    fn create_tx_info(&self) -> TransactionInfo {
+        TransactionInfo::Deprecated(DeprecatedTransactionInfo {
+            common_fields: CommonAccountFields {
+                transaction_hash: self.tx_hash,
+                version: self.tx.version,
+                signature: TransactionSignature::default(),
+                nonce: Nonce::default(),
+                sender_address: self.contract_address,
+                only_query: false,
+            },
+            max_fee: Fee::default(),
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DeployAccountTransaction {
     pub tx: starknet_api::transaction::DeployAccountTransaction,
@@ -269,6 +390,21 @@ impl DeployAccountTransaction {
         Self { tx: deploy_account_tx, tx_hash, contract_address, only_query: true }
     }
 
+    /// Same as [DeployAccountTransaction::new] (or [DeployAccountTransaction::new_for_query]), but
+    /// first verifies that the given hash is that of the given transaction on the given chain.
+    pub fn new_verified(
+        deploy_account_tx: starknet_api::transaction::DeployAccountTransaction,
+        tx_hash: TransactionHash,
+        contract_address: ContractAddress,
+        chain_id: &ChainId,
+        only_query: bool,
+    ) -> TransactionExecutionResult<Self> {
+        let computed_tx_hash =
+            calculate_deploy_account_tx_hash(&deploy_account_tx, chain_id, only_query)?;
+        check_transaction_hash(tx_hash, computed_tx_hash)?;
+        Ok(Self { tx: deploy_account_tx, tx_hash, contract_address, only_query })
+    }
+
     implement_inner_tx_getter_calls!(
         (class_hash, ClassHash),
         (constructor_calldata, Calldata),
@@ -368,6 +504,19 @@ impl InvokeTransaction {
         Self { tx: invoke_tx, tx_hash, only_query: true }
     }
 
+    /// Same as [InvokeTransaction::new] (or [InvokeTransaction::new_for_query]), but first
+    /// verifies that the given hash is that of the given transaction on the given chain.
+    pub fn new_verified(
+        invoke_tx: starknet_api::transaction::InvokeTransaction,
+        tx_hash: TransactionHash,
+        chain_id: &ChainId,
+        only_query: bool,
+    ) -> TransactionExecutionResult<Self> {
+        let computed_tx_hash = calculate_invoke_tx_hash(&invoke_tx, chain_id, only_query)?;
+        check_transaction_hash(tx_hash, computed_tx_hash)?;
+        Ok(Self { tx: invoke_tx, tx_hash, only_query })
+    }
+
     implement_inner_tx_getter_calls!(
         (calldata, Calldata),
         (signature, TransactionSignature),
@@ -451,7 +600,7 @@ impl TransactionInfoCreator for InvokeTransaction {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct L1HandlerTransaction {
     pub tx: starknet_api::transaction::L1HandlerTransaction,
     pub tx_hash: TransactionHash,
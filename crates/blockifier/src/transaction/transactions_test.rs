@@ -10,13 +10,16 @@ use num_traits::Pow;
 use once_cell::sync::Lazy;
 use pretty_assertions::assert_eq;
 use rstest::{fixture, rstest};
-use starknet_api::core::{ChainId, ClassHash, ContractAddress, EthAddress, Nonce, PatriciaKey};
+use starknet_api::core::{
+    calculate_contract_address, ChainId, ClassHash, CompiledClassHash, ContractAddress, EthAddress,
+    Nonce, PatriciaKey,
+};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{
-    Calldata, EventContent, EventData, EventKey, Fee, L2ToL1Payload, TransactionHash,
-    TransactionSignature, TransactionVersion,
+    Calldata, ContractAddressSalt, EventContent, EventData, EventKey, Fee, L2ToL1Payload,
+    TransactionHash, TransactionSignature, TransactionVersion,
 };
 use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
 use strum::IntoEnumIterator;
@@ -31,7 +34,10 @@ use crate::context::{BlockContext, ChainInfo, FeeTokenAddresses, TransactionCont
 use crate::execution::call_info::{
     CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
 };
-use crate::execution::contract_class::{ContractClass, ContractClassV0, ContractClassV1};
+use crate::execution::class_hash::calculate_compiled_class_hash;
+use crate::execution::contract_class::{
+    ClassInfo, ContractClass, ContractClassV0, ContractClassV1,
+};
 use crate::execution::entry_point::{CallEntryPoint, CallType};
 use crate::execution::errors::EntryPointExecutionError;
 use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
@@ -77,6 +83,7 @@ use crate::transaction::test_utils::{
     GET_BLOCK_HASH, GET_BLOCK_NUMBER, GET_BLOCK_TIMESTAMP, GET_EXECUTION_INFO,
     GET_SEQUENCER_ADDRESS, INVALID, VALID,
 };
+use crate::transaction::transaction_execution::Transaction;
 use crate::transaction::transaction_types::TransactionType;
 use crate::transaction::transactions::{ExecutableTransaction, L1HandlerTransaction};
 use crate::utils::usize_from_u128;
@@ -1217,6 +1224,62 @@ fn test_declare_tx(
     assert_eq!(contract_class_from_state, class_info.contract_class());
 }
 
+#[rstest]
+fn test_declare_tx_with_compiled_class_hash_verification(
+    #[values(TransactionVersion::TWO, TransactionVersion::THREE)] tx_version: TransactionVersion,
+) {
+    let block_context = &BlockContext::create_for_account_testing();
+    let empty_contract = FeatureContract::Empty(CairoVersion::Cairo1);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let state = &mut test_state(&block_context.chain_info, BALANCE, &[(account, 1)]);
+    let class_hash = empty_contract.get_class_hash();
+    let class_info = calculate_class_info_for_testing(empty_contract.get_class());
+    let ContractClass::V1(contract_class) = class_info.contract_class() else {
+        panic!("Expected a Cairo 1 class.")
+    };
+    let compiled_class_hash = calculate_compiled_class_hash(&contract_class).unwrap();
+    let verified_declare_tx = |compiled_class_hash| {
+        let account_tx = declare_tx(
+            declare_tx_args! {
+                max_fee: Fee(MAX_FEE),
+                sender_address: account.get_instance_address(0),
+                version: tx_version,
+                resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE),
+                class_hash,
+                compiled_class_hash,
+            },
+            class_info.clone(),
+        );
+        let AccountTransaction::Declare(tx) = account_tx else {
+            panic!("Expected a declare transaction.")
+        };
+        AccountTransaction::Declare(tx.with_compiled_class_hash_verification())
+    };
+
+    // A wrong compiled class hash is rejected.
+    let wrong_compiled_class_hash = CompiledClassHash(stark_felt!(1_u8));
+    let error = verified_declare_tx(wrong_compiled_class_hash)
+        .execute(state, block_context, true, true)
+        .unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::CompiledClassHashMismatch {
+            class_hash: error_class_hash,
+            expected,
+            actual,
+        }
+        if error_class_hash == class_hash
+            && expected == wrong_compiled_class_hash
+            && actual == compiled_class_hash
+    );
+
+    // The right one is accepted; the failed attempt is not applied to the state.
+    let execution_info =
+        verified_declare_tx(compiled_class_hash).execute(state, block_context, true, true).unwrap();
+    assert!(!execution_info.is_reverted());
+    assert_eq!(state.get_compiled_class_hash(class_hash).unwrap(), compiled_class_hash);
+}
+
 #[rstest]
 #[case(83, 3805, CairoVersion::Cairo0)]
 #[case(85, 3861, CairoVersion::Cairo1)]
@@ -1810,6 +1873,131 @@ fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
     );
 }
 
+#[rstest]
+#[case::declared_class(false)]
+#[case::implicitly_declared_class(true)]
+fn test_deploy_tx(#[case] declare_class: bool) {
+    let block_context = &BlockContext::create_for_account_testing();
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let declared_contracts: &[(FeatureContract, u8)] =
+        if declare_class { &[] } else { &[(test_contract, 0)] };
+    let state = &mut test_state(&block_context.chain_info, BALANCE, declared_contracts);
+    let class_hash = test_contract.get_class_hash();
+    let (key, value) = (stark_felt!(5_u8), stark_felt!(6_u8));
+    let constructor_calldata = calldata![key, value];
+    let deploy_tx = starknet_api::transaction::DeployTransaction {
+        version: TransactionVersion::ZERO,
+        class_hash,
+        contract_address_salt: ContractAddressSalt(stark_felt!(7_u8)),
+        constructor_calldata: constructor_calldata.clone(),
+    };
+    let contract_address = calculate_contract_address(
+        deploy_tx.contract_address_salt,
+        class_hash,
+        &constructor_calldata,
+        ContractAddress::default(),
+    )
+    .unwrap();
+    let class_info =
+        declare_class.then(|| calculate_class_info_for_testing(test_contract.get_class()));
+    let tx = Transaction::from_api(
+        starknet_api::transaction::Transaction::Deploy(deploy_tx),
+        TransactionHash::default(),
+        class_info,
+        None,
+        None,
+        false,
+    )
+    .unwrap();
+    assert_matches!(&tx, Transaction::DeployTransaction(deploy_tx)
+        if deploy_tx.contract_address == contract_address);
+
+    let execution_info = tx.execute(state, block_context, true, true).unwrap();
+
+    // The constructor runs; no validation and no fee.
+    let execute_call_info = execution_info.execute_call_info.unwrap();
+    assert_eq!(execute_call_info.call.entry_point_type, EntryPointType::Constructor);
+    assert_eq!(execute_call_info.call.storage_address, contract_address);
+    assert_eq!(execute_call_info.call.calldata, constructor_calldata);
+    assert_eq!(execution_info.validate_call_info, None);
+    assert_eq!(execution_info.fee_transfer_call_info, None);
+    assert_eq!(execution_info.actual_fee, Fee(0));
+    assert!(execution_info.actual_resources.n_steps() > 0);
+    assert_eq!(execution_info.revert_error, None);
+
+    // Check the state changes.
+    assert_eq!(state.get_class_hash_at(contract_address).unwrap(), class_hash);
+    assert_eq!(
+        state.get_storage_at(contract_address, StorageKey::try_from(key).unwrap()).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_deploy_tx_resources() {
+    let block_context = &BlockContext::create_for_account_testing();
+    let versioned_constants = &block_context.versioned_constants;
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let constructor_calldata = calldata![stark_felt!(5_u8), stark_felt!(6_u8)];
+    let execute_deploy_tx = |class_info: Option<ClassInfo>| {
+        let declared_contracts: &[(FeatureContract, u8)] =
+            if class_info.is_some() { &[] } else { &[(test_contract, 0)] };
+        let state = &mut test_state(&block_context.chain_info, BALANCE, declared_contracts);
+        let deploy_tx = starknet_api::transaction::DeployTransaction {
+            version: TransactionVersion::ZERO,
+            class_hash: test_contract.get_class_hash(),
+            contract_address_salt: ContractAddressSalt::default(),
+            constructor_calldata: constructor_calldata.clone(),
+        };
+        let tx = Transaction::from_api(
+            starknet_api::transaction::Transaction::Deploy(deploy_tx),
+            TransactionHash::default(),
+            class_info,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        tx.execute(state, block_context, true, true).unwrap()
+    };
+
+    // The OS resources of a deploy account transaction are charged, on top of the constructor
+    // run.
+    let execution_info = execute_deploy_tx(None);
+    let os_resources = versioned_constants
+        .os_resources_for_tx_type(&TransactionType::DeployAccount, constructor_calldata.0.len());
+    let constructor_resources = &execution_info.execute_call_info.as_ref().unwrap().resources;
+    assert!(
+        execution_info.actual_resources.n_steps()
+            >= os_resources.n_steps + constructor_resources.n_steps
+    );
+
+    // Declaring the class of the deployed contract adds the gas of publishing its code; the
+    // state diff is the same for a Cairo 0 class.
+    let class_info = calculate_class_info_for_testing(test_contract.get_class());
+    let code_gas = get_code_gas_cost(Some(class_info.clone()), versioned_constants);
+    let declaring_execution_info = execute_deploy_tx(Some(class_info));
+    assert_eq!(
+        declaring_execution_info.actual_resources.gas_usage(),
+        execution_info.actual_resources.gas_usage() + usize::try_from(code_gas.l1_gas).unwrap()
+    );
+}
+
+#[test]
+fn test_from_api_with_missing_data() {
+    let l1_handler_tx = l1_handler_tx(&calldata![stark_felt!(1_u8)], Fee(1)).tx;
+    let error = Transaction::from_api(
+        starknet_api::transaction::Transaction::L1Handler(l1_handler_tx),
+        TransactionHash::default(),
+        None,
+        None,
+        None,
+        false,
+    )
+    .unwrap_err();
+    assert_eq!(error.to_string(), "L1Handler transaction is missing its fee paid on L1.");
+}
+
 #[test]
 fn test_execute_tx_with_invalid_transaction_version() {
     let cairo_version = CairoVersion::Cairo0;
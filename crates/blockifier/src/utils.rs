@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use starknet_api::hash::StarkFelt;
+
 use crate::transaction::errors::NumericConversionError;
 
 #[cfg(test)]
@@ -33,3 +35,20 @@ pub fn usize_from_u128(val: u128) -> Result<usize, NumericConversionError> {
 pub fn u128_from_usize(val: usize) -> Result<u128, NumericConversionError> {
     val.try_into().map_err(|_| NumericConversionError::UsizeToU128Error(val))
 }
+
+/// Returns the given ASCII string, of at most 31 characters, as a Cairo short string.
+pub const fn short_string(string: &[u8]) -> StarkFelt {
+    assert!(string.len() < 32, "Short strings are limited to 31 characters.");
+    let mut bytes = [0_u8; 32];
+    let mut i = 0;
+    while i < string.len() {
+        bytes[32 - string.len() + i] = string[i];
+        i += 1;
+    }
+
+    StarkFelt::new_unchecked(bytes)
+}
+
+pub fn usize_to_felt(value: usize) -> StarkFelt {
+    StarkFelt::from(u64::try_from(value).expect("Conversion of usize to u64 should not fail."))
+}
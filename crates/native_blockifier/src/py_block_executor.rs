@@ -1,20 +1,25 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use blockifier::blockifier::block::{
     pre_process_block as pre_process_block_blockifier, BlockInfo, BlockNumberHashPair, GasPrices,
 };
+use blockifier::blockifier::bouncer::BouncerConfig;
 use blockifier::blockifier::transaction_executor::TransactionExecutor;
 use blockifier::context::{BlockContext, ChainInfo, FeeTokenAddresses};
+use blockifier::execution::view_call::call_contract;
 use blockifier::state::cached_state::{CachedState, GlobalContractCache};
 use blockifier::state::state_api::State;
 use blockifier::transaction::objects::TransactionExecutionInfo;
 use blockifier::transaction::transaction_execution::Transaction;
 use blockifier::versioned_constants::VersionedConstants;
+use papyrus_state_reader::papyrus_state::PapyrusReader;
 use pyo3::prelude::*;
 use serde::Serialize;
 use starknet_api::block::{BlockNumber, BlockTimestamp};
-use starknet_api::core::{ChainId, ContractAddress};
+use starknet_api::core::{ChainId, ContractAddress, EntryPointSelector};
 use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Calldata;
 
 use crate::errors::{
     InvalidNativeBlockifierInputError, NativeBlockifierError, NativeBlockifierInputError,
@@ -23,8 +28,9 @@ use crate::errors::{
 use crate::py_state_diff::{PyBlockInfo, PyStateDiff};
 use crate::py_transaction::{py_tx, PyClassInfo};
 use crate::py_transaction_execution_info::PyBouncerInfo;
-use crate::py_utils::{int_to_chain_id, py_attr, versioned_constants_with_overrides, PyFelt};
-use crate::state_readers::papyrus_state::PapyrusReader;
+use crate::py_utils::{
+    from_py_felts, int_to_chain_id, py_attr, to_py_vec, versioned_constants_with_overrides, PyFelt,
+};
 use crate::storage::{PapyrusStorage, Storage, StorageConfig};
 
 pub(crate) type RawTransactionExecutionInfo = Vec<u8>;
@@ -46,6 +52,7 @@ pub(crate) struct TypedTransactionExecutionInfo {
 
 #[pyclass]
 pub struct PyBlockExecutor {
+    pub bouncer_config: BouncerConfig,
     pub general_config: PyGeneralConfig,
     pub versioned_constants: VersionedConstants,
     pub tx_executor: Option<TransactionExecutor<PapyrusReader>>,
@@ -57,13 +64,14 @@ pub struct PyBlockExecutor {
 #[pymethods]
 impl PyBlockExecutor {
     #[new]
-    #[pyo3(signature = (general_config, validate_max_n_steps, max_recursion_depth, global_contract_cache_size, target_storage_config))]
+    #[pyo3(signature = (general_config, validate_max_n_steps, max_recursion_depth, global_contract_cache_size, target_storage_config, bouncer_config = None))]
     pub fn create(
         general_config: PyGeneralConfig,
         validate_max_n_steps: u32,
         max_recursion_depth: usize,
         global_contract_cache_size: usize,
         target_storage_config: StorageConfig,
+        bouncer_config: Option<PyBouncerConfig>,
     ) -> Self {
         log::debug!("Initializing Block Executor...");
         let storage =
@@ -73,6 +81,8 @@ impl PyBlockExecutor {
         log::debug!("Initialized Block Executor.");
 
         Self {
+            // Blocks are not limited, unless a bouncer config is given.
+            bouncer_config: bouncer_config.map_or_else(BouncerConfig::max, Into::into),
             general_config,
             versioned_constants,
             tx_executor: None,
@@ -101,7 +111,8 @@ impl PyBlockExecutor {
             &self.versioned_constants,
         )?;
 
-        let tx_executor = TransactionExecutor::new(state, block_context);
+        let tx_executor =
+            TransactionExecutor::new(state, block_context, self.bouncer_config.clone());
         self.tx_executor = Some(tx_executor);
 
         Ok(())
@@ -111,6 +122,8 @@ impl PyBlockExecutor {
         self.tx_executor = None;
     }
 
+    /// Executes the given transaction; fails with a block-full error if it does not fit into the
+    /// block, in which case the block should be closed.
     #[pyo3(signature = (tx, optional_py_class_info))]
     pub fn execute(
         &mut self,
@@ -119,8 +132,11 @@ impl PyBlockExecutor {
     ) -> NativeBlockifierResult<(RawTransactionExecutionInfo, PyBouncerInfo)> {
         let charge_fee = true;
         let tx_type: &str = tx.getattr("tx_type")?.getattr("name")?.extract()?;
-        let tx: Transaction = py_tx(tx, optional_py_class_info)?;
-        let (tx_execution_info, bouncer_info) = self.tx_executor().execute(tx, charge_fee)?;
+        // Transactions of a block are already verified by the gateway.
+        let chain_id = None;
+        let tx: Transaction = py_tx(tx, optional_py_class_info, chain_id)?;
+        let (tx_execution_info, bouncer_info, _state_accesses) =
+            self.tx_executor().execute(tx, charge_fee)?;
         let typed_tx_execution_info =
             TypedTransactionExecutionInfo { info: tx_execution_info, tx_type: tx_type.to_string() };
         let raw_tx_execution_info = serde_json::to_vec(&typed_tx_execution_info)?;
@@ -154,6 +170,27 @@ impl PyBlockExecutor {
         self.tx_executor().abort()
     }
 
+    /// Executes the given entry point as a view call, on top of the state of the block being
+    /// executed (i.e., the pending state); returns the return data of the call.
+    #[pyo3(signature = (contract_address, entry_point_selector, calldata))]
+    pub fn call_contract(
+        &mut self,
+        contract_address: PyFelt,
+        entry_point_selector: PyFelt,
+        calldata: Vec<PyFelt>,
+    ) -> NativeBlockifierResult<Vec<PyFelt>> {
+        let tx_executor = self.tx_executor();
+        let call_info = call_contract(
+            &mut tx_executor.state,
+            &tx_executor.block_context,
+            ContractAddress::try_from(contract_address.0)?,
+            EntryPointSelector(entry_point_selector.0),
+            Calldata(Arc::from(from_py_felts(calldata))),
+        )?;
+
+        Ok(to_py_vec(call_info.execution.retdata.0, PyFelt))
+    }
+
     // Storage Alignment API.
 
     /// Appends state diff and block header into Papyrus storage.
@@ -239,6 +276,7 @@ impl PyBlockExecutor {
                 path,
                 &general_config.starknet_os_config.chain_id,
             )),
+            bouncer_config: BouncerConfig::max(),
             general_config,
             versioned_constants: VersionedConstants::latest_constants().clone(),
             tx_executor: None,
@@ -263,6 +301,7 @@ impl PyBlockExecutor {
         use blockifier::state::cached_state::GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST;
         Self {
             storage: Box::new(storage),
+            bouncer_config: BouncerConfig::max(),
             general_config: PyGeneralConfig::default(),
             versioned_constants: VersionedConstants::latest_constants().clone(),
             tx_executor: None,
@@ -271,6 +310,29 @@ impl PyBlockExecutor {
     }
 }
 
+#[derive(Clone, FromPyObject)]
+pub struct PyBouncerConfig {
+    pub max_state_diff_size: usize,
+    pub max_l1_gas_amount: usize,
+    pub max_message_segment_length: usize,
+    pub max_n_steps: usize,
+    pub max_builtin_instance_counter: HashMap<String, usize>,
+    pub max_n_events: usize,
+}
+
+impl From<PyBouncerConfig> for BouncerConfig {
+    fn from(py_bouncer_config: PyBouncerConfig) -> Self {
+        Self {
+            max_state_diff_size: py_bouncer_config.max_state_diff_size,
+            max_l1_gas_amount: py_bouncer_config.max_l1_gas_amount,
+            max_message_segment_length: py_bouncer_config.max_message_segment_length,
+            max_n_steps: py_bouncer_config.max_n_steps,
+            max_builtin_instance_counter: py_bouncer_config.max_builtin_instance_counter,
+            max_n_events: py_bouncer_config.max_n_events,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct PyGeneralConfig {
     pub starknet_os_config: PyOsConfig,
@@ -3,7 +3,7 @@ use std::convert::TryFrom;
 use blockifier::transaction::transaction_types::TransactionType;
 use blockifier::transaction::transactions::DeclareTransaction;
 use pyo3::prelude::*;
-use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::transaction::{
     AccountDeploymentData, DeclareTransactionV0V1, DeclareTransactionV2, DeclareTransactionV3, Fee,
@@ -101,6 +101,7 @@ impl TryFrom<PyDeclareTransactionV3> for DeclareTransactionV3 {
 pub fn py_declare(
     py_tx: &PyAny,
     py_class_info: PyClassInfo,
+    chain_id: Option<&ChainId>,
 ) -> NativeBlockifierResult<DeclareTransaction> {
     let version = usize::try_from(py_attr::<PyFelt>(py_tx, "version")?.0)?;
     let tx = match version {
@@ -131,5 +132,11 @@ pub fn py_declare(
     }?;
     let tx_hash = TransactionHash(py_attr::<PyFelt>(py_tx, "hash_value")?.0);
     let class_info = PyClassInfo::try_from(py_class_info, &tx)?;
-    Ok(DeclareTransaction::new(tx, tx_hash, class_info)?)
+    Ok(match chain_id {
+        Some(chain_id) => {
+            let only_query = false;
+            DeclareTransaction::new_verified(tx, tx_hash, class_info, chain_id, only_query)?
+        }
+        None => DeclareTransaction::new(tx, tx_hash, class_info)?,
+    })
 }
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use blockifier::transaction::transaction_types::TransactionType;
 use blockifier::transaction::transactions::DeployAccountTransaction;
 use pyo3::prelude::*;
-use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::core::{ChainId, ClassHash, ContractAddress, Nonce};
 use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::transaction::{
     Calldata, ContractAddressSalt, DeployAccountTransactionV1, DeployAccountTransactionV3, Fee,
@@ -71,7 +71,10 @@ impl TryFrom<PyDeployAccountTransactionV3> for DeployAccountTransactionV3 {
     }
 }
 
-pub fn py_deploy_account(py_tx: &PyAny) -> NativeBlockifierResult<DeployAccountTransaction> {
+pub fn py_deploy_account(
+    py_tx: &PyAny,
+    chain_id: Option<&ChainId>,
+) -> NativeBlockifierResult<DeployAccountTransaction> {
     let version = usize::try_from(py_attr::<PyFelt>(py_tx, "version")?.0)?;
     let tx = match version {
         1 => {
@@ -93,5 +96,17 @@ pub fn py_deploy_account(py_tx: &PyAny) -> NativeBlockifierResult<DeployAccountT
     let tx_hash = TransactionHash(py_attr::<PyFelt>(py_tx, "hash_value")?.0);
     let contract_address =
         ContractAddress::try_from(py_attr::<PyFelt>(py_tx, "sender_address")?.0)?;
-    Ok(DeployAccountTransaction::new(tx, tx_hash, contract_address))
+    Ok(match chain_id {
+        Some(chain_id) => {
+            let only_query = false;
+            DeployAccountTransaction::new_verified(
+                tx,
+                tx_hash,
+                contract_address,
+                chain_id,
+                only_query,
+            )?
+        }
+        None => DeployAccountTransaction::new(tx, tx_hash, contract_address),
+    })
 }
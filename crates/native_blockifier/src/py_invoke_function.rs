@@ -4,7 +4,7 @@ use std::sync::Arc;
 use blockifier::transaction::transaction_types::TransactionType;
 use blockifier::transaction::transactions::InvokeTransaction;
 use pyo3::prelude::*;
-use starknet_api::core::{ContractAddress, EntryPointSelector, Nonce};
+use starknet_api::core::{ChainId, ContractAddress, EntryPointSelector, Nonce};
 use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::transaction::{
     AccountDeploymentData, Calldata, Fee, InvokeTransactionV0, InvokeTransactionV1,
@@ -96,7 +96,10 @@ impl TryFrom<PyInvokeTransactionV3> for InvokeTransactionV3 {
     }
 }
 
-pub fn py_invoke_function(py_tx: &PyAny) -> NativeBlockifierResult<InvokeTransaction> {
+pub fn py_invoke_function(
+    py_tx: &PyAny,
+    chain_id: Option<&ChainId>,
+) -> NativeBlockifierResult<InvokeTransaction> {
     let version = usize::try_from(py_attr::<PyFelt>(py_tx, "version")?.0)?;
     let tx = match version {
         0 => {
@@ -121,5 +124,11 @@ pub fn py_invoke_function(py_tx: &PyAny) -> NativeBlockifierResult<InvokeTransac
     }?;
 
     let tx_hash = TransactionHash(py_attr::<PyFelt>(py_tx, "hash_value")?.0);
-    Ok(InvokeTransaction::new(tx, tx_hash))
+    Ok(match chain_id {
+        Some(chain_id) => {
+            let only_query = false;
+            InvokeTransaction::new_verified(tx, tx_hash, chain_id, only_query)?
+        }
+        None => InvokeTransaction::new(tx, tx_hash),
+    })
 }
@@ -8,6 +8,7 @@ use blockifier::transaction::transaction_execution::Transaction;
 use blockifier::transaction::transaction_types::TransactionType;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use starknet_api::core::ChainId;
 use starknet_api::transaction::{Resource, ResourceBounds};
 use starknet_api::StarknetApiError;
 
@@ -110,17 +111,22 @@ impl From<PyDataAvailabilityMode> for starknet_api::data_availability::DataAvail
 pub fn py_account_tx(
     tx: &PyAny,
     optional_py_class_info: Option<PyClassInfo>,
+    chain_id: Option<&ChainId>,
 ) -> NativeBlockifierResult<AccountTransaction> {
-    let Transaction::AccountTransaction(account_tx) = py_tx(tx, optional_py_class_info)? else {
+    let Transaction::AccountTransaction(account_tx) = py_tx(tx, optional_py_class_info, chain_id)?
+    else {
         panic!("Not an account transaction.");
     };
 
     Ok(account_tx)
 }
 
+/// Converts a Python transaction; if a chain is given, the hash of an account transaction is
+/// verified to be that of the transaction on it.
 pub fn py_tx(
     tx: &PyAny,
     optional_py_class_info: Option<PyClassInfo>,
+    chain_id: Option<&ChainId>,
 ) -> NativeBlockifierResult<Transaction> {
     let tx_type: &str = tx.getattr("tx_type")?.getattr("name")?.extract()?;
     let tx_type: TransactionType =
@@ -130,13 +136,14 @@ pub fn py_tx(
         TransactionType::Declare => {
             let non_optional_py_class_info: PyClassInfo = optional_py_class_info
                 .expect("A class info must be passed in a Declare transaction.");
-            AccountTransaction::Declare(py_declare(tx, non_optional_py_class_info)?).into()
+            AccountTransaction::Declare(py_declare(tx, non_optional_py_class_info, chain_id)?)
+                .into()
         }
         TransactionType::DeployAccount => {
-            AccountTransaction::DeployAccount(py_deploy_account(tx)?).into()
+            AccountTransaction::DeployAccount(py_deploy_account(tx, chain_id)?).into()
         }
         TransactionType::InvokeFunction => {
-            AccountTransaction::Invoke(py_invoke_function(tx)?).into()
+            AccountTransaction::Invoke(py_invoke_function(tx, chain_id)?).into()
         }
         TransactionType::L1Handler => py_l1_handler(tx)?.into(),
     })
@@ -1,3 +1,4 @@
+use blockifier::blockifier::bouncer::BouncerConfig;
 use blockifier::blockifier::transaction_executor::TransactionExecutor;
 use blockifier::context::{BlockContext, TransactionContext};
 use blockifier::execution::call_info::CallInfo;
@@ -52,7 +53,7 @@ impl PyValidator {
         // TODO(Yael 24/01/24): calc block_context using pre_process_block
         let block_context =
             BlockContext::new_unchecked(&block_info, &chain_info, &versioned_constants);
-        let tx_executor = TransactionExecutor::new(state, block_context);
+        let tx_executor = TransactionExecutor::new(state, block_context, BouncerConfig::max());
 
         let validator = Self {
             max_nonce_for_validation_skip: Nonce(max_nonce_for_validation_skip.0),
@@ -71,7 +72,9 @@ impl PyValidator {
         optional_py_class_info: Option<PyClassInfo>,
         deploy_account_tx_hash: Option<PyFelt>,
     ) -> NativeBlockifierResult<()> {
-        let account_tx = py_account_tx(tx, optional_py_class_info)?;
+        // The gateway relies on the blockifier to verify the hashes of incoming transactions.
+        let chain_id = &self.tx_executor.block_context.chain_info().chain_id;
+        let account_tx = py_account_tx(tx, optional_py_class_info, Some(chain_id))?;
         let tx_context = self.tx_executor.block_context.to_tx_context(&account_tx);
         // Deploy account transactions should be fully executed, since the constructor must run
         // before `__validate_deploy__`. The execution already includes all necessary validations,
@@ -131,7 +134,7 @@ impl PyValidator {
             VersionedConstants::latest_constants(),
         );
         // TODO(Yael 24/01/24): calc block_context using pre_process_block
-        let tx_executor = TransactionExecutor::new(state, block_context);
+        let tx_executor = TransactionExecutor::new(state, block_context, BouncerConfig::max());
 
         Ok(Self { max_nonce_for_validation_skip: Nonce(StarkFelt::ONE), tx_executor })
     }
@@ -146,8 +149,10 @@ impl PyValidator {
         optional_class_info: Option<PyClassInfo>,
     ) -> NativeBlockifierResult<(TransactionExecutionInfo, PyBouncerInfo)> {
         let limit_execution_steps_by_resource_bounds = true;
-        let tx: Transaction = py_tx(tx, optional_class_info)?;
-        let (tx_execution_info, bouncer_info) =
+        // The hash was already verified on conversion in `perform_validations`.
+        let chain_id = None;
+        let tx: Transaction = py_tx(tx, optional_class_info, chain_id)?;
+        let (tx_execution_info, bouncer_info, _state_accesses) =
             self.tx_executor.execute(tx, limit_execution_steps_by_resource_bounds)?;
         let py_bouncer_info = PyBouncerInfo::from(bouncer_info);
 
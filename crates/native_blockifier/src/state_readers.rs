@@ -1,2 +1 @@
-pub mod papyrus_state;
 pub mod py_state_reader;
@@ -0,0 +1,104 @@
+//! Replays a range of blocks of a Papyrus storage, and reports any mismatch between the replayed
+//! and the stored execution outputs.
+//!
+//! Usage: replay_blocks <storage path prefix> <chain id> <first block> <last block>
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use blockifier::context::{ChainInfo, FeeTokenAddresses};
+use blockifier::state::cached_state::GlobalContractCache;
+use blockifier::versioned_constants::VersionedConstants;
+use papyrus_state_reader::block_replay::BlockReplayer;
+use papyrus_storage::db::DbConfig;
+use papyrus_storage::{open_storage, StorageConfig, StorageScope};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ChainId, ContractAddress, PatriciaKey};
+use starknet_api::hash::StarkHash;
+use starknet_api::{contract_address, patricia_key};
+
+// The fee token addresses of both Starknet mainnet and Sepolia.
+const ETH_FEE_TOKEN_ADDRESS: &str =
+    "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+const STRK_FEE_TOKEN_ADDRESS: &str =
+    "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d";
+const GLOBAL_CONTRACT_CACHE_SIZE: usize = 400;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    let [_, path_prefix, chain_id, first_block, last_block] = args.as_slice() else {
+        eprintln!(
+            "Usage: replay_blocks <storage path prefix> <chain id> <first block> <last block>"
+        );
+        return ExitCode::FAILURE;
+    };
+    let (Ok(first_block), Ok(last_block)) = (first_block.parse(), last_block.parse()) else {
+        eprintln!("Block numbers must be nonnegative integers.");
+        return ExitCode::FAILURE;
+    };
+
+    let chain_id = ChainId(chain_id.clone());
+    let storage_config = StorageConfig {
+        db_config: DbConfig {
+            path_prefix: PathBuf::from(path_prefix),
+            chain_id: chain_id.clone(),
+            enforce_file_exists: true,
+            ..Default::default()
+        },
+        scope: StorageScope::FullArchive,
+        ..Default::default()
+    };
+    let (storage_reader, _storage_writer) = match open_storage(storage_config) {
+        Ok(storage) => storage,
+        Err(error) => {
+            eprintln!("Failed to open the storage: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let chain_info = ChainInfo {
+        chain_id,
+        fee_token_addresses: FeeTokenAddresses {
+            strk_fee_token_address: contract_address!(STRK_FEE_TOKEN_ADDRESS),
+            eth_fee_token_address: contract_address!(ETH_FEE_TOKEN_ADDRESS),
+        },
+    };
+    let block_replayer = BlockReplayer::new(
+        storage_reader,
+        chain_info,
+        VersionedConstants::latest_constants().clone(),
+        GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE),
+    );
+
+    let mut is_consistent = true;
+    for block_number in (first_block..=last_block).map(BlockNumber) {
+        let report = match block_replayer.replay_block(block_number) {
+            Ok(report) => report,
+            Err(error) => {
+                eprintln!("Failed to replay block {block_number}: {error}");
+                return ExitCode::FAILURE;
+            }
+        };
+        if report.is_consistent() {
+            println!("Block {block_number}: consistent.");
+            continue;
+        }
+
+        is_consistent = false;
+        println!("Block {block_number}: inconsistent.");
+        for tx_report in report.transactions.iter().filter(|report| !report.mismatches.is_empty()) {
+            println!("  Transaction {}:", tx_report.tx_hash);
+            for mismatch in &tx_report.mismatches {
+                println!("    {mismatch:?}");
+            }
+        }
+        for mismatch in &report.unattributed_mismatches {
+            println!("  Unattributed: {mismatch:?}");
+        }
+    }
+
+    match is_consistent {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
@@ -0,0 +1,543 @@
+//! Re-execution of blocks stored in a Papyrus storage, for verifying the blockifier against the
+//! recorded chain.
+//!
+//! Each block is executed on the state right before it, with the stored header as its block
+//! info; the execution outputs of every transaction (status, fee, resources and events) and the
+//! resulting state diff of the block are compared with the stored ones.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::num::NonZeroU128;
+
+use blockifier::abi::constants::{N_STEPS_RESOURCE, STORED_BLOCK_HASH_BUFFER};
+use blockifier::blockifier::block::{pre_process_block, BlockInfo, BlockNumberHashPair, GasPrices};
+use blockifier::blockifier::bouncer::BouncerConfig;
+use blockifier::blockifier::transaction_executor::TransactionExecutor;
+use blockifier::context::ChainInfo;
+use blockifier::execution::call_info::CallInfo;
+use blockifier::execution::contract_class::ClassInfo;
+use blockifier::execution::errors::ContractClassError;
+use blockifier::state::cached_state::{CachedState, CommitmentStateDiff, GlobalContractCache};
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::StateReader;
+use blockifier::transaction::errors::TransactionExecutionError;
+use blockifier::transaction::objects::{ResourcesMapping, TransactionExecutionInfo};
+use blockifier::transaction::transaction_execution::Transaction;
+use blockifier::versioned_constants::VersionedConstants;
+use papyrus_storage::body::events::ThinTransactionOutput;
+use papyrus_storage::body::{BodyStorageReader, TransactionIndex};
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::{StorageError, StorageReader};
+use starknet_api::block::{BlockHeader, BlockNumber};
+use starknet_api::core::{ClassHash, ContractAddress};
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::{StateNumber, StorageKey, ThinStateDiff};
+use starknet_api::transaction::{
+    Builtin, Event, ExecutionResources, Fee, Transaction as StarknetApiTransaction,
+    TransactionExecutionStatus, TransactionHash, TransactionOffsetInBlock,
+};
+use thiserror::Error;
+
+use crate::papyrus_state::PapyrusReader;
+
+#[cfg(test)]
+#[path = "block_replay_test.rs"]
+mod test;
+
+#[derive(Debug, Error)]
+pub enum BlockReplayError {
+    #[error(transparent)]
+    ContractClassError(#[from] ContractClassError),
+    #[error("Block {block_number} is missing its {data} in the storage.")]
+    MissingBlockData { block_number: BlockNumber, data: &'static str },
+    #[error(transparent)]
+    StateError(#[from] StateError),
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    #[error(transparent)]
+    TransactionExecutionError(#[from] TransactionExecutionError),
+}
+
+pub type BlockReplayResult<T> = Result<T, BlockReplayError>;
+
+/// A single cell of the state, as it appears in a state diff.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum StateCell {
+    Storage(ContractAddress, StorageKey),
+    Nonce(ContractAddress),
+    ClassHash(ContractAddress),
+    CompiledClassHash(ClassHash),
+}
+
+/// A state cell whose value in the replayed state diff differs from its stored value; `None`
+/// stands for a cell missing from the respective diff.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StateDiffMismatch {
+    pub cell: StateCell,
+    pub expected: Option<StarkFelt>,
+    pub actual: Option<StarkFelt>,
+}
+
+/// The VM resources of a transaction, in the form common to the storage and the blockifier.
+/// Memory holes are not compared, as the blockifier does not report them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VmResources {
+    pub n_steps: u64,
+    /// Maps builtin names (e.g., "pedersen_builtin") to their nonzero instance counts.
+    pub builtin_instance_counter: BTreeMap<String, u64>,
+}
+
+impl From<&ResourcesMapping> for VmResources {
+    fn from(resources: &ResourcesMapping) -> Self {
+        let count = |value: &usize| u64::try_from(*value).expect("Resource count exceeds u64.");
+        Self {
+            n_steps: resources.0.get(N_STEPS_RESOURCE).map(count).unwrap_or_default(),
+            builtin_instance_counter: resources
+                .0
+                .iter()
+                .filter(|(name, value)| name.ends_with("_builtin") && **value > 0)
+                .map(|(name, value)| (name.clone(), count(value)))
+                .collect(),
+        }
+    }
+}
+
+impl From<&ExecutionResources> for VmResources {
+    fn from(resources: &ExecutionResources) -> Self {
+        Self {
+            n_steps: resources.steps,
+            builtin_instance_counter: resources
+                .builtin_instance_counter
+                .iter()
+                .filter(|(_, count)| **count > 0)
+                .map(|(builtin, count)| (builtin_name(builtin).to_string(), *count))
+                .collect(),
+        }
+    }
+}
+
+fn builtin_name(builtin: &Builtin) -> &'static str {
+    match builtin {
+        Builtin::RangeCheck => "range_check_builtin",
+        Builtin::Pedersen => "pedersen_builtin",
+        Builtin::Poseidon => "poseidon_builtin",
+        Builtin::EcOp => "ec_op_builtin",
+        Builtin::Ecdsa => "ecdsa_builtin",
+        Builtin::Bitwise => "bitwise_builtin",
+        Builtin::Keccak => "keccak_builtin",
+        Builtin::SegmentArena => "segment_arena_builtin",
+    }
+}
+
+/// A difference between the replayed execution of a transaction and its stored outputs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TransactionMismatch {
+    /// The transaction could not be executed; i.e., it would not have been included in the block.
+    ReplayFailure(String),
+    ExecutionStatus {
+        expected: TransactionExecutionStatus,
+        actual: TransactionExecutionStatus,
+    },
+    Fee {
+        expected: Fee,
+        actual: Fee,
+    },
+    Resources {
+        expected: VmResources,
+        actual: VmResources,
+    },
+    Events {
+        expected: Vec<Event>,
+        actual: Vec<Event>,
+    },
+    /// A mismatching state diff cell, last written by the transaction.
+    StateDiff(StateDiffMismatch),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionReplayReport {
+    pub tx_hash: TransactionHash,
+    pub mismatches: Vec<TransactionMismatch>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockReplayReport {
+    pub block_number: BlockNumber,
+    pub transactions: Vec<TransactionReplayReport>,
+    /// Mismatching state diff cells that were not written by any of the replayed transactions;
+    /// e.g., cells written by the stored block only.
+    pub unattributed_mismatches: Vec<StateDiffMismatch>,
+}
+
+impl BlockReplayReport {
+    pub fn is_consistent(&self) -> bool {
+        self.unattributed_mismatches.is_empty()
+            && self.transactions.iter().all(|tx_report| tx_report.mismatches.is_empty())
+    }
+}
+
+/// Replays blocks of a Papyrus storage, each on top of the stored state right before it.
+///
+/// Note: all blocks are executed with the given versioned constants; blocks of Starknet versions
+/// with other constants are expected to mismatch in fees and resources.
+pub struct BlockReplayer {
+    storage_reader: StorageReader,
+    chain_info: ChainInfo,
+    versioned_constants: VersionedConstants,
+    global_contract_cache: GlobalContractCache,
+}
+
+impl BlockReplayer {
+    pub fn new(
+        storage_reader: StorageReader,
+        chain_info: ChainInfo,
+        versioned_constants: VersionedConstants,
+        global_contract_cache: GlobalContractCache,
+    ) -> Self {
+        Self { storage_reader, chain_info, versioned_constants, global_contract_cache }
+    }
+
+    /// Re-executes the given block, and compares the outcome with the stored one.
+    pub fn replay_block(&self, block_number: BlockNumber) -> BlockReplayResult<BlockReplayReport> {
+        let txn = self.storage_reader.begin_ro_txn()?;
+        let missing = |data| BlockReplayError::MissingBlockData { block_number, data };
+        let header = txn.get_block_header(block_number)?.ok_or_else(|| missing("header"))?;
+        let txs = txn.get_block_transactions(block_number)?.ok_or_else(|| missing("body"))?;
+        let tx_hashes =
+            txn.get_block_transaction_hashes(block_number)?.ok_or_else(|| missing("body"))?;
+        let tx_outputs =
+            txn.get_block_transaction_outputs(block_number)?.ok_or_else(|| missing("body"))?;
+        let stored_state_diff =
+            txn.get_state_diff(block_number)?.ok_or_else(|| missing("state diff"))?;
+        let old_block_number_and_hash = match block_number.0.checked_sub(STORED_BLOCK_HASH_BUFFER) {
+            Some(old_block_number) => {
+                let old_header = txn.get_block_header(BlockNumber(old_block_number))?.ok_or(
+                    BlockReplayError::MissingBlockData {
+                        block_number: BlockNumber(old_block_number),
+                        data: "header",
+                    },
+                )?;
+                Some(BlockNumberHashPair::new(old_block_number, old_header.block_hash.0))
+            }
+            None => None,
+        };
+
+        let mut state = CachedState::new(
+            PapyrusReader::new(self.storage_reader.clone(), block_number),
+            self.global_contract_cache.clone(),
+        );
+        let block_context = pre_process_block(
+            &mut state,
+            old_block_number_and_hash,
+            block_info(&header),
+            self.chain_info.clone(),
+            self.versioned_constants.clone(),
+        )?;
+        // Stored blocks were closed by the sequencer; the replayed ones are not limited.
+        let mut executor = TransactionExecutor::new(state, block_context, BouncerConfig::max());
+
+        // Maps each written cell to the index of the last transaction that wrote it.
+        let mut last_writers = HashMap::<StateCell, usize>::new();
+        // Deprecated classes of the block that were not declared yet by any of its transactions;
+        // the first deploy transaction of such a class declares it.
+        let mut undeclared_classes: HashSet<ClassHash> =
+            stored_state_diff.deprecated_declared_classes.iter().copied().collect();
+        let mut tx_reports = Vec::with_capacity(txs.len());
+        for (tx_index, ((tx, tx_hash), tx_output)) in
+            txs.into_iter().zip(tx_hashes).zip(tx_outputs).enumerate()
+        {
+            let is_l1_handler = matches!(tx, StarknetApiTransaction::L1Handler(_));
+            let tx = match self.to_blockifier_tx(block_number, tx, tx_hash, &mut undeclared_classes)
+            {
+                Ok(tx) => tx,
+                // The rest of the block is still replayed; its state may diverge from the stored
+                // one, which is reported as well.
+                Err(error) => {
+                    let mismatches = vec![TransactionMismatch::ReplayFailure(error.to_string())];
+                    tx_reports.push(TransactionReplayReport { tx_hash, mismatches });
+                    continue;
+                }
+            };
+            let transaction_index =
+                TransactionIndex(block_number, TransactionOffsetInBlock(tx_index));
+            let stored_events = txn
+                .get_transaction_events(transaction_index)?
+                .ok_or_else(|| missing("transaction events"))?;
+
+            let charge_fee = true;
+            let mismatches = match executor.execute(tx, charge_fee) {
+                Ok((tx_execution_info, _bouncer_info, state_accesses)) => {
+                    executor.commit();
+                    let write_set = state_accesses.write_set;
+                    let written_cells = write_set
+                        .storage
+                        .into_keys()
+                        .map(|(address, key)| StateCell::Storage(address, key))
+                        .chain(write_set.nonces.into_keys().map(StateCell::Nonce))
+                        .chain(write_set.class_hashes.into_keys().map(StateCell::ClassHash))
+                        .chain(
+                            write_set
+                                .compiled_class_hashes
+                                .into_keys()
+                                .map(StateCell::CompiledClassHash),
+                        );
+                    last_writers.extend(written_cells.map(|cell| (cell, tx_index)));
+
+                    compare_tx_outputs(&tx_execution_info, &tx_output, stored_events, is_l1_handler)
+                }
+                Err(error) => vec![TransactionMismatch::ReplayFailure(error.to_string())],
+            };
+            tx_reports.push(TransactionReplayReport { tx_hash, mismatches });
+        }
+
+        let is_pending_block = false;
+        let (state_diff, _visited_pcs) = executor.finalize(is_pending_block);
+        let mut unattributed_mismatches = Vec::new();
+        for mismatch in diff_state_cells(stored_state_diff, state_diff) {
+            match last_writers.get(&mismatch.cell) {
+                Some(tx_index) => {
+                    tx_reports[*tx_index].mismatches.push(TransactionMismatch::StateDiff(mismatch))
+                }
+                None => unattributed_mismatches.push(mismatch),
+            }
+        }
+
+        Ok(BlockReplayReport { block_number, transactions: tx_reports, unattributed_mismatches })
+    }
+
+    fn to_blockifier_tx(
+        &self,
+        block_number: BlockNumber,
+        tx: StarknetApiTransaction,
+        tx_hash: TransactionHash,
+        undeclared_classes: &mut HashSet<ClassHash>,
+    ) -> BlockReplayResult<Transaction> {
+        let (class_info, paid_fee_on_l1) = match &tx {
+            StarknetApiTransaction::Declare(declare_tx) => {
+                let class_hash = declare_tx.class_hash();
+                undeclared_classes.remove(&class_hash);
+                (Some(self.declared_class_info(block_number, class_hash)?), None)
+            }
+            StarknetApiTransaction::Deploy(deploy_tx) => {
+                let class_info = match undeclared_classes.remove(&deploy_tx.class_hash) {
+                    true => Some(self.declared_class_info(block_number, deploy_tx.class_hash)?),
+                    false => None,
+                };
+                (class_info, None)
+            }
+            // The fee paid on L1 is not stored; any positive amount is accepted by the blockifier.
+            StarknetApiTransaction::L1Handler(_) => (None, Some(Fee(1))),
+            _ => (None, None),
+        };
+
+        let deployed_contract_address = None;
+        let only_query = false;
+        Ok(Transaction::from_api(
+            tx,
+            tx_hash,
+            class_info,
+            paid_fee_on_l1,
+            deployed_contract_address,
+            only_query,
+        )?)
+    }
+
+    /// Returns the class info of a class declared in the given block.
+    fn declared_class_info(
+        &self,
+        block_number: BlockNumber,
+        class_hash: ClassHash,
+    ) -> BlockReplayResult<ClassInfo> {
+        let state_after_block =
+            PapyrusReader::new(self.storage_reader.clone(), block_number.next());
+        let contract_class = state_after_block.get_compiled_contract_class(class_hash)?;
+        let sierra_class = self
+            .storage_reader
+            .begin_ro_txn()?
+            .get_state_reader()?
+            .get_class_definition_at(StateNumber::right_after_block(block_number), &class_hash)?;
+
+        // Deprecated classes have no Sierra program; their ABI is not charged for.
+        let (sierra_program_length, abi_length) = match sierra_class {
+            Some(sierra_class) => (sierra_class.sierra_program.len(), sierra_class.abi.len()),
+            None => (0, 0),
+        };
+        Ok(ClassInfo::new(&contract_class, sierra_program_length, abi_length)?)
+    }
+}
+
+fn block_info(header: &BlockHeader) -> BlockInfo {
+    let gas_price = |price: u128| NonZeroU128::new(price).unwrap_or(NonZeroU128::MIN);
+    BlockInfo {
+        block_number: header.block_number,
+        block_timestamp: header.timestamp,
+        sequencer_address: header.sequencer.0,
+        gas_prices: GasPrices {
+            eth_l1_gas_price: gas_price(header.l1_gas_price.price_in_wei.0),
+            strk_l1_gas_price: gas_price(header.l1_gas_price.price_in_fri.0),
+            eth_l1_data_gas_price: gas_price(header.l1_data_gas_price.price_in_wei.0),
+            strk_l1_data_gas_price: gas_price(header.l1_data_gas_price.price_in_fri.0),
+        },
+        use_kzg_da: header.l1_da_mode == L1DataAvailabilityMode::Blob,
+    }
+}
+
+fn compare_tx_outputs(
+    tx_execution_info: &TransactionExecutionInfo,
+    tx_output: &ThinTransactionOutput,
+    stored_events: Vec<Event>,
+    is_l1_handler: bool,
+) -> Vec<TransactionMismatch> {
+    let mut mismatches = Vec::new();
+
+    let actual_status = match tx_execution_info.is_reverted() {
+        true => TransactionExecutionStatus::Reverted,
+        false => TransactionExecutionStatus::Succeeded,
+    };
+    let expected_status = tx_output.execution_status().clone();
+    if actual_status != expected_status {
+        mismatches.push(TransactionMismatch::ExecutionStatus {
+            expected: expected_status,
+            actual: actual_status,
+        });
+    }
+
+    // L1 handlers are paid for on L1, and report no fee in the blockifier.
+    if !is_l1_handler && tx_execution_info.actual_fee != tx_output.actual_fee() {
+        mismatches.push(TransactionMismatch::Fee {
+            expected: tx_output.actual_fee(),
+            actual: tx_execution_info.actual_fee,
+        });
+    }
+
+    let expected_resources = VmResources::from(execution_resources(tx_output));
+    let actual_resources = VmResources::from(&tx_execution_info.actual_resources);
+    if actual_resources != expected_resources {
+        mismatches.push(TransactionMismatch::Resources {
+            expected: expected_resources,
+            actual: actual_resources,
+        });
+    }
+
+    let actual_events = emitted_events(tx_execution_info);
+    if actual_events != stored_events {
+        mismatches
+            .push(TransactionMismatch::Events { expected: stored_events, actual: actual_events });
+    }
+
+    mismatches
+}
+
+fn execution_resources(tx_output: &ThinTransactionOutput) -> &ExecutionResources {
+    match tx_output {
+        ThinTransactionOutput::Declare(tx_output) => &tx_output.execution_resources,
+        ThinTransactionOutput::Deploy(tx_output) => &tx_output.execution_resources,
+        ThinTransactionOutput::DeployAccount(tx_output) => &tx_output.execution_resources,
+        ThinTransactionOutput::Invoke(tx_output) => &tx_output.execution_resources,
+        ThinTransactionOutput::L1Handler(tx_output) => &tx_output.execution_resources,
+    }
+}
+
+/// Returns the events emitted by the transaction, in emission order: the validation events, then
+/// the execution events, then the fee transfer events.
+fn emitted_events(tx_execution_info: &TransactionExecutionInfo) -> Vec<Event> {
+    [
+        &tx_execution_info.validate_call_info,
+        &tx_execution_info.execute_call_info,
+        &tx_execution_info.fee_transfer_call_info,
+    ]
+    .into_iter()
+    .flatten()
+    .flat_map(|call_info: &CallInfo| {
+        let mut ordered_events: Vec<_> = call_info
+            .into_iter()
+            .flat_map(|call| {
+                call.execution.events.iter().map(move |ordered_event| {
+                    let event = Event {
+                        from_address: call.call.storage_address,
+                        content: ordered_event.event.clone(),
+                    };
+                    (ordered_event.order, event)
+                })
+            })
+            .collect();
+        ordered_events.sort_by_key(|(order, _)| *order);
+        ordered_events.into_iter().map(|(_, event)| event)
+    })
+    .collect()
+}
+
+fn stored_state_cells(state_diff: ThinStateDiff) -> BTreeMap<StateCell, StarkFelt> {
+    let storage_cells = state_diff.storage_diffs.into_iter().flat_map(|(address, storage)| {
+        storage.into_iter().map(move |(key, value)| (StateCell::Storage(address, key), value))
+    });
+    let nonce_cells =
+        state_diff.nonces.into_iter().map(|(address, nonce)| (StateCell::Nonce(address), nonce.0));
+    let class_hash_cells = state_diff
+        .deployed_contracts
+        .into_iter()
+        .chain(state_diff.replaced_classes)
+        .map(|(address, class_hash)| (StateCell::ClassHash(address), class_hash.0));
+    let compiled_class_hash_cells =
+        state_diff.declared_classes.into_iter().map(|(class_hash, compiled_class_hash)| {
+            (StateCell::CompiledClassHash(class_hash), compiled_class_hash.0)
+        });
+
+    storage_cells
+        .chain(nonce_cells)
+        .chain(class_hash_cells)
+        .chain(compiled_class_hash_cells)
+        .collect()
+}
+
+fn replayed_state_cells(state_diff: CommitmentStateDiff) -> BTreeMap<StateCell, StarkFelt> {
+    let storage_cells = state_diff.storage_updates.into_iter().flat_map(|(address, storage)| {
+        storage.into_iter().map(move |(key, value)| (StateCell::Storage(address, key), value))
+    });
+    let nonce_cells = state_diff
+        .address_to_nonce
+        .into_iter()
+        .map(|(address, nonce)| (StateCell::Nonce(address), nonce.0));
+    let class_hash_cells = state_diff
+        .address_to_class_hash
+        .into_iter()
+        .map(|(address, class_hash)| (StateCell::ClassHash(address), class_hash.0));
+    let compiled_class_hash_cells = state_diff.class_hash_to_compiled_class_hash.into_iter().map(
+        |(class_hash, compiled_class_hash)| {
+            (StateCell::CompiledClassHash(class_hash), compiled_class_hash.0)
+        },
+    );
+
+    storage_cells
+        .chain(nonce_cells)
+        .chain(class_hash_cells)
+        .chain(compiled_class_hash_cells)
+        .collect()
+}
+
+/// Returns the cells whose values differ between the stored and the replayed state diffs, in
+/// cell order.
+fn diff_state_cells(
+    stored_state_diff: ThinStateDiff,
+    replayed_state_diff: CommitmentStateDiff,
+) -> Vec<StateDiffMismatch> {
+    let mut expected_cells = stored_state_cells(stored_state_diff);
+    let actual_cells = replayed_state_cells(replayed_state_diff);
+
+    let mut mismatches = Vec::new();
+    for (cell, actual) in actual_cells {
+        let expected = expected_cells.remove(&cell);
+        if expected != Some(actual) {
+            mismatches.push(StateDiffMismatch { cell, expected, actual: Some(actual) });
+        }
+    }
+    mismatches.extend(expected_cells.into_iter().map(|(cell, expected)| StateDiffMismatch {
+        cell,
+        expected: Some(expected),
+        actual: None,
+    }));
+    mismatches.sort_by_key(|mismatch| mismatch.cell);
+
+    mismatches
+}
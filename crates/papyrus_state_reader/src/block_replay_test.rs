@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use blockifier::context::ChainInfo;
+use blockifier::state::cached_state::{GlobalContractCache, GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST};
+use blockifier::transaction::objects::ResourcesMapping;
+use blockifier::versioned_constants::VersionedConstants;
+use indexmap::IndexMap;
+use papyrus_storage::body::BodyStorageWriter;
+use papyrus_storage::header::HeaderStorageWriter;
+use papyrus_storage::state::StateStorageWriter;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockBody, BlockHeader, BlockNumber};
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::{StateDiff, StorageKey};
+use starknet_api::transaction::{Builtin, ExecutionResources};
+use starknet_api::{contract_address, patricia_key, stark_felt};
+
+use crate::block_replay::{
+    BlockReplayError, BlockReplayer, StateCell, StateDiffMismatch, VmResources,
+};
+
+fn block_replayer(storage_reader: papyrus_storage::StorageReader) -> BlockReplayer {
+    BlockReplayer::new(
+        storage_reader,
+        ChainInfo::create_for_testing(),
+        VersionedConstants::create_for_testing(),
+        GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
+    )
+}
+
+#[test]
+fn test_replay_empty_block() -> papyrus_storage::StorageResult<()> {
+    let ((storage_reader, mut storage_writer), _) = papyrus_storage::test_utils::get_test_storage();
+    let contract_address = contract_address!("0x1234");
+    let key = StorageKey(patricia_key!("0x10"));
+
+    // Block 0 has no transactions, yet its stored state diff holds a storage write.
+    let block_number = BlockNumber(0);
+    let state_diff = StateDiff {
+        storage_diffs: IndexMap::from([(
+            contract_address,
+            IndexMap::from([(key, stark_felt!("0x5"))]),
+        )]),
+        ..Default::default()
+    };
+    storage_writer
+        .begin_rw_txn()?
+        .append_header(block_number, &BlockHeader { block_number, ..Default::default() })?
+        .append_body(block_number, BlockBody::default())?
+        .append_state_diff(block_number, state_diff, IndexMap::new())?
+        .commit()?;
+
+    let report = block_replayer(storage_reader.clone()).replay_block(block_number).unwrap();
+    assert!(!report.is_consistent());
+    assert_eq!(report.transactions, vec![]);
+    assert_eq!(
+        report.unattributed_mismatches,
+        vec![StateDiffMismatch {
+            cell: StateCell::Storage(contract_address, key),
+            expected: Some(stark_felt!("0x5")),
+            actual: None,
+        }]
+    );
+
+    // Block 1 is not stored.
+    assert_matches!(
+        block_replayer(storage_reader).replay_block(block_number.next()),
+        Err(BlockReplayError::MissingBlockData { data: "header", .. })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_vm_resources_conversion() {
+    let stored_resources = ExecutionResources {
+        steps: 100,
+        builtin_instance_counter: HashMap::from([(Builtin::Pedersen, 2), (Builtin::Keccak, 0)]),
+        memory_holes: 3,
+    };
+    let blockifier_resources = ResourcesMapping(HashMap::from([
+        ("n_steps".to_string(), 100),
+        ("pedersen_builtin".to_string(), 2),
+        ("keccak_builtin".to_string(), 0),
+        ("l1_gas_usage".to_string(), 1000),
+    ]));
+
+    let expected_resources = VmResources {
+        n_steps: 100,
+        builtin_instance_counter: [("pedersen_builtin".to_string(), 2)].into(),
+    };
+    assert_eq!(VmResources::from(&stored_resources), expected_resources);
+    assert_eq!(VmResources::from(&blockifier_resources), expected_resources);
+}
@@ -0,0 +1,2 @@
+pub mod block_replay;
+pub mod papyrus_state;
@@ -16,16 +16,28 @@ mod test;
 
 type RawPapyrusReader<'env> = papyrus_storage::StorageTxn<'env, RO>;
 
+/// A state reader of a Papyrus storage, serving the state as it was before a given block.
 pub struct PapyrusReader {
     storage_reader: StorageReader,
     latest_block: BlockNumber,
 }
 
 impl PapyrusReader {
+    /// Returns a reader of the state right before `latest_block`; i.e., the state the block is
+    /// executed on. Any block up to the state marker of the storage is supported.
     pub fn new(storage_reader: StorageReader, latest_block: BlockNumber) -> Self {
         Self { storage_reader, latest_block }
     }
 
+    /// Returns a reader of the same storage, at the state right before the given block.
+    pub fn at_block(&self, block_number: BlockNumber) -> Self {
+        Self::new(self.storage_reader.clone(), block_number)
+    }
+
+    fn state_number(&self) -> StateNumber {
+        StateNumber::right_before_block(self.latest_block)
+    }
+
     fn reader(&self) -> StateResult<RawPapyrusReader<'_>> {
         self.storage_reader
             .begin_ro_txn()
@@ -33,14 +45,13 @@ impl PapyrusReader {
     }
 }
 
-// Currently unused - will soon replace the same `impl` for `PapyrusStateReader`.
 impl StateReader for PapyrusReader {
     fn get_storage_at(
         &self,
         contract_address: ContractAddress,
         key: StorageKey,
     ) -> StateResult<StarkFelt> {
-        let state_number = StateNumber(self.latest_block);
+        let state_number = self.state_number();
         self.reader()?
             .get_state_reader()
             .and_then(|sr| sr.get_storage_at(state_number, &contract_address, &key))
@@ -48,7 +59,7 @@ impl StateReader for PapyrusReader {
     }
 
     fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
-        let state_number = StateNumber(self.latest_block);
+        let state_number = self.state_number();
         match self
             .reader()?
             .get_state_reader()
@@ -61,7 +72,7 @@ impl StateReader for PapyrusReader {
     }
 
     fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
-        let state_number = StateNumber(self.latest_block);
+        let state_number = self.state_number();
         match self
             .reader()?
             .get_state_reader()
@@ -76,14 +87,14 @@ impl StateReader for PapyrusReader {
     /// Returns a V1 contract if found, or a V0 contract if a V1 contract is not
     /// found, or an `Error` otherwise.
     fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
-        let state_number = StateNumber(self.latest_block);
+        let state_number = self.state_number();
         let class_declaration_block_number = self
             .reader()?
             .get_state_reader()
             .and_then(|sr| sr.get_class_definition_block_number(&class_hash))
             .map_err(|err| StateError::StateReadError(err.to_string()))?;
         let class_is_declared: bool = matches!(class_declaration_block_number,
-                    Some(block_number) if block_number <= state_number.0);
+                    Some(block_number) if state_number.is_after(block_number));
 
         if class_is_declared {
             let casm_contract_class = self
@@ -112,7 +123,31 @@ impl StateReader for PapyrusReader {
         }
     }
 
-    fn get_compiled_class_hash(&self, _class_hash: ClassHash) -> StateResult<CompiledClassHash> {
-        todo!()
+    /// Returns the compiled class hash set by the state diff of the block declaring the class, or
+    /// the default value if the class is not declared (as is the case for Cairo 0 classes).
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        let state_number = self.state_number();
+        let reader = self.reader()?;
+        let class_declaration_block_number = reader
+            .get_state_reader()
+            .and_then(|sr| sr.get_class_definition_block_number(&class_hash))
+            .map_err(|err| StateError::StateReadError(err.to_string()))?;
+        let Some(block_number) = class_declaration_block_number
+            .filter(|&block_number| state_number.is_after(block_number))
+        else {
+            return Ok(CompiledClassHash::default());
+        };
+
+        let state_diff = reader
+            .get_state_diff(block_number)
+            .map_err(|err| StateError::StateReadError(err.to_string()))?;
+        let compiled_class_hash = state_diff
+            .and_then(|state_diff| state_diff.declared_classes.get(&class_hash).copied())
+            .expect(
+                "Should be able to fetch the compiled class hash of a class from the state diff \
+                 declaring it, database is inconsistent.",
+            );
+
+        Ok(compiled_class_hash)
     }
 }
@@ -10,14 +10,15 @@ use blockifier::test_utils::{
 };
 use indexmap::IndexMap;
 use papyrus_storage::state::StateStorageWriter;
+use pretty_assertions::assert_eq;
 use starknet_api::block::BlockNumber;
-use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
 use starknet_api::hash::{StarkFelt, StarkHash};
-use starknet_api::state::{StateDiff, StorageKey};
+use starknet_api::state::{ContractClass, StateDiff, StorageKey};
 use starknet_api::transaction::Calldata;
 use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
 
-use crate::state_readers::papyrus_state::PapyrusReader;
+use crate::papyrus_state::PapyrusReader;
 
 #[test]
 fn test_entry_point_with_papyrus_state() -> papyrus_storage::StorageResult<()> {
@@ -63,3 +64,62 @@ fn test_entry_point_with_papyrus_state() -> papyrus_storage::StorageResult<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_historical_reads() -> papyrus_storage::StorageResult<()> {
+    let ((storage_reader, mut storage_writer), _) = papyrus_storage::test_utils::get_test_storage();
+    let contract_address = contract_address!(TEST_CONTRACT_ADDRESS);
+    let key = StorageKey(patricia_key!("0x10"));
+    let class_hash = class_hash!("0x20");
+    let compiled_class_hash = CompiledClassHash(stark_felt!("0x21"));
+
+    // Block 0 initializes a storage cell and a nonce; block 1 updates them, and declares a class.
+    let state_diff_at_block = |value: u8, declared_classes| StateDiff {
+        storage_diffs: IndexMap::from([(
+            contract_address,
+            IndexMap::from([(key, stark_felt!(value))]),
+        )]),
+        nonces: IndexMap::from([(contract_address, Nonce(stark_felt!(value)))]),
+        declared_classes,
+        ..Default::default()
+    };
+    let state_diffs = [
+        (BlockNumber(0), state_diff_at_block(1, IndexMap::new())),
+        (
+            BlockNumber(1),
+            state_diff_at_block(
+                2,
+                IndexMap::from([(class_hash, (compiled_class_hash, ContractClass::default()))]),
+            ),
+        ),
+    ];
+    for (block_number, state_diff) in state_diffs {
+        storage_writer
+            .begin_rw_txn()?
+            .append_state_diff(block_number, state_diff, IndexMap::new())?
+            .commit()?;
+    }
+
+    let latest_reader = PapyrusReader::new(storage_reader, BlockNumber(2));
+    for (block_number, expected_value, expected_compiled_class_hash) in [
+        (0, 0_u8, CompiledClassHash::default()),
+        (1, 1_u8, CompiledClassHash::default()),
+        (2, 2_u8, compiled_class_hash),
+    ] {
+        let papyrus_reader = latest_reader.at_block(BlockNumber(block_number));
+        assert_eq!(
+            papyrus_reader.get_storage_at(contract_address, key).unwrap(),
+            stark_felt!(expected_value)
+        );
+        assert_eq!(
+            papyrus_reader.get_nonce_at(contract_address).unwrap(),
+            Nonce(stark_felt!(expected_value))
+        );
+        assert_eq!(
+            papyrus_reader.get_compiled_class_hash(class_hash).unwrap(),
+            expected_compiled_class_hash
+        );
+    }
+
+    Ok(())
+}